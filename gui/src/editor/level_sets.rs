@@ -376,30 +376,29 @@ impl LevelSetsTab<'_> {
             };
 
             SidePanel::left("area_list_panel").show_inside(ui, |ui| {
-                if ui.button("Reset Selected").clicked() {
-                    if let Some(idx) = data.selected_area {
-                        let area = &areas[idx];
-
-                        data.area_modal = Some(Modal::new(
-                            "area_modal",
-                            RichText::new(format!("Reset {}?", area.def.sid())),
-                            RichText::new(format!(
-                                "Are you sure you want to reset stats for {}? This is \
-                                 irreversable.",
-                                area.def.sid()
-                            )),
-                            move |ui| {
-                                ui.horizontal(|ui| {
-                                    (
-                                        idx,
-                                        ui.button("Reset").clicked(),
-                                        ui.button("Cancel").clicked(),
-                                    )
-                                })
-                                .inner
-                            },
-                        ));
-                    }
+                if ui.button("Reset Selected").clicked()
+                    && let Some(idx) = data.selected_area
+                {
+                    let area = &areas[idx];
+
+                    data.area_modal = Some(Modal::new(
+                        "area_modal",
+                        RichText::new(format!("Reset {}?", area.def.sid())),
+                        RichText::new(format!(
+                            "Are you sure you want to reset stats for {}? This is irreversable.",
+                            area.def.sid()
+                        )),
+                        move |ui| {
+                            ui.horizontal(|ui| {
+                                (
+                                    idx,
+                                    ui.button("Reset").clicked(),
+                                    ui.button("Cancel").clicked(),
+                                )
+                            })
+                            .inner
+                        },
+                    ));
                 }
 
                 ScrollArea::both().show_rows(ui, row_height, areas.len(), |ui, row_range| {
@@ -222,11 +222,8 @@ impl SessionsTab<'_> {
 
 
         ui.checkbox(&mut session.beat_best_time, "Beat best time");
-        if session.restarted_from_golden.is_some() {
-            ui.checkbox(
-                session.restarted_from_golden.as_mut().unwrap(),
-                "Restarted from golden",
-            );
+        if let Some(restarted_from_golden) = &mut session.restarted_from_golden {
+            ui.checkbox(restarted_from_golden, "Restarted from golden");
         }
 
         CollapsingHeader::new(RichText::new("Session Stats").heading2())
@@ -0,0 +1,439 @@
+//! Implements reading of Celeste's `Dialog/*.txt` localization files
+//!
+//! Vanilla stores these in `Content/Dialog` and mods store them in their own `Dialog` directory.
+//! Mod dialog is loaded on top of the vanilla dialog, so any key a mod defines overrides the vanilla one.
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsStr,
+    fs::{read_dir, read_to_string},
+    io::Read,
+    path::Path,
+};
+
+use crate::{
+    maps::{
+        ErasedMapElement,
+        elements::triggers::{MapTrigger, vanilla_triggers::MiniTextboxTrigger},
+    },
+    mods::ModCollection,
+};
+
+/// Header keys that set properties of the language instead of being dialog entries
+///
+/// These are usually written in the `#` comment header, but are also read without the `#`.
+const PROPERTY_KEYS: [&str; 7] = [
+    "LANGUAGE",
+    "ICON",
+    "ORDER",
+    "FONT",
+    "SPLIT_REGEX",
+    "COMMAS",
+    "PERIODS",
+];
+
+/// The dialog entries of a single language file, e.g. `English.txt`
+///
+/// Keys are case-insensitive, the same as they are in game.
+#[derive(Debug, Clone, Default)]
+pub struct Dialog {
+    /// Header properties like `LANGUAGE`, `ICON`, and `FONT`
+    pub properties: BTreeMap<String, String>,
+    /// Maps the uppercased key to the key as it was written and the value
+    entries: HashMap<String, (String, String)>,
+}
+
+impl Dialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the contents of a dialog file
+    ///
+    /// Lines starting with `#` are comments unless they set a property like `# LANGUAGE=english`,
+    /// `KEY= value` starts a new entry,
+    /// and any other line is appended to the value of the last entry on a new line.<br>
+    /// Portrait tags like `[MADELINE left normal]` are converted to `{portrait MADELINE left normal}`
+    /// like the game does.
+    pub fn parse(str: &str) -> Self {
+        let mut dialog = Dialog::new();
+        let mut current_key: Option<String> = None;
+
+        for line in str.trim_start_matches('\u{feff}').lines() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            // Language files write their properties as comments, e.g. `# LANGUAGE=english`
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some((key, value)) = split_entry(comment.trim())
+                    && PROPERTY_KEYS.contains(&key)
+                {
+                    dialog
+                        .properties
+                        .insert(key.to_owned(), value.trim().to_owned());
+                }
+
+                continue;
+            }
+
+            let line = convert_portraits(line);
+
+            if let Some((key, value)) = split_entry(&line) {
+                let value = value.trim();
+
+                if PROPERTY_KEYS.contains(&key) {
+                    dialog.properties.insert(key.to_owned(), value.to_owned());
+                    current_key = None;
+                } else {
+                    dialog.insert(key, value);
+                    current_key = Some(key.to_ascii_uppercase());
+                }
+            } else if let Some(key) = &current_key
+                && let Some((_, value)) = dialog.entries.get_mut(key)
+            {
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(&line);
+            }
+        }
+
+        dialog
+    }
+
+    /// Reads and parses a dialog file
+    pub fn from_reader(mut reader: impl Read) -> std::io::Result<Self> {
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf)?;
+
+        Ok(Self::parse(&buf))
+    }
+
+    /// The value of the `LANGUAGE` property, this is the id the game uses for the language
+    pub fn language(&self) -> Option<&str> {
+        self.properties.get("LANGUAGE").map(String::as_str)
+    }
+
+    /// Gets the raw value of a key, including any formatting tags
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&str> {
+        self.entries
+            .get(&key.as_ref().to_ascii_uppercase())
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Gets the value of a key with any `{+KEY}` references replaced by the referenced value
+    pub fn get_resolved(&self, key: impl AsRef<str>) -> Option<String> {
+        self.get(key).map(|v| self.resolve_references(v, 0))
+    }
+
+    /// Gets a human-readable version of a key's value
+    ///
+    /// This resolves any `{+KEY}` references and removes all other `{}` tags
+    /// which is what the game does for things like chapter and checkpoint names.
+    pub fn get_clean(&self, key: impl AsRef<str>) -> Option<String> {
+        self.get_resolved(key).map(|v| strip_tags(&v))
+    }
+
+    pub fn contains_key(&self, key: impl AsRef<str>) -> bool {
+        self.entries
+            .contains_key(&key.as_ref().to_ascii_uppercase())
+    }
+
+    /// Inserts an entry, replacing any existing entry with the same key
+    pub fn insert(&mut self, key: impl ToString, value: impl ToString) {
+        let key = key.to_string();
+        self.entries
+            .insert(key.to_ascii_uppercase(), (key, value.to_string()));
+    }
+
+    pub fn remove(&mut self, key: impl AsRef<str>) -> Option<String> {
+        self.entries
+            .remove(&key.as_ref().to_ascii_uppercase())
+            .map(|(_, value)| value)
+    }
+
+    /// Iterates over every entry as `(key, value)`
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .values()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.values().map(|(key, _)| key.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Loads all the entries and properties from `other` on top of this dialog
+    ///
+    /// This is how mod dialog is applied over vanilla dialog.
+    pub fn merge(&mut self, other: &Dialog) {
+        for (key, value) in other.iter() {
+            self.insert(key, value);
+        }
+
+        for (key, value) in &other.properties {
+            self.properties.insert(key.clone(), value.clone());
+        }
+    }
+
+    /// Returns the references whose key isn't defined in this dialog
+    pub fn missing_keys<'a>(&self, references: &'a [DialogReference]) -> Vec<&'a DialogReference> {
+        references
+            .iter()
+            .filter(|r| !self.contains_key(&r.key))
+            .collect()
+    }
+
+    fn resolve_references(&self, value: &str, depth: u8) -> String {
+        // Stop following references if they loop
+        if depth > 16 {
+            return value.to_owned();
+        }
+
+        let mut output = String::with_capacity(value.len());
+        let mut rest = value;
+
+        while let Some(start) = rest.find("{+") {
+            let Some(len) = rest[start ..].find('}') else {
+                break;
+            };
+
+            output.push_str(&rest[.. start]);
+            let key = rest[start + 2 .. start + len].trim();
+
+            match self.get(key) {
+                Some(referenced) =>
+                    output.push_str(&self.resolve_references(referenced, depth + 1)),
+                None => output.push_str(&rest[start ..= start + len]),
+            }
+
+            rest = &rest[start + len + 1 ..];
+        }
+
+        output.push_str(rest);
+        output
+    }
+}
+
+/// Dialog for every language, keyed by the name of the file it was loaded from
+///
+/// For example the entries in `English.txt` are stored under `English`.
+#[derive(Debug, Clone, Default)]
+pub struct Localization {
+    pub languages: HashMap<String, Dialog>,
+}
+
+impl Localization {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads every `.txt` file in a directory such as the vanilla `Content/Dialog` folder
+    pub fn from_dir(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut localization = Localization::new();
+
+        for entry in read_dir(path)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(OsStr::to_str) != Some("txt") {
+                continue;
+            }
+
+            if let Some(language) = path.file_stem().and_then(OsStr::to_str) {
+                let dialog = Dialog::parse(&read_to_string(&path)?);
+                localization.add_dialog(language, dialog);
+            }
+        }
+
+        Ok(localization)
+    }
+
+    /// Adds a language's dialog, merging it onto any dialog already loaded for that language
+    pub fn add_dialog(&mut self, language: impl ToString, dialog: Dialog) {
+        match self.languages.get_mut(&language.to_string()) {
+            Some(existing) => existing.merge(&dialog),
+            None => {
+                self.languages.insert(language.to_string(), dialog);
+            }
+        }
+    }
+
+    pub fn language(&self, language: impl AsRef<str>) -> Option<&Dialog> {
+        self.languages.get(language.as_ref())
+    }
+
+    /// Looks up a key in a specific language
+    pub fn get(&self, language: impl AsRef<str>, key: impl AsRef<str>) -> Option<&str> {
+        self.language(language).and_then(|d| d.get(key))
+    }
+
+    /// Loads every language in `other` on top of this one
+    ///
+    /// Use this to apply mod dialog over the vanilla dialog.
+    pub fn merge(&mut self, other: &Localization) {
+        for (language, dialog) in &other.languages {
+            self.add_dialog(language, dialog.clone());
+        }
+    }
+}
+
+/// A dialog key used somewhere in a mod
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogReference {
+    /// The SID of the map the key is used in
+    pub sid: String,
+    pub key: String,
+    pub source: DialogKeySource,
+}
+
+/// Where a [DialogReference] came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogKeySource {
+    /// The name of the chapter, this is the map's SID ran through [dialog_keyify]
+    ChapterName,
+    /// The name of the map's level set, this is the SID without the map's own name ran through [dialog_keyify]
+    LevelSet,
+    /// The `Name` of a checkpoint in the map's meta.yaml
+    CheckpointName { level: String },
+    /// The `LoadingVignetteText` in the map's meta.yaml
+    LoadingVignette,
+    /// A `minitextboxTrigger` in a room
+    MiniTextbox { room: String },
+}
+
+impl ModCollection {
+    /// Lists the dialog keys used by every map in the collection
+    ///
+    /// Pass this into [Dialog::missing_keys] to find keys that aren't defined
+    pub fn dialog_references(&self) -> Vec<DialogReference> {
+        let mut references = Vec::new();
+
+        for (sid, map) in &self.maps {
+            let mut push = |key: String, source| {
+                references.push(DialogReference {
+                    sid: sid.clone(),
+                    key,
+                    source,
+                })
+            };
+
+            push(dialog_keyify(sid), DialogKeySource::ChapterName);
+
+            if let Some((level_set, _)) = sid.rsplit_once('/') {
+                push(dialog_keyify(level_set), DialogKeySource::LevelSet);
+            }
+
+            if let Some(meta) = &map.meta {
+                for mode in meta.map_meta_mode_properties.iter().flatten().flatten() {
+                    for checkpoint in mode.checkpoints.iter().flatten() {
+                        push(checkpoint.name.clone(), DialogKeySource::CheckpointName {
+                            level: checkpoint.level.clone(),
+                        });
+                    }
+                }
+
+                if let Some(vignette) = &meta.loading_vignette_text {
+                    push(vignette.dialog.clone(), DialogKeySource::LoadingVignette);
+                }
+            }
+
+            for level in &map.map.levels.levels {
+                let Some(triggers) = &level.triggers else {
+                    continue;
+                };
+
+                for trigger in &triggers.triggers {
+                    let trigger: &dyn ErasedMapElement = trigger.as_ref();
+
+                    if let Some(textbox) = (trigger as &dyn std::any::Any)
+                        .downcast_ref::<MapTrigger<MiniTextboxTrigger>>()
+                    {
+                        push(
+                            textbox
+                                .entity
+                                .dialog_id
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_owned(),
+                            DialogKeySource::MiniTextbox {
+                                room: level.name.as_str().unwrap_or_default().to_owned(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        references
+    }
+}
+
+/// Converts a string into the form Everest uses for dialog keys
+///
+/// This is used to get the dialog key of things like chapter names from their SID,
+/// e.g. `Celeste/1-ForsakenCity` becomes `Celeste_1_ForsakenCity`.
+pub fn dialog_keyify(str: impl AsRef<str>) -> String {
+    str.as_ref().replace(['/', '-', '+', ' '], "_")
+}
+
+/// Removes all `{}` tags from a dialog value
+pub fn strip_tags(str: &str) -> String {
+    let mut output = String::with_capacity(str.len());
+    let mut rest = str;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start ..].find('}') else {
+            break;
+        };
+
+        output.push_str(&rest[.. start]);
+        rest = &rest[start + len + 1 ..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Splits a `KEY= value` line into the key and value
+///
+/// Keys can only contain letters, numbers, and underscores.
+fn split_entry(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once('=')?;
+
+    if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        Some((key, value))
+    } else {
+        None
+    }
+}
+
+/// Converts `[portrait]` tags into `{portrait portrait}` tags
+fn convert_portraits(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find('[') {
+        let Some(len) = rest[start ..].find(']') else {
+            break;
+        };
+
+        output.push_str(&rest[.. start]);
+        output.push_str("{portrait ");
+        output.push_str(&rest[start + 1 .. start + len]);
+        output.push('}');
+        rest = &rest[start + len + 1 ..];
+    }
+
+    output.push_str(rest);
+    output
+}
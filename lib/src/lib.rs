@@ -5,8 +5,10 @@
 )]
 
 //! Celeste save reader and writer
+pub mod dialog;
 pub mod maps;
 pub mod mods;
 pub mod playbacks;
 pub mod saves;
+pub mod sprites;
 pub mod utils;
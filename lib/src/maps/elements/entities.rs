@@ -3,13 +3,16 @@ use celeste_rs_macros::EntityData;
 use crate::{
     maps::{
         ErasedMapElement,
+        ExtraData,
         MapElement,
         MapManager,
+        RawMapElement,
         ResolvableString,
-        elements::Node,
+        elements::{Node, NodeError, NodeList, check_node_count},
         encoder::MapEncoder,
         parser::{MapElementParsingError, MapParser},
-        var_types::Character,
+        reflect::{FieldError, FieldInfo, FieldKind, optional_field, required_field},
+        var_types::{Character, EncodedVar},
     },
     utils::num::{Float, Integer},
 };
@@ -36,6 +39,8 @@ pub trait ErasedEntity: ErasedMapElement {
     fn origin_x(&self) -> Float;
     fn origin_y(&self) -> Float;
     fn kind(&self) -> &'static str;
+    /// Clones the entity into a new box
+    fn clone_entity(&self) -> DynEntity;
 }
 
 impl<T: Entity> ErasedEntity for T {
@@ -70,9 +75,14 @@ impl<T: Entity> ErasedEntity for T {
     fn kind(&self) -> &'static str {
         self.kind()
     }
+
+    fn clone_entity(&self) -> DynEntity {
+        Box::new(self.clone())
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// The full struct of an [Entity] implementaiton
 ///
 /// These fields are on every entity element and are
@@ -116,6 +126,48 @@ impl<T: EntityData> MapElement for MapEntity<T> {
         encoder.attribute("originY", self.origin_y);
         self.entity.to_raw(encoder);
     }
+
+    fn field_info(&self) -> Vec<FieldInfo> {
+        let mut fields = vec![
+            FieldInfo::new("id", "id", FieldKind::Integer, false),
+            FieldInfo::new("x", "x", FieldKind::Float, false),
+            FieldInfo::new("y", "y", FieldKind::Float, false),
+            FieldInfo::new("width", "width", FieldKind::Integer, true),
+            FieldInfo::new("height", "height", FieldKind::Integer, true),
+            FieldInfo::new("origin_x", "originX", FieldKind::Float, false),
+            FieldInfo::new("origin_y", "originY", FieldKind::Float, false),
+        ];
+        fields.extend(self.entity.field_info());
+        fields
+    }
+
+    fn get_field(&self, name: &str) -> Option<EncodedVar> {
+        match name {
+            "id" => Some(self.id.into()),
+            "x" => Some(self.x.into()),
+            "y" => Some(self.y.into()),
+            "width" => self.width.map(EncodedVar::from),
+            "height" => self.height.map(EncodedVar::from),
+            "originX" => Some(self.origin_x.into()),
+            "originY" => Some(self.origin_y.into()),
+            _ => self.entity.get_field(name),
+        }
+    }
+
+    fn set_field(&mut self, name: &str, value: Option<EncodedVar>) -> Result<(), FieldError> {
+        match name {
+            "id" => self.id = required_field(name, value)?,
+            "x" => self.x = required_field(name, value)?,
+            "y" => self.y = required_field(name, value)?,
+            "width" => self.width = optional_field(name, value)?,
+            "height" => self.height = optional_field(name, value)?,
+            "originX" => self.origin_x = required_field(name, value)?,
+            "originY" => self.origin_y = required_field(name, value)?,
+            _ => return self.entity.set_field(name, value),
+        }
+
+        Ok(())
+    }
 }
 
 impl<E: EntityData> Entity for MapEntity<E> {
@@ -152,74 +204,161 @@ impl<E: EntityData> Entity for MapEntity<E> {
     }
 }
 
+impl<T: EntityData> NodeList for MapEntity<T> {
+    fn nodes(&self) -> &[Node] {
+        self.entity.nodes()
+    }
+
+    fn nodes_mut(&mut self) -> &mut [Node] {
+        self.entity.nodes_mut()
+    }
+
+    fn set_nodes(&mut self, nodes: Vec<Node>) -> Result<(), NodeError> {
+        self.entity.set_nodes(nodes)
+    }
+
+    fn node_limits(&self) -> (usize, Option<usize>) {
+        (T::MIN_NODES, T::MAX_NODES)
+    }
+}
+
+/// Entities without a registered parser are kept as [RawMapElement]s
+///
+/// The common entity fields are read from the attributes, falling back to 0 when they're missing.
+impl ErasedEntity for RawMapElement {
+    fn id(&self) -> Integer {
+        self.get_attribute("id")
+            .and_then(|v| v.int().ok())
+            .unwrap_or(Integer::I32(0))
+    }
+
+    fn x(&self) -> Float {
+        self.get_attribute("x")
+            .and_then(|v| v.float().ok())
+            .unwrap_or(Float::F32(0.0))
+    }
+
+    fn y(&self) -> Float {
+        self.get_attribute("y")
+            .and_then(|v| v.float().ok())
+            .unwrap_or(Float::F32(0.0))
+    }
+
+    fn width(&self) -> Option<Integer> {
+        self.get_attribute("width").and_then(|v| v.int().ok())
+    }
+
+    fn height(&self) -> Option<Integer> {
+        self.get_attribute("height").and_then(|v| v.int().ok())
+    }
+
+    fn origin_x(&self) -> Float {
+        self.get_attribute("originX")
+            .and_then(|v| v.float().ok())
+            .unwrap_or(Float::F32(0.0))
+    }
+
+    fn origin_y(&self) -> Float {
+        self.get_attribute("originY")
+            .and_then(|v| v.float().ok())
+            .unwrap_or(Float::F32(0.0))
+    }
+
+    /// Raw elements don't have a static name, use [ErasedMapElement::name] instead
+    fn kind(&self) -> &'static str {
+        "RawMapElement"
+    }
+
+    fn clone_entity(&self) -> DynEntity {
+        Box::new(self.clone())
+    }
+}
+
 impl dyn ErasedEntity {
+    /// Gets the entity as a [RawMapElement] if there was no parser registered for it
+    pub fn as_raw(&self) -> Option<&RawMapElement> {
+        (self as &dyn Any).downcast_ref()
+    }
+
+    /// Checks if the entity was parsed into a typed struct
+    pub fn is_parsed(&self) -> bool {
+        self.as_raw().is_none()
+    }
+
     pub fn downcast<T: EntityData>(&self) -> Option<&MapEntity<T>> {
-        // Runtime check that this is a T
-        if std::any::TypeId::of::<T>() == (*self).type_id() {
-            // EntityData NAMEs are *required* to be unique
-            // as long as that holds this is safe
-            // I seriously doubt this will be broken but its documented in EntityData
-            unsafe { Some(&*(self as *const dyn ErasedEntity as *const MapEntity<T>)) }
-        } else {
-            None
-        }
+        (self as &dyn Any).downcast_ref()
     }
 
     pub fn downcast_mut<T: EntityData>(&mut self) -> Option<&mut MapEntity<T>> {
-        // Runtime check that this is a T
-        if std::any::TypeId::of::<T>() == (*self).type_id() {
-            // EntityData NAMEs are *required* to be unique
-            // as long as that holds this is safe
-            // I seriously doubt this will be broken but its documented in EntityData
-            unsafe { Some(&mut *(self as *mut dyn ErasedEntity as *mut MapEntity<T>)) }
-        } else {
-            None
-        }
+        (self as &mut dyn Any).downcast_mut()
     }
 }
 
 pub type DynEntity = Box<dyn ErasedEntity>;
 
+impl Clone for DynEntity {
+    fn clone(&self) -> Self {
+        self.as_ref().clone_entity()
+    }
+}
+
+impl PartialEq for DynEntity {
+    fn eq(&self, other: &Self) -> bool {
+        (self.as_ref() as &dyn ErasedMapElement).eq_dyn(other.as_ref())
+    }
+}
+
 /// Represents an entity, anything that can be stored in [Entities](super::Entities).
-pub trait EntityData: Debug + Any {
+pub trait EntityData: Debug + Any + Clone + PartialEq {
     /// The name of the entity in the binary file
     ///
-    /// This has to be unique or else downcasting [`dyn ErasedEntity`](ErasedEntity) will be unsound.<br>
-    /// Despite this, the trait is not marked as unsafe as there is a runtime check against this case when using [`MapManager`] which is the main case this library supports.
+    /// This should be unique, registering two entities with the same name on a [`MapManager`]
+    /// keeps only the parser added last.
     const NAME: &'static str;
 
     fn from_raw(parser: MapParser) -> Result<Self, MapElementParsingError>
     where Self: Sized;
     fn to_raw(&self, encoder: &mut MapEncoder);
-}
 
+    /// The attribute fields of the entity, see [reflect](crate::maps::reflect)
+    fn field_info(&self) -> Vec<FieldInfo> {
+        Vec::new()
+    }
 
-macro_rules! unit_entities {
-    ($($struct_name: ident, $name: literal),*) => {
-        pub(super) fn add_unit_entity_parsers(mm: &mut MapManager) {
-            $(
-                mm.add_entity_parser::<$struct_name>();
-            )*
-        }
+    /// Gets the value of an attribute by name, `None` if it isn't set
+    fn get_field(&self, _name: &str) -> Option<EncodedVar> {
+        None
+    }
 
-        $(
-            #[derive(Debug, Clone, Copy, Default)]
-            pub struct $struct_name;
+    /// Sets the value of an attribute by name, unsetting it if `value` is `None`
+    fn set_field(&mut self, name: &str, _value: Option<EncodedVar>) -> Result<(), FieldError> {
+        Err(FieldError::UnknownField(name.to_owned()))
+    }
+
+    /// The fewest nodes the entity can have
+    const MIN_NODES: usize = 0;
+    /// The most nodes the entity can have, `None` if there's no limit
+    ///
+    /// This is `Some(0)` when there's no `#[node]` field, any nodes read for the entity are kept
+    /// in its `#[extra]` field and aren't seen by [NodeList].
+    const MAX_NODES: Option<usize> = Some(0);
 
-            impl EntityData for $struct_name {
-                const NAME: &'static str = $name;
+    /// The nodes of the entity, in pixels relative to the room
+    fn nodes(&self) -> &[Node] {
+        &[]
+    }
 
-                fn from_raw(_parser: MapParser) -> Result<Self, MapElementParsingError>
-                where Self: Sized {
-                    Ok(Self::default())
-                }
+    fn nodes_mut(&mut self) -> &mut [Node] {
+        &mut []
+    }
 
-                fn to_raw(&self, _encoder: &mut MapEncoder) {}
-            }
-        )*
-    };
+    /// Replaces the nodes of the entity, failing if there are too few or too many
+    fn set_nodes(&mut self, nodes: Vec<Node>) -> Result<(), NodeError> {
+        check_node_count(nodes.len(), Self::MIN_NODES, Self::MAX_NODES)
+    }
 }
 
+
 /// Adds parsers for all the vanilla entities to the [MapManager]
 pub fn add_entity_parsers(mm: &mut MapManager) {
     mm.add_entity_parser::<SpikesUp>();
@@ -321,8 +460,67 @@ pub fn add_entity_parsers(mm: &mut MapManager) {
     mm.add_entity_parser::<CrushBlock>();
     mm.add_entity_parser::<TrackSpinner>();
     mm.add_entity_parser::<Gondola>();
-
-    add_unit_entity_parsers(mm);
+    mm.add_entity_parser::<GoldenBerry>();
+    mm.add_entity_parser::<CrumbleBlock>();
+    mm.add_entity_parser::<Checkpoint>();
+    mm.add_entity_parser::<DarkChaser>();
+    mm.add_entity_parser::<BounceBlock>();
+    mm.add_entity_parser::<IntroCar>();
+    mm.add_entity_parser::<GlassBlock>();
+    mm.add_entity_parser::<Slider>();
+    mm.add_entity_parser::<RotatingPlatforms>();
+    mm.add_entity_parser::<FakeHeart>();
+    mm.add_entity_parser::<Player>();
+    mm.add_entity_parser::<WingedGoldenStrawberry>();
+    mm.add_entity_parser::<FlutterBird>();
+    mm.add_entity_parser::<Plateau>();
+    mm.add_entity_parser::<Payphone>();
+    mm.add_entity_parser::<WavedashMachine>();
+    mm.add_entity_parser::<PlaybackBillboard>();
+    mm.add_entity_parser::<SinkingPlatform>();
+    mm.add_entity_parser::<PlayerSeeker>();
+    mm.add_entity_parser::<PICOConsole>();
+    mm.add_entity_parser::<ResortRoofEnding>();
+    mm.add_entity_parser::<FireBarrier>();
+    mm.add_entity_parser::<TriggerSpikesUp>();
+    mm.add_entity_parser::<TriggerSpikesDown>();
+    mm.add_entity_parser::<IceBlock>();
+    mm.add_entity_parser::<Waterfall>();
+    mm.add_entity_parser::<ResortLantern>();
+    mm.add_entity_parser::<KillBox>();
+    mm.add_entity_parser::<TouchSwitch>();
+    mm.add_entity_parser::<ClutterCabinet>();
+    mm.add_entity_parser::<TheoCrystal>();
+    mm.add_entity_parser::<BlockField>();
+    mm.add_entity_parser::<InvisibleBarrier>();
+    mm.add_entity_parser::<TempleMirrorPortal>();
+    mm.add_entity_parser::<TriggerSpikesRight>();
+    mm.add_entity_parser::<BridgeFixed>();
+    mm.add_entity_parser::<ForegroundDebris>();
+    mm.add_entity_parser::<OshiroDoor>();
+    mm.add_entity_parser::<TheoCrystalHoldingBarrier>();
+    mm.add_entity_parser::<TempleEye>();
+    mm.add_entity_parser::<TheoCrystalPedestal>();
+    mm.add_entity_parser::<FloatingDebris>();
+    mm.add_entity_parser::<TriggerSpikesLeft>();
+    mm.add_entity_parser::<KevinsPC>();
+    mm.add_entity_parser::<SandwichLava>();
+    mm.add_entity_parser::<Trapdoor>();
+    mm.add_entity_parser::<SummitCloud>();
+    mm.add_entity_parser::<FriendlyGhost>();
+    mm.add_entity_parser::<FinalBossFallingBlock>();
+    mm.add_entity_parser::<DreamMirror>();
+    mm.add_entity_parser::<SeekerBarrier>();
+    mm.add_entity_parser::<TempleBigEyeball>();
+    mm.add_entity_parser::<ResortMirror>();
+    mm.add_entity_parser::<WhiteBlock>();
+    mm.add_entity_parser::<HangingLamp>();
+    mm.add_entity_parser::<GoldenBlock>();
+    mm.add_entity_parser::<NegaBlock>();
+    mm.add_entity_parser::<StarClimbController>();
+    mm.add_entity_parser::<ChaserBarrier>();
+    mm.add_entity_parser::<DreamHeartGem>();
+    mm.add_entity_parser::<SoundTest3D>();
 }
 
 use vanilla_entities::*;
@@ -332,50 +530,75 @@ pub mod vanilla_entities {
     use super::*;
 
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "spikesUp"]
     pub struct SpikesUp {
         #[name = "type"]
         pub kind: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "spikesDown"]
     pub struct SpikesDown {
         #[name = "type"]
         pub kind: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "spikesLeft"]
     pub struct SpikesLeft {
         #[name = "type"]
         pub kind: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "spikesRight"]
     pub struct SpikesRight {
         #[name = "type"]
         pub kind: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "jumpThru"]
     pub struct JumpThru {
         #[name = "texture"]
         pub texture: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "wire"]
     pub struct Wire {
         #[name = "above"]
         pub above: bool,
         pub to: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "strawberry"]
     pub struct Strawberry {
         #[name = "winged"]
@@ -384,34 +607,54 @@ pub mod vanilla_entities {
         pub checkpoint_id: Integer,
         #[name = "order"]
         pub order: Option<Integer>,
+        #[name = "moon"]
+        pub moon: Option<bool>,
+        #[node]
+        pub seeds: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "lightbeam"]
     pub struct Lightbeam {
         #[name = "rotation"]
         pub rotation: Integer,
         #[name = "flag"]
         pub flag: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cassette"]
     pub struct Cassette {
         #[node]
         pub bubble_points: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cassetteBlock"]
     pub struct CassetteBlock {
         #[name = "index"]
         pub index: Integer,
         #[name = "finishedState"]
         pub finished_state: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "dashBlock"]
     pub struct DashBlock {
         #[name = "permanent"]
@@ -422,43 +665,67 @@ pub mod vanilla_entities {
         pub blend_in: bool,
         #[name = "canDash"]
         pub can_dash: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "bonfire"]
     pub struct Bonfire {
         #[name = "mode"]
         pub mode: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "npc"]
     pub struct NPC {
         #[name = "npc"]
         pub npc: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "coverupWall"]
     pub struct CoverupWall {
         #[name = "tiletype"]
         pub tile_type: Character,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "memorial"]
     pub struct Memorial {
         #[name = "dreaming"]
         pub dreaming: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "birdForsakenCityGem"]
     pub struct BirdForsakenCityGem {
         #[node]
         pub nodes: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "fallingBlock"]
     pub struct FallingBlock {
         #[name = "tiletype"]
@@ -467,96 +734,662 @@ pub mod vanilla_entities {
         pub behind: Option<bool>,
         #[name = "climbFall"]
         pub climb_fall: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "fakeWall"]
     pub struct FakeWall {
         #[name = "tiletype"]
         pub tile_type: Character,
         #[name = "playTransitionReveal"]
         pub play_transition_reveal: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "spring"]
     pub struct Spring {
         #[name = "playerCanUse"]
         pub player_can_use: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "zipMover"]
     pub struct ZipMover {
         #[name = "theme"]
         pub theme: Option<ResolvableString>,
         pub to: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "refill"]
     pub struct Refill {
         #[name = "twoDash"]
         pub two_dash: Option<bool>,
         #[name = "oneUse"]
         pub one_use: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "player"]
+    pub struct Player {
+        /// Marks the spawn point Everest uses when entering the map
+        #[name = "isDefaultSpawn"]
+        pub is_default_spawn: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "memorialTextController"]
+    pub struct WingedGoldenStrawberry {
+        #[name = "winged"]
+        pub winged: Option<bool>,
+        #[name = "moon"]
+        pub moon: Option<bool>,
+        #[node]
+        pub seeds: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "flutterbird"]
+    pub struct FlutterBird {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "plateau"]
+    pub struct Plateau {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "payphone"]
+    pub struct Payphone {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "wavedashmachine"]
+    pub struct WavedashMachine {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "playbackBillboard"]
+    pub struct PlaybackBillboard {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "sinkingPlatform"]
+    pub struct SinkingPlatform {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "playerSeeker"]
+    pub struct PlayerSeeker {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "picoconsole"]
+    pub struct PICOConsole {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "resortRoofEnding"]
+    pub struct ResortRoofEnding {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "fireBarrier"]
+    pub struct FireBarrier {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "triggerSpikesUp"]
+    pub struct TriggerSpikesUp {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "triggerSpikesDown"]
+    pub struct TriggerSpikesDown {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "iceBlock"]
+    pub struct IceBlock {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "waterfall"]
+    pub struct Waterfall {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "resortLantern"]
+    pub struct ResortLantern {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "killbox"]
+    pub struct KillBox {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    /// Touch switches only have a position
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "touchSwitch"]
+    pub struct TouchSwitch {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "clutterCabinet"]
+    pub struct ClutterCabinet {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "theoCrystal"]
+    pub struct TheoCrystal {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    /// Sized by the entity's [width](MapEntity::width) and [height](MapEntity::height), it has no attributes of its own
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "blockField"]
+    pub struct BlockField {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    /// Sized by the entity's [width](MapEntity::width) and [height](MapEntity::height), it has no attributes of its own
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "invisibleBarrier"]
+    pub struct InvisibleBarrier {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "templeMirrorPortal"]
+    pub struct TempleMirrorPortal {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "triggerSpikesRight"]
+    pub struct TriggerSpikesRight {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "bridgeFixed"]
+    pub struct BridgeFixed {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "foregroundDebris"]
+    pub struct ForegroundDebris {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "oshirodoor"]
+    pub struct OshiroDoor {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "theoCrystalHoldingBarrier"]
+    pub struct TheoCrystalHoldingBarrier {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "templeEye"]
+    pub struct TempleEye {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "theoCrystalPedestal"]
+    pub struct TheoCrystalPedestal {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "floatingDebris"]
+    pub struct FloatingDebris {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "triggerSpikesLeft"]
+    pub struct TriggerSpikesLeft {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "kevins_pc"]
+    pub struct KevinsPC {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "sandwichLava"]
+    pub struct SandwichLava {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "trapdoor"]
+    pub struct Trapdoor {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "summitcloud"]
+    pub struct SummitCloud {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "friendlyGhost"]
+    pub struct FriendlyGhost {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    /// Sized by the entity's [width](MapEntity::width) and [height](MapEntity::height), it has no attributes of its own
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "finalBossFallingBlock"]
+    pub struct FinalBossFallingBlock {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "dreammirror"]
+    pub struct DreamMirror {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "seekerBarrier"]
+    pub struct SeekerBarrier {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "templeBigEyeball"]
+    pub struct TempleBigEyeball {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "resortmirror"]
+    pub struct ResortMirror {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "whiteblock"]
+    pub struct WhiteBlock {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "hanginglamp"]
+    pub struct HangingLamp {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "goldenBlock"]
+    pub struct GoldenBlock {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "negaBlock"]
+    pub struct NegaBlock {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "starClimbController"]
+    pub struct StarClimbController {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "chaserBarrier"]
+    pub struct ChaserBarrier {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "dreamHeartGem"]
+    pub struct DreamHeartGem {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "SoundTest3d"]
+    pub struct SoundTest3D {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "goldenBerry"]
+    pub struct GoldenBerry {
+        #[name = "winged"]
+        pub winged: Option<bool>,
+        #[name = "moon"]
+        pub moon: Option<bool>,
+        #[node]
+        pub seeds: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    unit_entities! {
-        Player, "player",
-        GoldenBerry, "goldenBerry",
-        CrumbleBlock, "crumbleBlock",
-        Checkpoint, "checkpoint",
-        WingedGoldenStrawberry, "memorialTextController",
-        FlutterBird, "flutterbird",
-        Plateau, "plateau",
-        Payphone, "payphone",
-        WavedashMachine, "wavedashmachine",
-        PlaybackBillboard, "playbackBillboard",
-        SinkingPlatform, "sinkingPlatform",
-        PlayerSeeker, "playerSeeker",
-        PICOConsole, "picoconsole",
-        DarkChaser, "darkChaser",
-        ResortRoofEnding, "resortRoofEnding",
-        FireBarrier, "fireBarrier",
-        TriggerSpikesUp, "triggerSpikesUp",
-        TriggerSpikesDown, "triggerSpikesDown",
-        IceBlock, "iceBlock",
-        Waterfall, "waterfall",
-        BounceBlock, "bounceBlock",
-        ResortLantern, "resortLantern",
-        KillBox, "killbox",
-        TouchSwitch, "touchSwitch",
-        ClutterCabinet, "clutterCabinet",
-        TheoCrystal, "theoCrystal",
-        BlockField, "blockField",
-        InvisibleBarrier, "invisibleBarrier",
-        TempleMirrorPortal, "templeMirrorPortal",
-        TriggerSpikesRight, "triggerSpikesRight",
-        BridgeFixed, "bridgeFixed",
-        IntroCar, "introCar",
-        ForegroundDebris, "foregroundDebris",
-        OshiroDoor, "oshirodoor",
-        TheoCrystalHoldingBarrier, "theoCrystalHoldingBarrier",
-        TempleEye, "templeEye",
-        TheoCrystalPedestal, "theoCrystalPedestal",
-        FloatingDebris, "floatingDebris",
-        TriggerSpikesLeft, "triggerSpikesLeft",
-        KevinsPC, "kevins_pc",
-        SandwichLava, "sandwichLava",
-        Trapdoor, "trapdoor",
-        SummitCloud, "summitcloud",
-        FriendlyGhost, "friendlyGhost",
-        FinalBossFallingBlock, "finalBossFallingBlock",
-        DreamMirror, "dreammirror",
-        SeekerBarrier, "seekerBarrier",
-        TempleBigEyeball, "templeBigEyeball",
-        ResortMirror, "resortmirror",
-        WhiteBlock, "whiteblock",
-        HangingLamp, "hanginglamp"
-    }
-
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "crumbleBlock"]
+    pub struct CrumbleBlock {
+        #[name = "texture"]
+        pub texture: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "checkpoint"]
+    pub struct Checkpoint {
+        #[name = "bg"]
+        pub bg: Option<ResolvableString>,
+        #[name = "inventory"]
+        pub inventory: Option<ResolvableString>,
+        #[name = "dreaming"]
+        pub dreaming: Option<bool>,
+        #[name = "coreMode"]
+        pub core_mode: Option<ResolvableString>,
+        #[name = "allowOrigin"]
+        pub allow_origin: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "darkChaser"]
+    pub struct DarkChaser {
+        #[name = "canChangeMusic"]
+        pub can_change_music: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "bounceBlock"]
+    pub struct BounceBlock {
+        #[name = "notCoreMode"]
+        pub not_core_mode: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "introCar"]
+    pub struct IntroCar {
+        #[name = "hasRoadAndBarriers"]
+        pub has_road_and_barriers: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "glassBlock"]
+    pub struct GlassBlock {
+        #[name = "sinks"]
+        pub sinks: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "slider"]
+    pub struct Slider {
+        #[name = "clockwise"]
+        pub clockwise: Option<bool>,
+        #[name = "surface"]
+        pub surface: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "rotatingPlatforms"]
+    pub struct RotatingPlatforms {
+        #[name = "platforms"]
+        pub platforms: Option<Integer>,
+        #[name = "clockwise"]
+        pub clockwise: Option<bool>,
+        #[node]
+        pub center: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "fakeHeart"]
+    pub struct FakeHeart {
+        #[name = "color"]
+        pub color: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "tentacles"]
     pub struct Tentacles {
         #[name = "fear_distance"]
@@ -565,62 +1398,94 @@ pub mod vanilla_entities {
         pub slide_until: u8,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "glider"]
     pub struct Glider {
         #[name = "bubble"]
         pub bubble: bool,
         #[name = "tutorial"]
         pub tutorial: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "flingBirdIntro"]
     pub struct FlingBirdIntro {
         #[name = "crashes"]
         pub crashes: bool,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "exitBlock"]
     pub struct ExitBlock {
         #[name = "tileType"]
         pub tiletype: Character,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "templeCrackedBlock"]
     pub struct TempleCrackedBlock {
         #[name = "persistent"]
         pub persistent: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "clothesline"]
     pub struct Clothesline {
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "redBlocks"]
     pub struct RedBlocks {
         #[name = "inverted"]
         pub inverted: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "door"]
     pub struct Door {
         #[name = "type"]
         pub kind: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "dashSwitchV"]
     pub struct DashSwitchV {
         #[name = "ceiling"]
@@ -631,16 +1496,24 @@ pub mod vanilla_entities {
         pub sprite: ResolvableString,
         #[name = "allGates"]
         pub allgates: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "spinner"]
     pub struct Spinner {
         #[name = "attachToSolid"]
         pub attachtosolid: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "conditionBlock"]
     pub struct ConditionBlock {
         #[name = "tileType"]
@@ -649,25 +1522,37 @@ pub mod vanilla_entities {
         pub condition: ResolvableString,
         #[name = "conditionID"]
         pub conditionid: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "floatySpaceBlock"]
     pub struct FloatySpaceBlock {
         #[name = "tiletype"]
         pub tiletype: Character,
         #[name = "disableSpawnOffset"]
         pub disablespawnoffset: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "soundSource"]
     pub struct SoundSource {
         #[name = "sound"]
         pub sound: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "lockBlock"]
     pub struct LockBlock {
         #[name = "stepMusicProgress"]
@@ -676,16 +1561,24 @@ pub mod vanilla_entities {
         pub sprite: ResolvableString,
         #[name = "unlock_sfx"]
         pub unlock_sfx: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "seeker"]
     pub struct Seeker {
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "dashSwitchH"]
     pub struct DashSwitchH {
         #[name = "leftSide"]
@@ -696,31 +1589,47 @@ pub mod vanilla_entities {
         pub sprite: ResolvableString,
         #[name = "allGates"]
         pub allgates: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "summitcheckpoint"]
     pub struct SummitCheckpoint {
         #[name = "number"]
         pub number: u8,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "summitgem"]
     pub struct SummitGem {
         #[name = "gem"]
         pub gem: u8,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "summitGemManager"]
     pub struct SummitGemManager {
-        #[node]
-        pub node: Node,
+        #[node(max = 6)]
+        pub nodes: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "fireBall"]
     pub struct FireBall {
         #[name = "amount"]
@@ -733,18 +1642,26 @@ pub mod vanilla_entities {
         pub notcoremode: Option<bool>,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "flingBird"]
     pub struct FlingBird {
         #[name = "waiting"]
         pub waiting: bool,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "coreModeToggle"]
     pub struct CoreModeToggle {
         #[name = "onlyFire"]
@@ -753,9 +1670,13 @@ pub mod vanilla_entities {
         pub onlyice: bool,
         #[name = "persistent"]
         pub persistent: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "heartGemDoor"]
     pub struct HeartGemDoor {
         #[name = "requires"]
@@ -764,50 +1685,74 @@ pub mod vanilla_entities {
         pub starthidden: Option<bool>,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "eyebomb"]
     pub struct Eyebomb {
         #[name = "right"]
         pub right: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "templeGate"]
     pub struct TempleGate {
         #[name = "type"]
         pub kind: ResolvableString,
         #[name = "sprite"]
         pub sprite: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "playbackTutorial"]
     pub struct PlaybackTutorial {
         #[name = "tutorial"]
         pub tutorial: ResolvableString,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "starJumpBlock"]
     pub struct StarJumpBlock {
         #[name = "sinks"]
         pub sinks: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "seekerStatue"]
     pub struct SeekerStatue {
         #[name = "hatch"]
         pub hatch: ResolvableString,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "finalBoss"]
     pub struct FinalBoss {
         #[name = "patternIndex"]
@@ -822,52 +1767,80 @@ pub mod vanilla_entities {
         pub cameralocky: bool,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cloud"]
     pub struct Cloud {
         #[name = "fragile"]
         pub fragile: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "bigWaterfall"]
     pub struct BigWaterfall {
         #[name = "layer"]
         pub layer: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "wallSpringLeft"]
     pub struct WallSpringLeft {
         #[name = "playerCanUse"]
         pub playercanuse: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "coreMessage"]
     pub struct CoreMessage {
         #[name = "line"]
         pub line: u8,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "introCrusher"]
     pub struct IntroCrusher {
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "key"]
     pub struct Key {
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "hahaha"]
     pub struct HaHaHa {
         #[name = "ifset"]
@@ -876,9 +1849,13 @@ pub mod vanilla_entities {
         pub triggerlaughsfx: bool,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "badelineBoost"]
     pub struct BadelineBoost {
         #[name = "lockCamera"]
@@ -892,40 +1869,60 @@ pub mod vanilla_entities {
         #[name = "finalCh9Dialog"]
         pub finalch9dialog: Option<bool>,
         #[node]
-        pub node: Option<Node>,
+        pub nodes: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "wallSpringRight"]
     pub struct WallSpringRight {
         #[name = "playerCanUse"]
         pub playercanuse: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "crumbleWallOnRumble"]
     pub struct CrumbleWallOnRumble {
         #[name = "blendin"]
         pub blendin: bool,
         #[name = "persistent"]
         pub persistent: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "risingLava"]
     pub struct RisingLava {
         #[name = "intro"]
         pub intro: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "greenBlocks"]
     pub struct GreenBlocks {
         #[name = "inverted"]
         pub inverted: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "lightningBlock"]
     pub struct LightningBlock {
         #[name = "flag"]
@@ -938,9 +1935,13 @@ pub mod vanilla_entities {
         pub music_session: Option<bool>,
         #[name = "flipX"]
         pub flipx: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "birdPath"]
     pub struct BirdPath {
         #[name = "only_once"]
@@ -949,39 +1950,59 @@ pub mod vanilla_entities {
         pub onlyifleft: bool,
         #[name = "speedMult"]
         pub speedmult: Option<Float>,
-        #[node]
-        pub node: Node,
+        #[node(min = 1)]
+        pub nodes: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cutsceneNode"]
     pub struct CutsceneNode {
         #[name = "nodeName"]
         pub nodename: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "clutterDoor"]
     pub struct ClutterDoor {
         #[name = "type"]
         pub kind: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "bigSpinner"]
     pub struct BigSpinner {
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cliffside_flag"]
     pub struct CliffsideFlag {
         #[name = "index"]
         pub index: u8,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "ridgeGate"]
     pub struct RidgeGate {
         #[name = "strawberries"]
@@ -990,25 +2011,37 @@ pub mod vanilla_entities {
         pub keys: ResolvableString,
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "swapBlock"]
     pub struct SwapBlock {
         #[name = "theme"]
         pub theme: Option<ResolvableString>,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "movingPlatform"]
     pub struct MovingPlatform {
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "switchGate"]
     pub struct SwitchGate {
         #[name = "persistent"]
@@ -1017,18 +2050,26 @@ pub mod vanilla_entities {
         pub sprite: Option<ResolvableString>,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "blackGem"]
     pub struct BlackGem {
         #[name = "removeCameraTriggers"]
         pub removecameratriggers: Option<bool>,
         #[name = "fake"]
         pub fake: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "SummitBackgroundManager"]
     pub struct SummitBackgroundManager {
         #[name = "index"]
@@ -1041,18 +2082,26 @@ pub mod vanilla_entities {
         pub dark: Option<bool>,
         #[name = "ambience"]
         pub ambience: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "templeMirror"]
     pub struct TempleMirror {
         #[name = "reflectX"]
         pub reflectx: Integer,
         #[name = "reflectY"]
         pub reflecty: u8,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "moveBlock"]
     pub struct MoveBlock {
         #[name = "direction"]
@@ -1061,9 +2110,13 @@ pub mod vanilla_entities {
         pub cansteer: bool,
         #[name = "fast"]
         pub fast: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "dreamBlock"]
     pub struct DreamBlock {
         #[name = "fastMoving"]
@@ -1074,27 +2127,39 @@ pub mod vanilla_entities {
         pub below: Option<bool>,
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "wallBooster"]
     pub struct WallBooster {
         #[name = "left"]
         pub left: bool,
         #[name = "notCoreMode"]
         pub notcoremode: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "water"]
     pub struct Water {
         #[name = "steamy"]
         pub steamy: bool,
         #[name = "hasBottom"]
         pub hasbottom: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "lightning"]
     pub struct Lightning {
         #[name = "perLevel"]
@@ -1103,43 +2168,63 @@ pub mod vanilla_entities {
         pub movetime: Float,
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "moonCreature"]
     pub struct MoonCreature {
         #[name = "number"]
         pub number: u8,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "finalBossMovingBlock"]
     pub struct FinalBossMovingBlock {
         #[name = "nodeIndex"]
         pub nodeindex: u8,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "rotateSpinner"]
     pub struct RotateSpinner {
         #[name = "clockwise"]
         pub clockwise: bool,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "booster"]
     pub struct Booster {
         #[name = "red"]
         pub red: bool,
         #[name = "ch9_hub_booster"]
         pub ch9_hub_booster: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "bird"]
     pub struct Bird {
         #[name = "mode"]
@@ -1150,23 +2235,35 @@ pub mod vanilla_entities {
         pub onlyifplayerleft: Option<bool>,
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "reflectionHeartStatue"]
     pub struct ReflectionHeartStatue {
-        #[node]
-        pub node: Node,
+        #[node(min = 5, max = 5)]
+        pub nodes: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "yellowBlocks"]
     pub struct YellowBlocks {
         #[name = "inverted"]
         pub inverted: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "towerviewer"]
     pub struct TowerViewer {
         #[name = "summit"]
@@ -1175,53 +2272,81 @@ pub mod vanilla_entities {
         pub onlyy: Option<bool>,
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cobweb"]
     pub struct Cobweb {
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "infiniteStar"]
     pub struct InfiniteStar {
         #[name = "shielded"]
         pub shielded: bool,
         #[name = "singleUse"]
         pub singleuse: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "torch"]
     pub struct Torch {
         #[name = "startLit"]
         pub startlit: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "colorSwitch"]
     pub struct ColorSwitch {
         #[name = "type"]
         pub kind: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cliffflag"]
     pub struct CliffFlag {
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "lamp"]
     pub struct Lamp {
         #[name = "broken"]
         pub broken: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "powerSourceNumber"]
     pub struct PowerSourceNumber {
         #[name = "number"]
@@ -1230,27 +2355,39 @@ pub mod vanilla_entities {
         pub strawberries: ResolvableString,
         #[name = "keys"]
         pub keys: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "bridge"]
     pub struct Bridge {
-        #[node]
-        pub node: Node,
+        #[node(min = 2, max = 2)]
+        pub nodes: Vec<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "crushBlock"]
     pub struct CrushBlock {
         #[name = "axes"]
         pub axes: ResolvableString,
         #[name = "chillout"]
         pub chillout: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "trackSpinner"]
     pub struct TrackSpinner {
         #[name = "startCenter"]
@@ -1259,14 +2396,21 @@ pub mod vanilla_entities {
         pub speed: ResolvableString,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, EntityData)]
+    #[derive(Debug, Clone, PartialEq, EntityData)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "gondola"]
     pub struct Gondola {
         #[name = "active"]
         pub active: bool,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 }
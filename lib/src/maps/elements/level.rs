@@ -1,20 +1,25 @@
 use celeste_rs_macros::MapElement;
 
 use crate::{
-    maps::{DynMapElement, MapElement, ResolvableString},
+    maps::{DynMapElement, ExtraData, MapElement, ResolvableString},
     utils::num::{Float, Integer},
 };
 
 use super::entities::DynEntity;
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "levels"]
 pub struct Levels {
     #[child]
     pub levels: Vec<Level>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "level"]
 pub struct Level {
     #[name = "name"]
@@ -85,8 +90,12 @@ pub struct Level {
     pub bg: Background,
     #[child]
     pub objtiles: Option<ObjTiles>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "triggers"]
 pub struct Triggers {
     #[name = "offsetX"]
@@ -95,9 +104,13 @@ pub struct Triggers {
     pub offset_y: Option<Float>,
     #[dyn_child]
     pub triggers: Vec<DynMapElement>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "fgtiles"]
 pub struct FGTiles {
     #[name = "offsetX"]
@@ -110,9 +123,13 @@ pub struct FGTiles {
     pub export_mode: Option<Integer>,
     #[name = "innerText"]
     pub inner_text: Option<String>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "bgtiles"]
 pub struct BGTiles {
     #[name = "offsetX"]
@@ -123,9 +140,13 @@ pub struct BGTiles {
     pub tileset: Option<ResolvableString>,
     #[name = "exportMode"]
     pub export_mode: Option<Integer>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "bgdecals"]
 pub struct BGDecals {
     #[name = "offsetX"]
@@ -134,10 +155,14 @@ pub struct BGDecals {
     pub offset_y: Option<Float>,
     #[child]
     pub decals: Vec<Decal>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "fgdecals"]
 pub struct FGDecals {
     #[name = "offsetX"]
@@ -146,9 +171,13 @@ pub struct FGDecals {
     pub offset_y: Option<Float>,
     #[child]
     pub decals: Vec<Decal>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Clone, Debug, MapElement)]
+#[derive(Clone, PartialEq, Debug, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "decal"]
 pub struct Decal {
     #[name = "x"]
@@ -163,9 +192,13 @@ pub struct Decal {
     pub rotation: Option<Float>,
     #[name = "texture"]
     pub texture: ResolvableString,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "bg"]
 pub struct Background {
     #[name = "offsetX"]
@@ -175,9 +208,13 @@ pub struct Background {
     #[name = "innerText"]
     #[rle]
     pub inner_text: Option<String>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "objtiles"]
 pub struct ObjTiles {
     #[name = "offsetX"]
@@ -190,9 +227,13 @@ pub struct ObjTiles {
     pub export_mode: Option<Integer>,
     #[name = "innerText"]
     pub inner_text: Option<String>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "solids"]
 pub struct Solids {
     #[name = "offsetX"]
@@ -202,9 +243,13 @@ pub struct Solids {
     #[name = "innerText"]
     #[rle]
     pub inner_text: Option<String>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "entities"]
 pub struct Entities {
     #[name = "offsetX"]
@@ -213,4 +258,7 @@ pub struct Entities {
     pub offset_y: Option<Float>,
     #[dyn_entities]
     pub entities: Vec<DynEntity>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
@@ -1,7 +1,10 @@
+use std::{error::Error, fmt::Display};
+
 use celeste_rs_macros::MapElement;
 
 use crate::{
     maps::{
+        ExtraData,
         MapElement,
         MapManager,
         entities::add_entity_parsers,
@@ -37,15 +40,14 @@ impl MapManager {
         self.add_parser::<Entities>();
         self.add_parser::<Backgrounds>();
         self.add_parser::<Foregrounds>();
-        self.add_parser::<Parallax>();
-        self.add_parser::<SnowBG>();
-        self.add_parser::<SnowFG>();
+        add_effect_parsers(self);
         add_entity_parsers(self);
         add_trigger_parsers(self);
     }
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "Map"]
 pub struct MapRoot {
     #[child]
@@ -54,16 +56,24 @@ pub struct MapRoot {
     pub levels: Levels,
     #[child]
     pub style: Styles,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "Filler"]
 pub struct Filler {
     #[child]
     pub filler: Vec<Rect>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, Clone, Copy, MapElement)]
+#[derive(Debug, Clone, Copy, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "rect"]
 pub struct Rect {
     #[name = "x"]
@@ -76,11 +86,160 @@ pub struct Rect {
     pub h: Integer,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "node"]
 pub struct Node {
     #[name = "x"]
     pub x: Float,
     #[name = "y"]
     pub y: Float,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+impl Node {
+    /// Creates a node at a position relative to the room, the same as the entity's position
+    pub fn new(x: impl Into<Float>, y: impl Into<Float>) -> Self {
+        Node {
+            x: x.into(),
+            y: y.into(),
+            extra: ExtraData::default(),
+        }
+    }
+}
+
+/// An error changing the nodes of an entity or trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeError {
+    TooFewNodes {
+        min: usize,
+        found: usize,
+    },
+    TooManyNodes {
+        max: usize,
+        found: usize,
+    },
+    /// The index of a node was past the end of the node list
+    OutOfBounds {
+        index: usize,
+        len: usize,
+    },
+}
+
+impl Display for NodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeError::TooFewNodes { min, found } =>
+                write!(f, "Expected at least {min} nodes, found {found}"),
+            NodeError::TooManyNodes { max, found } =>
+                write!(f, "Expected at most {max} nodes, found {found}"),
+            NodeError::OutOfBounds { index, len } =>
+                write!(f, "Node index {index} is out of bounds for {len} nodes"),
+        }
+    }
+}
+
+impl Error for NodeError {}
+
+/// Editing the nodes of an entity or trigger
+///
+/// Only [nodes](NodeList::nodes), [nodes_mut](NodeList::nodes_mut) and [set_nodes](NodeList::set_nodes)
+/// need to be implemented, every other change goes through `set_nodes` so the node count is always checked.
+///
+/// Only the nodes in a `#[node]` field are part of the list. Elements without one don't allow any nodes,
+/// so nodes read for them are kept in their `#[extra]` field instead. Those are still encoded,
+/// but they aren't returned by [nodes](NodeList::nodes) and can't be edited through this trait.
+pub trait NodeList {
+    /// The nodes, in pixels relative to the room like the element's own position
+    fn nodes(&self) -> &[Node];
+
+    fn nodes_mut(&mut self) -> &mut [Node];
+
+    /// Replaces all the nodes, failing if there are too few or too many
+    fn set_nodes(&mut self, nodes: Vec<Node>) -> Result<(), NodeError>;
+
+    /// The fewest and most nodes allowed, `None` if there is no limit
+    fn node_limits(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
+    /// Adds a node to the end of the path
+    fn add_node(&mut self, node: Node) -> Result<(), NodeError> {
+        let len = self.nodes().len();
+        self.insert_node(len, node)
+    }
+
+    /// Inserts a node at `index`, shifting the nodes after it along
+    fn insert_node(&mut self, index: usize, node: Node) -> Result<(), NodeError> {
+        let mut nodes = self.nodes().to_vec();
+
+        if index > nodes.len() {
+            return Err(NodeError::OutOfBounds {
+                index,
+                len: nodes.len(),
+            });
+        }
+
+        nodes.insert(index, node);
+        self.set_nodes(nodes)
+    }
+
+    /// Removes the node at `index`
+    fn remove_node(&mut self, index: usize) -> Result<Node, NodeError> {
+        let mut nodes = self.nodes().to_vec();
+
+        if index >= nodes.len() {
+            return Err(NodeError::OutOfBounds {
+                index,
+                len: nodes.len(),
+            });
+        }
+
+        let node = nodes.remove(index);
+        self.set_nodes(nodes)?;
+        Ok(node)
+    }
+
+    /// Moves the node at `from` to `to`, keeping the order of the other nodes
+    fn move_node(&mut self, from: usize, to: usize) -> Result<(), NodeError> {
+        let nodes = self.nodes_mut();
+        let len = nodes.len();
+
+        for index in [from, to] {
+            if index >= len {
+                return Err(NodeError::OutOfBounds { index, len });
+            }
+        }
+
+        if from < to {
+            nodes[from ..= to].rotate_left(1);
+        } else {
+            nodes[to ..= from].rotate_right(1);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that there is an allowed number of nodes
+    fn validate_nodes(&self) -> Result<(), NodeError> {
+        let (min, max) = self.node_limits();
+        check_node_count(self.nodes().len(), min, max)
+    }
+}
+
+/// Checks that a node count is within the `MIN_NODES` and `MAX_NODES` of an entity or trigger
+pub fn check_node_count(count: usize, min: usize, max: Option<usize>) -> Result<(), NodeError> {
+    if count < min {
+        return Err(NodeError::TooFewNodes { min, found: count });
+    }
+
+    if let Some(max) = max
+        && count > max
+    {
+        return Err(NodeError::TooManyNodes { max, found: count });
+    }
+
+    Ok(())
 }
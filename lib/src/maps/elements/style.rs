@@ -1,70 +1,119 @@
 use celeste_rs_macros::MapElement;
 
 use crate::{
-    maps::{MapElement, MapElementParsingError, MapEncoder, MapParser, ResolvableString},
-    utils::num::Float,
+    maps::{DynMapElement, ExtraData, MapElement, MapManager, ResolvableString},
+    utils::num::{Float, Integer},
 };
 
-#[derive(Debug, MapElement)]
+/// Adds parsers for all the vanilla stylegrounds to the [MapManager]
+///
+/// These are added as [effect parsers](MapManager::add_effect_parser), so [TentaclesEffect] doesn't conflict with the tentacles entity.
+pub fn add_effect_parsers(mm: &mut MapManager) {
+    mm.add_effect_parser::<Apply>();
+    mm.add_effect_parser::<Parallax>();
+    mm.add_effect_parser::<SnowBG>();
+    mm.add_effect_parser::<SnowFG>();
+    mm.add_effect_parser::<Stars>();
+    mm.add_effect_parser::<WindSnow>();
+    mm.add_effect_parser::<DreamStars>();
+    mm.add_effect_parser::<MirrorFG>();
+    mm.add_effect_parser::<ReflectionFG>();
+    mm.add_effect_parser::<Godrays>();
+    mm.add_effect_parser::<TentaclesEffect>();
+    mm.add_effect_parser::<NorthernLights>();
+    mm.add_effect_parser::<BossStarField>();
+    mm.add_effect_parser::<Petals>();
+    mm.add_effect_parser::<HeatWave>();
+    mm.add_effect_parser::<CoreStarsFG>();
+    mm.add_effect_parser::<StarField>();
+    mm.add_effect_parser::<Planets>();
+    mm.add_effect_parser::<Rain>();
+    mm.add_effect_parser::<StarDust>();
+    mm.add_effect_parser::<BlackholeBG>();
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "Style"]
 pub struct Styles {
     #[child]
     pub background: Backgrounds,
     #[child]
     pub foreground: Foregrounds,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "Backgrounds"]
 pub struct Backgrounds {
-    pub parallax_elements: Vec<Parallax>,
-    pub snow_bg: bool,
-}
-
-impl MapElement for Backgrounds {
-    const NAME: &'static str = "Backgrounds";
-
-    fn from_raw(parser: MapParser) -> Result<Self, MapElementParsingError> {
-        Ok(Self {
-            parallax_elements: parser.parse_all_elements()?,
-            snow_bg: parser.parse_element::<SnowBG>().is_ok(),
-        })
-    }
-
-    fn to_raw(&self, encoder: &mut MapEncoder) {
-        encoder.children(&self.parallax_elements);
-
-        if self.snow_bg {
-            encoder.child(&SnowBG);
-        }
-    }
+    /// The stylegrounds in the order they're drawn
+    ///
+    /// These are [Parallax], [Apply] or effect elements, any modded effects without a parser are kept as [RawMapElement](crate::maps::RawMapElement)s.
+    #[dyn_stylegrounds]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::maps::serialize::deserialize_stylegrounds")
+    )]
+    pub stylegrounds: Vec<DynMapElement>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "Foregrounds"]
 pub struct Foregrounds {
-    pub parallax_elements: Vec<Parallax>,
-    pub snow_fg: bool,
+    /// The stylegrounds in the order they're drawn
+    ///
+    /// These are [Parallax], [Apply] or effect elements, any modded effects without a parser are kept as [RawMapElement](crate::maps::RawMapElement)s.
+    #[dyn_stylegrounds]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::maps::serialize::deserialize_stylegrounds")
+    )]
+    pub stylegrounds: Vec<DynMapElement>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-impl MapElement for Foregrounds {
-    const NAME: &'static str = "Foregrounds";
-
-    fn from_raw(parser: MapParser) -> Result<Self, MapElementParsingError> {
-        Ok(Self {
-            parallax_elements: parser.parse_all_elements()?,
-            snow_fg: parser.parse_element::<SnowFG>().is_ok(),
-        })
-    }
-
-    fn to_raw(&self, encoder: &mut MapEncoder) {
-        if self.snow_fg {
-            encoder.child(&SnowFG);
-        }
-
-        encoder.children(&self.parallax_elements);
-    }
+/// A group of stylegrounds that share attributes
+///
+/// Any attribute on the group is used by the stylegrounds inside it that don't set it themselves.
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "apply"]
+pub struct Apply {
+    /// The rooms the stylegrounds are shown in
+    #[name = "only"]
+    pub only: Option<ResolvableString>,
+    /// The rooms the stylegrounds are hidden in
+    #[name = "exclude"]
+    pub exclude: Option<ResolvableString>,
+    /// The session flag that needs to be set for the stylegrounds to show
+    #[name = "flag"]
+    pub flag: Option<ResolvableString>,
+    /// The session flag that hides the stylegrounds when set
+    #[name = "notflag"]
+    pub not_flag: Option<ResolvableString>,
+    /// The stylegrounds in the group, in the order they're drawn
+    #[dyn_stylegrounds]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::maps::serialize::deserialize_stylegrounds")
+    )]
+    pub stylegrounds: Vec<DynMapElement>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "parallax"]
 pub struct Parallax {
     #[name = "blendmode"]
@@ -91,12 +140,194 @@ pub struct Parallax {
     pub color: Option<ResolvableString>,
     #[name = "alpha"]
     pub alpha: Option<Float>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "stars"]
+pub struct Stars {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "windsnow"]
+pub struct WindSnow {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "dreamstars"]
+pub struct DreamStars {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "mirrorfg"]
+pub struct MirrorFG {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "reflectionfg"]
+pub struct ReflectionFG {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "godrays"]
+pub struct Godrays {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "tentacles"]
+pub struct TentaclesEffect {
+    #[name = "side"]
+    pub side: Option<ResolvableString>,
+    #[name = "color"]
+    pub color: Option<ResolvableString>,
+    #[name = "offset"]
+    pub offset: Option<Float>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
 }
 
-#[derive(Debug, Clone, Copy, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "northernlights"]
+pub struct NorthernLights {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "bossStarField"]
+pub struct BossStarField {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "petals"]
+pub struct Petals {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "heatwave"]
+pub struct HeatWave {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "coreStarsFg"]
+pub struct CoreStarsFG {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "starfield"]
+pub struct StarField {
+    #[name = "color"]
+    pub color: Option<ResolvableString>,
+    #[name = "speed"]
+    pub speed: Option<Float>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "planets"]
+pub struct Planets {
+    #[name = "count"]
+    pub count: Option<Integer>,
+    #[name = "size"]
+    pub size: Option<ResolvableString>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "rain"]
+pub struct Rain {
+    #[name = "color"]
+    pub color: Option<ResolvableString>,
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "stardust"]
+pub struct StarDust {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[name = "blackhole"]
+pub struct BlackholeBG {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
+
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "snowBg"]
-pub struct SnowBG;
+pub struct SnowBG {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
 
-#[derive(Debug, Clone, Copy, MapElement)]
+#[derive(Debug, Clone, PartialEq, MapElement)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[name = "snowFg"]
-pub struct SnowFG;
+pub struct SnowFG {
+    #[extra]
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: ExtraData,
+}
@@ -4,17 +4,23 @@ use celeste_rs_macros::Trigger;
 
 use crate::{
     maps::{
+        ExtraData,
         MapElement,
         MapManager,
         Node,
+        NodeError,
+        NodeList,
         ResolvableString,
+        check_node_count,
         encoder::MapEncoder,
         parser::{MapElementParsingError, MapParser},
-        var_types::Character,
+        reflect::{FieldError, FieldInfo, FieldKind, optional_field, required_field},
+        var_types::{Character, EncodedVar},
     },
     utils::num::{Float, Integer},
 };
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// The metadata related to all triggers
 pub struct MapTrigger<T: Trigger> {
     pub id: Integer,
@@ -54,14 +60,111 @@ impl<T: Trigger> MapElement for MapTrigger<T> {
         encoder.optional_attribute("originY", &self.origin_y);
         self.entity.to_raw(encoder);
     }
+
+    fn field_info(&self) -> Vec<FieldInfo> {
+        let mut fields = vec![
+            FieldInfo::new("id", "id", FieldKind::Integer, false),
+            FieldInfo::new("x", "x", FieldKind::Float, false),
+            FieldInfo::new("y", "y", FieldKind::Float, false),
+            FieldInfo::new("width", "width", FieldKind::Integer, true),
+            FieldInfo::new("height", "height", FieldKind::Integer, true),
+            FieldInfo::new("origin_x", "originX", FieldKind::Float, true),
+            FieldInfo::new("origin_y", "originY", FieldKind::Float, true),
+        ];
+        fields.extend(self.entity.field_info());
+        fields
+    }
+
+    fn get_field(&self, name: &str) -> Option<EncodedVar> {
+        match name {
+            "id" => Some(self.id.into()),
+            "x" => Some(self.x.into()),
+            "y" => Some(self.y.into()),
+            "width" => self.width.map(EncodedVar::from),
+            "height" => self.height.map(EncodedVar::from),
+            "originX" => self.origin_x.map(EncodedVar::from),
+            "originY" => self.origin_y.map(EncodedVar::from),
+            _ => self.entity.get_field(name),
+        }
+    }
+
+    fn set_field(&mut self, name: &str, value: Option<EncodedVar>) -> Result<(), FieldError> {
+        match name {
+            "id" => self.id = required_field(name, value)?,
+            "x" => self.x = required_field(name, value)?,
+            "y" => self.y = required_field(name, value)?,
+            "width" => self.width = optional_field(name, value)?,
+            "height" => self.height = optional_field(name, value)?,
+            "originX" => self.origin_x = optional_field(name, value)?,
+            "originY" => self.origin_y = optional_field(name, value)?,
+            _ => return self.entity.set_field(name, value),
+        }
+
+        Ok(())
+    }
 }
 
-pub trait Trigger: Debug + Any {
+impl<T: Trigger> NodeList for MapTrigger<T> {
+    fn nodes(&self) -> &[Node] {
+        self.entity.nodes()
+    }
+
+    fn nodes_mut(&mut self) -> &mut [Node] {
+        self.entity.nodes_mut()
+    }
+
+    fn set_nodes(&mut self, nodes: Vec<Node>) -> Result<(), NodeError> {
+        self.entity.set_nodes(nodes)
+    }
+
+    fn node_limits(&self) -> (usize, Option<usize>) {
+        (T::MIN_NODES, T::MAX_NODES)
+    }
+}
+
+pub trait Trigger: Debug + Any + Clone + PartialEq {
     const NAME: &'static str;
 
     fn from_raw(parser: MapParser) -> Result<Self, MapElementParsingError>
     where Self: Sized;
     fn to_raw(&self, encoder: &mut MapEncoder);
+
+    /// The attribute fields of the trigger, see [reflect](crate::maps::reflect)
+    fn field_info(&self) -> Vec<FieldInfo> {
+        Vec::new()
+    }
+
+    /// Gets the value of an attribute by name, `None` if it isn't set
+    fn get_field(&self, _name: &str) -> Option<EncodedVar> {
+        None
+    }
+
+    /// Sets the value of an attribute by name, unsetting it if `value` is `None`
+    fn set_field(&mut self, name: &str, _value: Option<EncodedVar>) -> Result<(), FieldError> {
+        Err(FieldError::UnknownField(name.to_owned()))
+    }
+
+    /// The fewest nodes the trigger can have
+    const MIN_NODES: usize = 0;
+    /// The most nodes the trigger can have, `None` if there's no limit
+    ///
+    /// This is `Some(0)` when there's no `#[node]` field, any nodes read for the trigger are kept
+    /// in its `#[extra]` field and aren't seen by [NodeList].
+    const MAX_NODES: Option<usize> = Some(0);
+
+    /// The nodes of the trigger, in pixels relative to the room
+    fn nodes(&self) -> &[Node] {
+        &[]
+    }
+
+    fn nodes_mut(&mut self) -> &mut [Node] {
+        &mut []
+    }
+
+    /// Replaces the nodes of the trigger, failing if there are too few or too many
+    fn set_nodes(&mut self, nodes: Vec<Node>) -> Result<(), NodeError> {
+        check_node_count(nodes.len(), Self::MIN_NODES, Self::MAX_NODES)
+    }
 }
 
 
@@ -87,6 +190,7 @@ pub fn add_trigger_parsers(mm: &mut MapManager) {
     mm.add_trigger_parser::<EventTrigger>();
     mm.add_trigger_parser::<DetachFollowersTrigger>();
     mm.add_trigger_parser::<CheckpointBlockerTrigger>();
+    mm.add_trigger_parser::<StopBoostTrigger>();
     mm.add_trigger_parser::<AltMusicTrigger>();
     mm.add_trigger_parser::<WindTrigger>();
     mm.add_trigger_parser::<CreditsTrigger>();
@@ -103,11 +207,17 @@ pub mod vanilla_triggers {
 
     use super::*;
 
-    #[derive(Debug, Trigger, Default, Clone, Copy, PartialEq, Eq)]
+    #[derive(Debug, Clone, PartialEq, Default, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "lookoutBlocker"]
-    pub struct LookoutBlocker;
+    pub struct LookoutBlocker {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "musicTrigger"]
     pub struct MusicTrigger {
         #[name = "track"]
@@ -120,35 +230,56 @@ pub mod vanilla_triggers {
         pub resetonleave: bool,
         #[name = "progress"]
         pub progress: Option<u8>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
-    #[derive(Debug, Trigger, Default, Clone, Copy, PartialEq, Eq)]
+    #[derive(Debug, Clone, PartialEq, Default, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "birdPathTrigger"]
-    pub struct BirdPathTrigger;
+    pub struct BirdPathTrigger {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "blackholeStrength"]
     pub struct BlackholeStrength {
         #[name = "strength"]
         pub strength: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cameraOffsetTrigger"]
     pub struct CameraOffsetTrigger {
         #[name = "cameraX"]
         pub camerax: Float,
         #[name = "cameraY"]
         pub cameray: Float,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "changeRespawnTrigger"]
     pub struct ChangeRespawnTrigger {
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "minitextboxTrigger"]
     pub struct MiniTextboxTrigger {
         #[name = "mode"]
@@ -159,23 +290,35 @@ pub mod vanilla_triggers {
         pub only_once: bool,
         #[name = "death_count"]
         pub death_count: Integer,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "respawnTargetTrigger"]
     pub struct RespawnTargetTrigger {
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "noRefillTrigger"]
     pub struct NoRefillTrigger {
         #[name = "state"]
         pub state: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "bloomFadeTrigger"]
     pub struct BloomFadeTrigger {
         #[name = "bloomAddFrom"]
@@ -184,9 +327,13 @@ pub mod vanilla_triggers {
         pub bloomaddto: Float,
         #[name = "positionMode"]
         pub positionmode: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cameraTargetTrigger"]
     pub struct CameraTargetTrigger {
         #[name = "lerpStrength"]
@@ -201,9 +348,13 @@ pub mod vanilla_triggers {
         pub deleteflag: Option<Character>,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "musicFadeTrigger"]
     pub struct MusicFadeTrigger {
         #[name = "direction"]
@@ -214,12 +365,21 @@ pub mod vanilla_triggers {
         pub fadeb: Float,
         #[name = "parameter"]
         pub parameter: Option<ResolvableString>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
-    #[derive(Debug, Trigger, Default, Clone, Copy, PartialEq, Eq)]
+    #[derive(Debug, Clone, PartialEq, Default, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "goldenBerryCollectTrigger"]
-    pub struct GoldenBerryCollectTrigger;
+    pub struct GoldenBerryCollectTrigger {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "cameraAdvanceTargetTrigger"]
     pub struct CameraAdvanceTargetTrigger {
         #[name = "lerpStrengthX"]
@@ -236,9 +396,13 @@ pub mod vanilla_triggers {
         pub yonly: bool,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "lightFadeTrigger"]
     pub struct LightFadeTrigger {
         #[name = "lightAddFrom"]
@@ -247,19 +411,32 @@ pub mod vanilla_triggers {
         pub lightaddto: Float,
         #[name = "positionMode"]
         pub positionmode: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "spawnFacingTrigger"]
     pub struct SpawnFacingTrigger {
         #[name = "facing"]
         pub facing: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
-    #[derive(Debug, Trigger, Default, Clone, Copy, PartialEq, Eq)]
+    #[derive(Debug, Clone, PartialEq, Default, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "windAttackTrigger"]
-    pub struct WindAttackTrigger;
+    pub struct WindAttackTrigger {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "ambienceParamTrigger"]
     pub struct AmbienceParamTrigger {
         #[name = "direction"]
@@ -270,54 +447,92 @@ pub mod vanilla_triggers {
         pub from: Float,
         #[name = "to"]
         pub to: Float,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "eventTrigger"]
     pub struct EventTrigger {
         #[name = "event"]
         pub event: ResolvableString,
         #[name = "onSpawn"]
         pub onspawn: Option<bool>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "detachFollowersTrigger"]
     pub struct DetachFollowersTrigger {
         #[name = "global"]
         pub global: bool,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger, Default, Clone, Copy, PartialEq, Eq)]
+    #[derive(Debug, Clone, PartialEq, Default, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "checkpointBlockerTrigger"]
-    pub struct CheckpointBlockerTrigger;
+    pub struct CheckpointBlockerTrigger {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[name = "stopBoostTrigger"]
+    pub struct StopBoostTrigger {
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
+    }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "altMusicTrigger"]
     pub struct AltMusicTrigger {
         #[name = "track"]
         pub track: ResolvableString,
         #[name = "resetOnLeave"]
         pub resetonleave: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "windTrigger"]
     pub struct WindTrigger {
         #[name = "pattern"]
         pub pattern: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "creditsTrigger"]
     pub struct CreditsTrigger {
         #[name = "event"]
         pub event: ResolvableString,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "rumbleTrigger"]
     pub struct RumbleTrigger {
         #[name = "manualTrigger"]
@@ -326,16 +541,24 @@ pub mod vanilla_triggers {
         pub persistent: bool,
         #[node]
         pub node: Node,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "oshiroTrigger"]
     pub struct OshiroTrigger {
         #[name = "state"]
         pub state: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "moonGlitchBackgroundTrigger"]
     pub struct MoonGlitchBackgroundTrigger {
         #[name = "duration"]
@@ -344,9 +567,13 @@ pub mod vanilla_triggers {
         pub stay: bool,
         #[name = "glitch"]
         pub glitch: bool,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 
-    #[derive(Debug, Trigger)]
+    #[derive(Debug, Clone, PartialEq, Trigger)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[name = "interactTrigger"]
     pub struct InteractTrigger {
         #[name = "event"]
@@ -357,5 +584,8 @@ pub mod vanilla_triggers {
         pub event_3: ResolvableString,
         #[node]
         pub node: Option<Node>,
+        #[extra]
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub extra: ExtraData,
     }
 }
@@ -1,5 +1,6 @@
 use crate::maps::{
     ErasedMapElement,
+    ExtraData,
     LookupTable,
     MapAttribute,
     RawMapElement,
@@ -75,9 +76,25 @@ impl MapEncoder<'_> {
         }
     }
 
+    /// Pushes the attributes and children stored in an [ExtraData] onto the raw element
+    ///
+    /// Children are put back at their [original position](ExtraData::child_positions), so this needs to be called
+    /// after every other child is encoded.
+    pub fn extra(&mut self, extra: &ExtraData) {
+        self.attrs.extend_from_slice(&extra.attributes);
+
+        for (i, child) in extra.children.iter().enumerate() {
+            match extra.child_positions.get(i) {
+                Some(&position) if position < self.children.len() =>
+                    self.children.insert(position, child.clone()),
+                _ => self.children.push(child.clone()),
+            }
+        }
+    }
+
     /// Forks the current encoder to create a new [RawMapElement]
     #[doc(hidden)]
-    pub fn fork(&mut self, name: ResolvableString) -> MapEncoder {
+    pub fn fork(&mut self, name: ResolvableString) -> MapEncoder<'_> {
         MapEncoder {
             lookup: self.lookup,
             element_name: name,
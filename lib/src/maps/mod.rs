@@ -1,10 +1,14 @@
 //! Implements reading and writing of Celeste's map format
 //! along with providing helper structs for all the map elements seen in the vanilla game
+//!
+//! With the `serde` feature enabled, parsed elements can be serialized with [serde](https://serde.rs).
 use std::{
     any::Any,
+    cell::RefCell,
     collections::HashMap,
     fmt::{Debug, Display},
     io::{Read, Write},
+    sync::Arc,
 };
 
 pub mod elements;
@@ -12,22 +16,41 @@ pub mod encoder;
 pub mod lookup;
 pub mod parser;
 pub mod reader;
+pub mod reflect;
+pub mod rooms;
+pub mod schema;
+pub mod selector;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod stream;
+pub mod transform;
 pub mod var_types;
+pub mod visitor;
 pub mod writer;
 use elements::*;
 pub use lookup::*;
 
 use crate::maps::{
     encoder::MapEncoder,
-    entities::{EntityData, MapEntity},
-    parser::{ElementParser, ElementParserImpl, MapElementParsingError, MapParser},
+    entities::{DynEntity, EntityData, MapEntity},
+    parser::{
+        ElementParser,
+        ElementParserImpl,
+        EntityParser,
+        MapElementParsingError,
+        MapParser,
+        ParseWarning,
+    },
     reader::{MapReadError, MapReader},
+    reflect::{FieldError, FieldInfo},
+    schema::{EntitySchema, SchemaParser},
     triggers::{MapTrigger, Trigger},
     var_types::EncodedVar,
+    visitor::{ChildElement, ChildElementMut},
     writer::{MapWriteError, MapWriter},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 /// The raw structure of an element in the map binary
 ///
 /// All elements get parsed to this before being parsed into proper structs.
@@ -37,7 +60,66 @@ pub struct RawMapElement {
     pub children: Vec<RawMapElement>,
 }
 
-#[derive(Debug, Clone)]
+/// The attributes and children of an element that a typed struct doesn't have fields for
+///
+/// Stored in [`#[extra]`](celeste_rs_macros::MapElement) fields so that encoding a parsed element doesn't lose any data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExtraData {
+    pub attributes: Vec<MapAttribute>,
+    pub children: Vec<RawMapElement>,
+    /// The index each of the `children` had in the element it was parsed from
+    ///
+    /// Used to put the children back in their original place when encoding,
+    /// any children past the end of this list are written after the known children.
+    pub child_positions: Vec<usize>,
+}
+
+impl ExtraData {
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.children.is_empty()
+    }
+
+    /// Gets the value of an extra attribute by name
+    pub fn get_attribute(&self, name: &str) -> Option<&EncodedVar> {
+        self.attributes
+            .iter()
+            .find(|a| a.name.as_str() == Some(name))
+            .map(|a| &a.value)
+    }
+
+    /// Sets an extra attribute, adding it if it doesn't exist
+    pub fn set_attribute(&mut self, name: &str, value: impl Into<EncodedVar>) {
+        set_attribute(&mut self.attributes, name, value.into())
+    }
+
+    /// Removes an extra attribute, returning its value
+    pub fn remove_attribute(&mut self, name: &str) -> Option<EncodedVar> {
+        remove_attribute(&mut self.attributes, name)
+    }
+}
+
+fn set_attribute(attributes: &mut Vec<MapAttribute>, name: &str, value: EncodedVar) {
+    match attributes
+        .iter_mut()
+        .find(|a| a.name.as_str() == Some(name))
+    {
+        Some(attr) => attr.value = value,
+        None => attributes.push(MapAttribute::new(
+            ResolvableString::String(name.to_owned()),
+            value,
+        )),
+    }
+}
+
+fn remove_attribute(attributes: &mut Vec<MapAttribute>, name: &str) -> Option<EncodedVar> {
+    let index = attributes
+        .iter()
+        .position(|a| a.name.as_str() == Some(name))?;
+
+    Some(attributes.remove(index).value)
+}
+
+#[derive(Debug, Clone, PartialEq)]
 /// An attribute attached to a map element
 pub struct MapAttribute {
     pub name: ResolvableString,
@@ -139,6 +221,30 @@ impl Display for RawMap {
 }
 
 impl RawMapElement {
+    /// Gets the value of an attribute by name
+    ///
+    /// The attribute names need to be resolved, which is always the case with [MapManager].
+    pub fn get_attribute(&self, name: &str) -> Option<&EncodedVar> {
+        self.attributes
+            .iter()
+            .find(|a| a.name.as_str() == Some(name))
+            .map(|a| &a.value)
+    }
+
+    /// Sets an attribute, adding it if it doesn't exist
+    ///
+    /// The attribute names need to be resolved.
+    pub fn set_attribute(&mut self, name: &str, value: impl Into<EncodedVar>) {
+        set_attribute(&mut self.attributes, name, value.into())
+    }
+
+    /// Removes an attribute, returning its value
+    ///
+    /// The attribute names need to be resolved.
+    pub fn remove_attribute(&mut self, name: &str) -> Option<EncodedVar> {
+        remove_attribute(&mut self.attributes, name)
+    }
+
     fn to_string(&self, depth: u8, lookup_table: &LookupTable) -> String {
         let mut buf = String::new();
 
@@ -264,13 +370,42 @@ impl MapAttribute {
 }
 
 /// A trait to represent an element of a map.
-pub trait MapElement: Any + Debug {
+///
+/// Elements need to be [Clone] and [PartialEq] so that whole maps, including their [DynMapElement]s, can be cloned and compared.
+pub trait MapElement: Any + Debug + Clone + PartialEq {
     /// The name of the element in the map binary
     const NAME: &'static str;
 
     fn from_raw(parser: MapParser) -> Result<Self, MapElementParsingError>
     where Self: Sized;
     fn to_raw(&self, encoder: &mut MapEncoder);
+
+    /// The elements directly under this one, used by [visitors](visitor::MapVisitor) to walk the tree
+    ///
+    /// Elements without children don't need to implement this.
+    fn child_elements(&self) -> Vec<ChildElement<'_>> {
+        Vec::new()
+    }
+
+    /// Mutable version of [child_elements](MapElement::child_elements)
+    fn child_elements_mut(&mut self) -> Vec<ChildElementMut<'_>> {
+        Vec::new()
+    }
+
+    /// The attribute fields of the element, see [reflect]
+    fn field_info(&self) -> Vec<FieldInfo> {
+        Vec::new()
+    }
+
+    /// Gets the value of an attribute by name, `None` if it isn't set
+    fn get_field(&self, _name: &str) -> Option<EncodedVar> {
+        None
+    }
+
+    /// Sets the value of an attribute by name, unsetting it if `value` is `None`
+    fn set_field(&mut self, name: &str, _value: Option<EncodedVar>) -> Result<(), FieldError> {
+        Err(FieldError::UnknownField(name.to_owned()))
+    }
 }
 
 /// An object-safe version of [MapElement]
@@ -279,6 +414,15 @@ pub trait ErasedMapElement: Any + Debug {
     fn from_raw(parser: MapParser) -> Result<Self, MapElementParsingError>
     where Self: Sized;
     fn to_raw(&self, encoder: &mut MapEncoder);
+    /// Clones the element into a new box
+    fn clone_dyn(&self) -> DynMapElement;
+    /// Checks if `other` is the same type of element and is equal to this one
+    fn eq_dyn(&self, other: &dyn ErasedMapElement) -> bool;
+    fn child_elements(&self) -> Vec<ChildElement<'_>>;
+    fn child_elements_mut(&mut self) -> Vec<ChildElementMut<'_>>;
+    fn field_info(&self) -> Vec<FieldInfo>;
+    fn get_field(&self, name: &str) -> Option<EncodedVar>;
+    fn set_field(&mut self, name: &str, value: Option<EncodedVar>) -> Result<(), FieldError>;
 }
 
 impl<T: MapElement> ErasedMapElement for T {
@@ -294,6 +438,51 @@ impl<T: MapElement> ErasedMapElement for T {
     fn to_raw(&self, encoder: &mut MapEncoder) {
         self.to_raw(encoder)
     }
+
+    fn clone_dyn(&self) -> DynMapElement {
+        Box::new(self.clone())
+    }
+
+    fn eq_dyn(&self, other: &dyn ErasedMapElement) -> bool {
+        (other as &dyn Any).downcast_ref::<T>() == Some(self)
+    }
+
+    fn child_elements(&self) -> Vec<ChildElement<'_>> {
+        T::child_elements(self)
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<ChildElementMut<'_>> {
+        T::child_elements_mut(self)
+    }
+
+    fn field_info(&self) -> Vec<FieldInfo> {
+        T::field_info(self)
+    }
+
+    fn get_field(&self, name: &str) -> Option<EncodedVar> {
+        T::get_field(self, name)
+    }
+
+    fn set_field(&mut self, name: &str, value: Option<EncodedVar>) -> Result<(), FieldError> {
+        T::set_field(self, name, value)
+    }
+}
+
+impl dyn ErasedMapElement {
+    /// Encodes the element into a standalone [RawMapElement] with all of its strings resolved
+    pub fn encode_raw(&self) -> RawMapElement {
+        let mut lookup = LookupTable::new();
+        let mut encoder = MapEncoder {
+            element_name: lookup.index_string(self.name()),
+            lookup: &mut lookup,
+            children: Vec::new(),
+            attrs: Vec::new(),
+        };
+
+        self.to_raw(&mut encoder);
+
+        encoder.resolve()
+    }
 }
 
 /// A dynamic element, if a parser for the element was registered it will be parsed into that struct, otherwise it is a [RawMapElement]
@@ -314,6 +503,48 @@ impl ErasedMapElement for RawMapElement {
     fn to_raw(&self, encoder: &mut MapEncoder) {
         encoder.from_raw(self)
     }
+
+    fn clone_dyn(&self) -> DynMapElement {
+        Box::new(self.clone())
+    }
+
+    fn eq_dyn(&self, other: &dyn ErasedMapElement) -> bool {
+        (other as &dyn Any).downcast_ref::<Self>() == Some(self)
+    }
+
+    fn child_elements(&self) -> Vec<ChildElement<'_>> {
+        self.children
+            .iter()
+            .map(|c| ChildElement::Child(c))
+            .collect()
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<ChildElementMut<'_>> {
+        self.children
+            .iter_mut()
+            .map(|c| ChildElementMut::Child(c))
+            .collect()
+    }
+
+    /// Raw elements don't have any fields, but their attributes can still be read and written
+    fn field_info(&self) -> Vec<FieldInfo> {
+        Vec::new()
+    }
+
+    fn get_field(&self, name: &str) -> Option<EncodedVar> {
+        self.get_attribute(name).cloned()
+    }
+
+    fn set_field(&mut self, name: &str, value: Option<EncodedVar>) -> Result<(), FieldError> {
+        match value {
+            Some(value) => self.set_attribute(name, value),
+            None => {
+                self.remove_attribute(name);
+            }
+        }
+
+        Ok(())
+    }
 }
 
 
@@ -332,12 +563,56 @@ impl ErasedMapElement for DynMapElement {
     fn to_raw(&self, encoder: &mut MapEncoder) {
         (**self).to_raw(encoder)
     }
+
+    fn clone_dyn(&self) -> DynMapElement {
+        (**self).clone_dyn()
+    }
+
+    fn eq_dyn(&self, other: &dyn ErasedMapElement) -> bool {
+        (**self).eq_dyn(other)
+    }
+
+    fn child_elements(&self) -> Vec<ChildElement<'_>> {
+        (**self).child_elements()
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<ChildElementMut<'_>> {
+        (**self).child_elements_mut()
+    }
+
+    fn field_info(&self) -> Vec<FieldInfo> {
+        (**self).field_info()
+    }
+
+    fn get_field(&self, name: &str) -> Option<EncodedVar> {
+        (**self).get_field(name)
+    }
+
+    fn set_field(&mut self, name: &str, value: Option<EncodedVar>) -> Result<(), FieldError> {
+        (**self).set_field(name, value)
+    }
+}
+
+impl Clone for DynMapElement {
+    fn clone(&self) -> Self {
+        self.as_ref().clone_dyn()
+    }
+}
+
+impl PartialEq for DynMapElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref().eq_dyn(other.as_ref())
+    }
 }
 
 /// A manager struct that can read and write celeste maps.
 pub struct MapManager {
     map: RawMap,
-    parsers: HashMap<&'static str, Box<dyn ElementParserImpl>>,
+    parsers: HashMap<String, Box<dyn ElementParserImpl>>,
+    /// Parsers for stylegrounds, kept apart since some effects have the same name as an entity
+    effect_parsers: HashMap<String, Box<dyn ElementParserImpl>>,
+    lenient: bool,
+    warnings: RefCell<Vec<ParseWarning>>,
 }
 
 impl MapManager {
@@ -353,16 +628,49 @@ impl MapManager {
 
         raw.resolve_strings();
 
-        Ok(MapManager { map: raw, parsers })
+        Ok(MapManager {
+            map: raw,
+            parsers,
+            effect_parsers: HashMap::new(),
+            lenient: false,
+            warnings: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Creates a `MapManager` with an empty map, for when only its parsers are needed
+    pub(crate) fn empty() -> Self {
+        MapManager {
+            map: RawMap {
+                name: String::new(),
+                lookup_table: LookupTable::new(),
+                root_element: RawMapElement {
+                    name: ResolvableString::String(String::new()),
+                    attributes: Vec::new(),
+                    children: Vec::new(),
+                },
+            },
+            parsers: HashMap::new(),
+            effect_parsers: HashMap::new(),
+            lenient: false,
+            warnings: RefCell::new(Vec::new()),
+        }
     }
 
     /// Parse the map passed in the constructor using any registered parsers when needed
+    ///
+    /// In [lenient](Self::set_lenient) mode any warnings from the last parse can be read with [take_warnings](Self::take_warnings).
     pub fn parse_map(&self) -> Result<MapRoot, MapElementParsingError> {
+        self.warnings.borrow_mut().clear();
+
         let parser = MapParser {
             verbose_debug: false,
             lookup: &self.map.lookup_table,
             raw: &self.map.root_element,
             parsers: &self.parsers,
+            effect_parsers: &self.effect_parsers,
+            parent: None,
+            warnings: self.lenient.then_some(&self.warnings),
+            consumed: Default::default(),
         };
 
         parser.parse_self::<MapRoot>()
@@ -371,11 +679,17 @@ impl MapManager {
     /// Same as [parse_map](Self::parse_map) but when compiled with `debug_assertions` will
     /// print debug information about the parser
     pub fn verbose_parse(&self) -> Result<MapRoot, MapElementParsingError> {
+        self.warnings.borrow_mut().clear();
+
         let parser = MapParser {
             verbose_debug: cfg!(debug_assertions),
             lookup: &self.map.lookup_table,
             raw: &self.map.root_element,
             parsers: &self.parsers,
+            effect_parsers: &self.effect_parsers,
+            parent: None,
+            warnings: self.lenient.then_some(&self.warnings),
+            consumed: Default::default(),
         };
 
         parser.parse_self::<MapRoot>()
@@ -415,14 +729,138 @@ impl MapManager {
     /// no `Option<T>` impls are registered by default.
     pub fn add_parser<T: MapElement>(&mut self) {
         self.parsers
-            .insert(T::NAME, Box::new(ElementParser::<T>::new()));
+            .insert(T::NAME.to_owned(), Box::new(ElementParser::<T>::new()));
+    }
+
+    /// Allows the `MapManager` to parse a new type of styleground
+    ///
+    /// All the vanilla stylegrounds can be added via [add_effect_parsers](elements::style::add_effect_parsers).
+    ///
+    /// These are only used by [MapParser::parse_any_styleground], which looks them up before any parser added
+    /// with [add_parser](Self::add_parser), so an effect can share its name with an entity.
+    pub fn add_effect_parser<T: MapElement>(&mut self) {
+        self.effect_parsers
+            .insert(T::NAME.to_owned(), Box::new(ElementParser::<T>::new()));
     }
 
     /// Allows the `MapManager` to parse a new type of [Entity]
     ///
     /// Acts the same as (add_parser)[MapManager::add_parser] but for entities
     pub fn add_entity_parser<T: EntityData>(&mut self) {
-        self.add_parser::<MapEntity<T>>();
+        // Entities need an EntityParser so they get picked up by MapParser::parse_any_entity
+        self.parsers.insert(
+            T::NAME.to_owned(),
+            Box::new(EntityParser::<MapEntity<T>>::new()),
+        );
+    }
+
+    /// Allows the `MapManager` to parse an entity or trigger described at runtime
+    ///
+    /// Matching elements are parsed into [SchemaEntity](schema::SchemaEntity)s, failing if they don't match the schema.<br>
+    /// This replaces any parser already registered for the same name.
+    pub fn add_schema_parser(&mut self, schema: impl Into<Arc<EntitySchema>>) {
+        let parser = SchemaParser::new(schema);
+        self.parsers
+            .insert(parser.element_name().to_owned(), Box::new(parser));
+    }
+
+    /// Whether a parser is registered for elements called `name`
+    ///
+    /// This doesn't include the parsers added with [add_effect_parser](Self::add_effect_parser).
+    pub fn has_parser(&self, name: &str) -> bool {
+        self.parsers.contains_key(name)
+    }
+
+    /// Sets whether to parse in lenient mode, this is off by default
+    ///
+    /// When lenient, dynamic elements and entities that fail to parse are kept as [RawMapElement]s instead of failing
+    /// the whole map, so maps that reuse a name for a different element still load.
+    /// The errors are collected as [ParseWarning]s, read them with [take_warnings](Self::take_warnings).<br>
+    /// Only lists of dynamic elements are lenient, which are the entities, triggers, stylegrounds and `dyn_child` fields
+    /// parsed with [parse_any_entity](MapParser::parse_any_entity), [parse_any_element](MapParser::parse_any_element)
+    /// and [parse_any_styleground](MapParser::parse_any_styleground).
+    /// Elements with a fixed type, like [Level](elements::level::Level)s, still fail to parse.
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Takes the warnings from parsing in lenient mode
+    ///
+    /// These are reset at the start of every [parse_map](Self::parse_map).
+    pub fn take_warnings(&self) -> Vec<ParseWarning> {
+        self.warnings.take()
+    }
+
+    /// Parses a single `T` from a [RawMapElement], using the registered parsers for any dynamic children
+    ///
+    /// The strings in `raw` need to be resolved.
+    pub fn parse_element<T: MapElement>(
+        &self,
+        raw: &RawMapElement,
+    ) -> Result<T, MapElementParsingError> {
+        T::from_raw(self.parser(raw))
+    }
+
+    /// Parses a single element with the registered parsers
+    ///
+    /// Works the same as an element in [MapParser::parse_any_element], the strings in `raw` need to be resolved.
+    pub fn parse_dyn_element(
+        &self,
+        raw: &RawMapElement,
+    ) -> Result<DynMapElement, MapElementParsingError> {
+        let name = raw.name.to_string(&self.map.lookup_table);
+
+        match self.parsers.get(name) {
+            Some(parser) => parser.element_from_raw(self.parser(raw)),
+            None => Ok(Box::new(raw.clone())),
+        }
+    }
+
+    /// Parses a single styleground with the registered parsers
+    ///
+    /// Works the same as an element in [MapParser::parse_any_styleground], so effects are found before
+    /// elements with the same name. The strings in `raw` need to be resolved.
+    pub fn parse_styleground(
+        &self,
+        raw: &RawMapElement,
+    ) -> Result<DynMapElement, MapElementParsingError> {
+        let parser = self.parser(raw);
+
+        match parser.styleground_parser(raw.name.to_string(&self.map.lookup_table)) {
+            Some(styleground) => styleground.element_from_raw(parser),
+            None => Ok(Box::new(raw.clone())),
+        }
+    }
+
+    /// Parses a single entity with the registered parsers
+    ///
+    /// Works the same as an element in [MapParser::parse_any_entity], the strings in `raw` need to be resolved.
+    pub fn parse_entity(&self, raw: &RawMapElement) -> Result<DynEntity, MapElementParsingError> {
+        if let Some(parser) = self.parsers.get(raw.name.to_string(&self.map.lookup_table))
+            && parser.is_entity()
+            && let Some(entity) = parser.cast_to_entity(parser.element_from_raw(self.parser(raw))?)
+        {
+            Ok(entity)
+        } else {
+            Ok(Box::new(raw.clone()))
+        }
+    }
+
+    fn parser<'a>(&'a self, raw: &'a RawMapElement) -> MapParser<'a> {
+        MapParser {
+            verbose_debug: false,
+            lookup: &self.map.lookup_table,
+            raw,
+            parsers: &self.parsers,
+            effect_parsers: &self.effect_parsers,
+            parent: None,
+            warnings: self.lenient.then_some(&self.warnings),
+            consumed: Default::default(),
+        }
     }
 
     /// Gets a reference to the [RawMap] stored in the manager.
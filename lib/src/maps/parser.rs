@@ -1,8 +1,16 @@
-use std::{any::Any, collections::HashMap, error::Error, fmt::Display, marker::PhantomData};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::HashMap,
+    error::Error,
+    fmt::Display,
+    marker::PhantomData,
+};
 
 use crate::maps::{
     DynMapElement,
     ErasedMapElement,
+    ExtraData,
     LookupIndex,
     LookupTable,
     MapElement,
@@ -18,10 +26,40 @@ pub struct MapParser<'a> {
     pub(crate) verbose_debug: bool,
     pub(crate) lookup: &'a LookupTable,
     pub(crate) raw: &'a RawMapElement,
-    pub(crate) parsers: &'a HashMap<&'static str, Box<dyn ElementParserImpl>>,
+    pub(crate) parsers: &'a HashMap<String, Box<dyn ElementParserImpl>>,
+    pub(crate) effect_parsers: &'a HashMap<String, Box<dyn ElementParserImpl>>,
+    /// The parser of the parent element, used to build the paths in errors
+    pub(crate) parent: Option<&'a MapParser<'a>>,
+    /// Where warnings are collected when parsing in lenient mode, `None` when not lenient
+    pub(crate) warnings: Option<&'a RefCell<Vec<ParseWarning>>>,
+    /// Which attributes and children have been read, everything else goes into [extra](Self::extra)
+    pub(crate) consumed: Consumed,
+}
+
+/// Tracks the attributes and children read by a [MapParser] by index
+#[derive(Default)]
+pub(crate) struct Consumed {
+    attributes: RefCell<Vec<bool>>,
+    children: RefCell<Vec<bool>>,
+}
+
+impl Consumed {
+    fn mark(list: &RefCell<Vec<bool>>, index: usize) {
+        let mut list = list.borrow_mut();
+
+        if list.len() <= index {
+            list.resize(index + 1, false);
+        }
+
+        list[index] = true;
+    }
+
+    fn is_marked(list: &RefCell<Vec<bool>>, index: usize) -> bool {
+        list.borrow().get(index).copied().unwrap_or(false)
+    }
 }
 
-impl MapParser<'_> {
+impl<'a> MapParser<'a> {
     /// Parses `T` without forking, needed to parse the map root properly
     pub(crate) fn parse_self<T: MapElement>(self) -> Result<T, MapElementParsingError> {
         T::from_raw(self)
@@ -32,76 +70,87 @@ impl MapParser<'_> {
         if self.verbose_debug {
             println!("{}", T::NAME);
         }
-        for element in &self.raw.children {
+        for (i, element) in self.raw.children.iter().enumerate() {
             if element.name.to_string(self.lookup) == T::NAME {
-                return T::from_raw(MapParser {
-                    verbose_debug: self.verbose_debug,
-                    lookup: self.lookup,
-                    raw: element,
-                    parsers: self.parsers,
-                });
+                Consumed::mark(&self.consumed.children, i);
+                return T::from_raw(self.fork(element)).map_err(|e| self.child_context(element, e));
             }
         }
 
-        Err(MapElementParsingError::NoMatchingElementFound {
-            expected: T::NAME,
-            found: self.raw.name.to_string(self.lookup).to_owned(),
-        })
+        Err(
+            self.context(MapElementParsingError::NoMatchingElementFound {
+                expected: T::NAME,
+                found: self.raw.name.to_string(self.lookup).to_owned(),
+            }),
+        )
     }
 
-    /// Attempts to parse as many child elements of type `T` as possible
-    // TODO: make this return multierror instead of just ignoring all errors
+    /// Attempts to parse all the child elements of type `T`
+    ///
+    /// If any of them fail to parse every error is returned in a [MultiError](MapElementParsingError::MultiError).
     pub fn parse_all_elements<T: MapElement>(&self) -> Result<Vec<T>, MapElementParsingError> {
-        let len = self.raw.children.len();
-
         if self.verbose_debug {
             println!("Vec<{}>", T::NAME);
         }
 
-        self.raw
-            .children
-            .iter()
-            .filter(|r| r.name.to_string(self.lookup) == T::NAME)
-            .map(|r| {
-                T::from_raw(MapParser {
-                    verbose_debug: self.verbose_debug,
-                    lookup: self.lookup,
-                    raw: r,
-                    parsers: self.parsers,
-                })
-            })
-            .try_fold(
-                Vec::with_capacity(len),
-                |mut vec, t| -> Result<Vec<T>, MapElementParsingError> {
-                    vec.push(t?);
+        let mut errors = Vec::new();
+        let mut parsed = Vec::with_capacity(self.raw.children.len());
+
+        for (i, raw) in self.raw.children.iter().enumerate() {
+            if raw.name.to_string(self.lookup) != T::NAME {
+                continue;
+            }
 
-                    Ok(vec)
-                },
-            )
+            Consumed::mark(&self.consumed.children, i);
+
+            match T::from_raw(self.fork(raw)) {
+                Ok(t) => parsed.push(t),
+                Err(e) => errors.push((T::NAME.to_owned(), self.child_context(raw, e))),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(parsed)
+        } else {
+            Err(MapElementParsingError::multi(errors))
+        }
     }
 
     /// Parses all the children of the current element as [DynMapElement]s
     ///
-    /// Any elements found that don't have registered parsers will be kept as [RawMapElement]
+    /// Any elements found that don't have registered parsers will be kept as [RawMapElement].<br>
+    /// In lenient mode elements that fail to parse are also kept as [RawMapElement], with the error added as a warning.
     pub fn parse_any_element(&self) -> Result<Vec<DynMapElement>, MapElementParsingError> {
-        let parsed_elements = self.raw.children.iter().map(|raw| {
-            if let Some(parser) = self.parsers.get(raw.name.to_string(self.lookup)) {
-                if self.verbose_debug {
-                    println!("{}", parser.element_name());
-                }
+        self.parse_dyn_children(|name| self.parsers.get(name).map(Box::as_ref))
+    }
 
-                parser
-                    .element_from_raw(MapParser {
-                        verbose_debug: self.verbose_debug,
-                        lookup: self.lookup,
-                        raw,
-                        parsers: self.parsers,
-                    })
-                    .map_err(|e| (parser.element_name().to_owned(), e))
-            } else {
-                Ok(Box::new(raw.clone()) as DynMapElement)
-            }
-        });
+    /// Parses all the children of the current element as styleground [DynMapElement]s
+    ///
+    /// Works the same as [parse_any_element](Self::parse_any_element), but never uses entity parsers.
+    /// The [effect parsers](super::MapManager::add_effect_parser) are used first, so effects that have the same
+    /// name as an entity, like `tentacles`, are parsed as the effect.
+    pub fn parse_any_styleground(&self) -> Result<Vec<DynMapElement>, MapElementParsingError> {
+        self.parse_dyn_children(|name| self.styleground_parser(name))
+    }
+
+    /// Finds the parser for a styleground called `name`, checking the effect parsers before the non-entity parsers
+    pub(crate) fn styleground_parser(&self, name: &str) -> Option<&'a dyn ElementParserImpl> {
+        self.effect_parsers.get(name).map(Box::as_ref).or_else(|| {
+            self.parsers
+                .get(name)
+                .map(Box::as_ref)
+                .filter(|p| !p.is_entity())
+        })
+    }
+
+    /// Parses every child with the parser returned by `find_parser`, keeping them as [RawMapElement]s if there is none
+    fn parse_dyn_children<'b>(
+        &self,
+        find_parser: impl Fn(&str) -> Option<&'b dyn ElementParserImpl>,
+    ) -> Result<Vec<DynMapElement>, MapElementParsingError> {
+        self.consumed
+            .children
+            .replace(vec![true; self.raw.children.len()]);
 
         // Preallocate only enough for 1/4 of the possible errors
         // child lists can be long so we don't really want to preallocate the entire
@@ -109,47 +158,49 @@ impl MapParser<'_> {
         let mut errors = Vec::with_capacity(self.raw.children.len() / 4);
         let mut parsed = Vec::with_capacity(self.raw.children.len());
 
-        for element in parsed_elements {
-            match element {
+        for raw in &self.raw.children {
+            let Some(parser) = find_parser(raw.name.to_string(self.lookup)) else {
+                parsed.push(Box::new(raw.clone()) as DynMapElement);
+                continue;
+            };
+
+            if self.verbose_debug {
+                println!("{}", parser.element_name());
+            }
+
+            match parser.element_from_raw(self.fork(raw)) {
                 Ok(p) => parsed.push(p),
-                Err(e) => errors.push(e),
+                Err(e) => {
+                    let error = (parser.element_name().to_owned(), self.child_context(raw, e));
+
+                    match self.warnings {
+                        Some(warnings) => {
+                            warnings.borrow_mut().push(ParseWarning::from(error));
+                            parsed.push(Box::new(raw.clone()));
+                        }
+                        None => errors.push(error),
+                    }
+                }
             }
         }
 
         if errors.is_empty() {
             Ok(parsed)
         } else {
-            Err(MapElementParsingError::MultiError { errors })
+            Err(MapElementParsingError::multi(errors))
         }
     }
 
     /// Parse all the children of the current elements as [`DynEntity`](super::elements::entities::DynEntity) if they are entities.
     ///
     /// This detects if something is an entity by using [`ElementParserImpl::is_entity`].
-    /// Realistically this means a type registered via [`MapManager::add_entity`](super::MapManager::add_entity_parser).
+    /// Realistically this means a type registered via [`MapManager::add_entity`](super::MapManager::add_entity_parser).<br>
+    /// Any children without an entity parser are kept as [`RawMapElement`]s.<br>
+    /// In lenient mode entities that fail to parse are also kept as [`RawMapElement`]s, with the error added as a warning.
     pub fn parse_any_entity(&self) -> Result<Vec<Box<dyn ErasedEntity>>, MapElementParsingError> {
-        let parsed_elements = self.raw.children.iter().filter_map(|raw| {
-            if let Some(parser) = self.parsers.get(raw.name.to_string(self.lookup))
-                && parser.is_entity()
-            {
-                if self.verbose_debug {
-                    println!("{}", parser.element_name());
-                }
-
-                parser
-                    .element_from_raw(MapParser {
-                        verbose_debug: self.verbose_debug,
-                        lookup: self.lookup,
-                        raw,
-                        parsers: self.parsers,
-                    })
-                    .map(|d| parser.cast_to_entity(d))
-                    .map_err(|e| (parser.element_name().to_owned(), e))
-                    .transpose()
-            } else {
-                None
-            }
-        });
+        self.consumed
+            .children
+            .replace(vec![true; self.raw.children.len()]);
 
         // Preallocate only enough for 1/4 of the possible errors
         // child lists can be long so we don't really want to preallocate the entire
@@ -157,17 +208,40 @@ impl MapParser<'_> {
         let mut errors = Vec::with_capacity(self.raw.children.len() / 4);
         let mut parsed = Vec::with_capacity(self.raw.children.len());
 
-        for element in parsed_elements {
-            match element {
-                Ok(p) => parsed.push(p),
-                Err(e) => errors.push(e),
+        for raw in &self.raw.children {
+            let Some(parser) = self
+                .parsers
+                .get(raw.name.to_string(self.lookup))
+                .filter(|p| p.is_entity())
+            else {
+                parsed.push(Box::new(raw.clone()) as Box<dyn ErasedEntity>);
+                continue;
+            };
+
+            if self.verbose_debug {
+                println!("{}", parser.element_name());
+            }
+
+            match parser.element_from_raw(self.fork(raw)) {
+                Ok(p) => parsed.extend(parser.cast_to_entity(p)),
+                Err(e) => {
+                    let error = (parser.element_name().to_owned(), self.child_context(raw, e));
+
+                    match self.warnings {
+                        Some(warnings) => {
+                            warnings.borrow_mut().push(ParseWarning::from(error));
+                            parsed.push(Box::new(raw.clone()));
+                        }
+                        None => errors.push(error),
+                    }
+                }
             }
         }
 
         if errors.is_empty() {
             Ok(parsed)
         } else {
-            Err(MapElementParsingError::MultiError { errors })
+            Err(MapElementParsingError::multi(errors))
         }
     }
 
@@ -177,18 +251,15 @@ impl MapParser<'_> {
     pub fn parse_optional_element<T: MapElement>(
         &self,
     ) -> Result<Option<T>, MapElementParsingError> {
-        for child in &self.raw.children {
+        for (i, child) in self.raw.children.iter().enumerate() {
             if child.name.to_string(self.lookup) == T::NAME {
                 if self.verbose_debug {
                     println!("{}", T::NAME);
                 }
-                return T::from_raw(MapParser {
-                    verbose_debug: self.verbose_debug,
-                    lookup: self.lookup,
-                    raw: child,
-                    parsers: self.parsers,
-                })
-                .map(Some);
+                Consumed::mark(&self.consumed.children, i);
+                return T::from_raw(self.fork(child))
+                    .map(Some)
+                    .map_err(|e| self.child_context(child, e));
             }
         }
 
@@ -207,20 +278,13 @@ impl MapParser<'_> {
         }
 
         match self
-            .raw
-            .attributes
-            .iter()
-            .find_map(|a| {
-                if a.name.to_string(self.lookup) == str {
-                    Some(&a.value)
-                } else {
-                    None
-                }
-            })
+            .find_attribute(str)
             .ok_or(MapElementParsingError::attribute_missing(str))
         {
-            Ok(t) => T::try_from(t).map_err(MapElementParsingError::EncodedVarError),
-            Err(e) => Err(e),
+            Ok(t) => T::try_from(t).map_err(|e| {
+                self.attribute_context(str, MapElementParsingError::EncodedVarError(e))
+            }),
+            Err(e) => Err(self.attribute_context(str, e)),
         }
     }
 
@@ -233,17 +297,9 @@ impl MapParser<'_> {
             println!("Attr({str})");
         }
 
-        self.raw
-            .attributes
-            .iter()
-            .find_map(|a| {
-                if a.name.to_string(self.lookup) == str {
-                    Some(&a.value)
-                } else {
-                    None
-                }
-            })
-            .ok_or(MapElementParsingError::attribute_missing(str))
+        self.find_attribute(str).ok_or_else(|| {
+            self.attribute_context(str, MapElementParsingError::attribute_missing(str))
+        })
     }
 
     /// Returns the value attached to the attribute with name `str` if it is there, otherwise returns `None`
@@ -257,19 +313,145 @@ impl MapParser<'_> {
             println!("Attr({str})");
         }
 
-        match self.raw.attributes.iter().find_map(|a| {
-            if a.name.to_string(self.lookup) == str {
-                Some(&a.value)
-            } else {
-                None
-            }
-        }) {
+        match self.find_attribute(str) {
             Some(t) => T::try_from(t)
-                .map_err(MapElementParsingError::EncodedVarError)
+                .map_err(|e| {
+                    self.attribute_context(str, MapElementParsingError::EncodedVarError(e))
+                })
                 .map(Some),
             None => Ok(None),
         }
     }
+
+    /// Gets every attribute and child of the current element that hasn't been read by the parser
+    ///
+    /// This is used for [`#[extra]`](celeste_rs_macros::MapElement) fields, so it should be called after everything else is parsed.
+    /// All the strings in the returned data are resolved.
+    pub fn extra(&self) -> ExtraData {
+        let attributes = self
+            .raw
+            .attributes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !Consumed::is_marked(&self.consumed.attributes, *i))
+            .map(|(_, attr)| {
+                let mut attr = attr.clone();
+                attr.name.resolve(self.lookup);
+
+                if let EncodedVar::LookupIndex(i) = attr.value {
+                    attr.value = EncodedVar::String(self.lookup[i].clone());
+                }
+
+                attr
+            })
+            .collect();
+
+        let (child_positions, children) = self
+            .raw
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !Consumed::is_marked(&self.consumed.children, *i))
+            .map(|(i, child)| {
+                let mut child = child.clone();
+                child.resolve_strings(self.lookup);
+                (i, child)
+            })
+            .unzip();
+
+        ExtraData {
+            attributes,
+            children,
+            child_positions,
+        }
+    }
+
+    /// Finds an attribute by name, marking it as read
+    fn find_attribute(&self, name: &str) -> Option<&EncodedVar> {
+        let (i, attr) = self
+            .raw
+            .attributes
+            .iter()
+            .enumerate()
+            .find(|(_, a)| a.name.to_string(self.lookup) == name)?;
+
+        Consumed::mark(&self.consumed.attributes, i);
+
+        Some(&attr.value)
+    }
+
+    /// The path to the current element, like `Map/levels/level[name=a-00]/entities/spinner[id=4]`
+    ///
+    /// Elements with a `name` or `id` attribute include it to tell them apart from their siblings.
+    pub fn path(&self) -> String {
+        let segment = path_segment(self.raw, self.lookup);
+
+        match self.parent {
+            Some(parent) => format!("{}/{segment}", parent.path()),
+            None => segment,
+        }
+    }
+
+    /// Creates a parser for a child of the current element
+    fn fork<'b>(&'b self, raw: &'b RawMapElement) -> MapParser<'b> {
+        MapParser {
+            verbose_debug: self.verbose_debug,
+            lookup: self.lookup,
+            raw,
+            parsers: self.parsers,
+            effect_parsers: self.effect_parsers,
+            parent: Some(self),
+            warnings: self.warnings,
+            consumed: Consumed::default(),
+        }
+    }
+
+    /// Adds the path of the current element to an error
+    fn context(&self, error: MapElementParsingError) -> MapElementParsingError {
+        error.with_path(|| self.path())
+    }
+
+    /// Adds the path of an attribute on the current element to an error
+    fn attribute_context(
+        &self,
+        name: &str,
+        error: MapElementParsingError,
+    ) -> MapElementParsingError {
+        error.with_path(|| format!("{}@{name}", self.path()))
+    }
+
+    /// Adds the path of a child of the current element to an error
+    fn child_context(
+        &self,
+        child: &RawMapElement,
+        error: MapElementParsingError,
+    ) -> MapElementParsingError {
+        error.with_path(|| format!("{}/{}", self.path(), path_segment(child, self.lookup)))
+    }
+}
+
+fn path_segment(raw: &RawMapElement, lookup: &LookupTable) -> String {
+    let name = raw.name.to_string(lookup);
+    let attribute = |attr: &str| {
+        raw.attributes
+            .iter()
+            .find(|a| a.name.to_string(lookup) == attr)
+            .map(|a| &a.value)
+    };
+
+    if let Some(value) = attribute("name") {
+        let value = match value {
+            EncodedVar::String(s) | EncodedVar::LengthEncodedString(s) => s.as_str(),
+            EncodedVar::LookupIndex(i) => lookup.get(*i).map(String::as_str).unwrap_or_default(),
+            _ => "",
+        };
+
+        format!("{name}[name={value}]")
+    } else if let Some(id) = attribute("id").and_then(|v| v.int().ok()) {
+        format!("{name}[id={id}]")
+    } else {
+        name.to_owned()
+    }
 }
 
 
@@ -291,6 +473,13 @@ pub enum MapElementParsingError {
         errors: Vec<(String, MapElementParsingError)>,
     },
     Custom(Box<dyn Error>),
+    /// An error with the path to where it happened, see [MapParser::path]
+    ///
+    /// Errors on attributes end with `@` and the attribute name.
+    WithPath {
+        path: String,
+        error: Box<MapElementParsingError>,
+    },
 }
 
 impl MapElementParsingError {
@@ -312,6 +501,50 @@ impl MapElementParsingError {
     pub fn custom(err: impl Into<Box<dyn Error>>) -> Self {
         MapElementParsingError::Custom(err.into())
     }
+
+    /// The path to where the error happened, if it's known
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            MapElementParsingError::WithPath { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Gets the error without its path
+    pub fn without_path(&self) -> &MapElementParsingError {
+        match self {
+            MapElementParsingError::WithPath { error, .. } => error,
+            e => e,
+        }
+    }
+
+    /// Adds a path to the error if it doesn't already have one
+    ///
+    /// [MultiError](MapElementParsingError::MultiError)s are left alone as each of their errors has a path.
+    fn with_path(self, path: impl FnOnce() -> String) -> Self {
+        match self {
+            MapElementParsingError::WithPath { .. } | MapElementParsingError::MultiError { .. } =>
+                self,
+            error => MapElementParsingError::WithPath {
+                path: path(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Combines errors into a [MultiError](MapElementParsingError::MultiError), flattening any nested ones
+    fn multi(errors: Vec<(String, MapElementParsingError)>) -> Self {
+        let mut flattened = Vec::with_capacity(errors.len());
+
+        for (name, error) in errors {
+            match error {
+                MapElementParsingError::MultiError { errors } => flattened.extend(errors),
+                error => flattened.push((name, error)),
+            }
+        }
+
+        MapElementParsingError::MultiError { errors: flattened }
+    }
 }
 
 impl Display for MapElementParsingError {
@@ -331,7 +564,7 @@ impl Display for MapElementParsingError {
             MapElementParsingError::AttributeMissing { name } =>
                 write!(f, "Missing attribute \"{name}\""),
             MapElementParsingError::MultiError { errors } => {
-                writeln!(f, "Found errors parsing dynamic map elements:")?;
+                writeln!(f, "Found errors parsing map elements:")?;
 
                 for (name, error) in errors {
                     writeln!(f, "\t{name}: {error}")?;
@@ -339,12 +572,35 @@ impl Display for MapElementParsingError {
 
                 Ok(())
             }
+            MapElementParsingError::WithPath { path, error } => write!(f, "{path}: {error}"),
         }
     }
 }
 
 impl Error for MapElementParsingError {}
 
+/// An element that failed to parse in lenient mode and was kept as a [RawMapElement] instead
+///
+/// See [MapManager::set_lenient](super::MapManager::set_lenient)
+#[derive(Debug)]
+pub struct ParseWarning {
+    /// The name of the element
+    pub element: String,
+    pub error: MapElementParsingError,
+}
+
+impl From<(String, MapElementParsingError)> for ParseWarning {
+    fn from((element, error): (String, MapElementParsingError)) -> Self {
+        ParseWarning { element, error }
+    }
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} was kept unparsed: {}", self.element, self.error)
+    }
+}
+
 impl From<EncodedVarError> for MapElementParsingError {
     fn from(value: EncodedVarError) -> Self {
         MapElementParsingError::EncodedVarError(value)
@@ -355,7 +611,7 @@ impl From<EncodedVarError> for MapElementParsingError {
 ///
 /// The only reason to manually implement this is if you need more type erasure
 pub trait ElementParserImpl: Any {
-    fn element_name(&self) -> &'static str;
+    fn element_name(&self) -> &str;
     fn element_from_raw(&self, parser: MapParser) -> Result<DynMapElement, MapElementParsingError>;
     fn element_to_raw(&self, element: &dyn ErasedMapElement, encoder: &mut MapEncoder);
     fn is_entity(&self) -> bool;
@@ -378,7 +634,7 @@ impl<T> Default for ElementParser<T> {
 }
 
 impl<T: MapElement> ElementParserImpl for ElementParser<T> {
-    fn element_name(&self) -> &'static str {
+    fn element_name(&self) -> &str {
         T::NAME
     }
 
@@ -416,7 +672,7 @@ impl<T: Entity> Default for EntityParser<T> {
 }
 
 impl<T: Entity> ElementParserImpl for EntityParser<T> {
-    fn element_name(&self) -> &'static str {
+    fn element_name(&self) -> &str {
         T::NAME
     }
 
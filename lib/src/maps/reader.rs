@@ -109,6 +109,8 @@ impl DerefMut for MapReader<'_> {
 pub enum MapReadError {
     InvalidEncodedVarType(u8),
     InvalidHeader(String),
+    /// A string refers to an index past the end of the lookup table
+    InvalidLookupIndex(u16),
     IOError(std::io::Error),
     BinError(BinReadError),
 }
@@ -123,6 +125,8 @@ impl Display for MapReadError {
                 format!("Invalid encoded var type found: {kind}"),
             MapReadError::InvalidHeader(h) =>
                 format!("Invalid file header found, expected \"CELESTE MAP\", found \"{h}\""),
+            MapReadError::InvalidLookupIndex(index) =>
+                format!("Lookup index {index} is outside of the lookup table"),
             MapReadError::IOError(e) => e.to_string(),
         })
     }
@@ -0,0 +1,127 @@
+//! Runtime information about the attribute fields of typed map elements
+//!
+//! The [MapElement](celeste_rs_macros::MapElement), [EntityData](celeste_rs_macros::EntityData) and
+//! [Trigger](celeste_rs_macros::Trigger) derives list every `name` field in [field_info](super::ErasedMapElement::field_info)
+//! and allow them to be read and written as [EncodedVar]s by attribute name,
+//! so any element can be edited without knowing its type.
+//!
+//! ```ignore
+//! for field in entity.field_info() {
+//!     println!("{}: {:?}", field.attribute, entity.get_field(&field.attribute));
+//! }
+//!
+//! entity.set_field("attachToSolid", Some(EncodedVar::Bool(true)))?;
+//! ```
+//!
+//! Attributes without a field of their own are read from and written to the element's `extra` field if it has one.
+//! [RawMapElement](super::RawMapElement)s don't have any fields, but can still be read and written by attribute name.
+use std::{borrow::Cow, error::Error, fmt::Display};
+
+use crate::maps::var_types::{EncodedVar, EncodedVarError};
+
+/// The type of a field, as it is stored in the struct
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Bool,
+    /// An [Integer](crate::utils::num::Integer) or any integer primitive
+    Integer,
+    /// A [Float](crate::utils::num::Float) or any float primitive
+    Float,
+    /// A [String] or [ResolvableString](super::ResolvableString)
+    String,
+    Character,
+    /// Any other type that can be converted to and from an [EncodedVar](super::var_types::EncodedVar)
+    Other,
+}
+
+/// A description of an attribute field on a typed element
+///
+/// Elements described at runtime, like [SchemaEntity](super::schema::SchemaEntity)s, use the attribute name for both names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// The name of the field in the struct
+    pub name: Cow<'static, str>,
+    /// The name of the attribute in the map binary, used to get and set the field
+    pub attribute: Cow<'static, str>,
+    pub kind: FieldKind,
+    /// Whether the field is an [Option] and can be unset
+    pub optional: bool,
+}
+
+impl FieldInfo {
+    pub const fn new(
+        name: &'static str,
+        attribute: &'static str,
+        kind: FieldKind,
+        optional: bool,
+    ) -> Self {
+        FieldInfo {
+            name: Cow::Borrowed(name),
+            attribute: Cow::Borrowed(attribute),
+            kind,
+            optional,
+        }
+    }
+}
+
+/// Converts the value passed to [set_field](super::ErasedMapElement::set_field) for a field that isn't optional
+pub fn required_field<T: for<'a> TryFrom<&'a EncodedVar, Error = EncodedVarError>>(
+    name: &str,
+    value: Option<EncodedVar>,
+) -> Result<T, FieldError> {
+    let value = value.ok_or_else(|| FieldError::Required(name.to_owned()))?;
+
+    T::try_from(&value).map_err(|e| FieldError::invalid_value(name, e))
+}
+
+/// Converts the value passed to [set_field](super::ErasedMapElement::set_field) for an optional field
+pub fn optional_field<T: for<'a> TryFrom<&'a EncodedVar, Error = EncodedVarError>>(
+    name: &str,
+    value: Option<EncodedVar>,
+) -> Result<Option<T>, FieldError> {
+    value
+        .map(|value| T::try_from(&value).map_err(|e| FieldError::invalid_value(name, e)))
+        .transpose()
+}
+
+/// An error setting a field through [set_field](super::ErasedMapElement::set_field)
+#[derive(Debug)]
+pub enum FieldError {
+    /// The element doesn't have a field or extra attributes for the name
+    UnknownField(String),
+    /// Tried to unset a field that isn't optional
+    Required(String),
+    /// The value isn't one of the options the attribute is limited to
+    NotAnOption(String),
+    /// The value couldn't be converted to the type of the field
+    InvalidValue {
+        field: String,
+        error: EncodedVarError,
+    },
+}
+
+impl FieldError {
+    pub fn invalid_value(field: &str, error: EncodedVarError) -> Self {
+        FieldError::InvalidValue {
+            field: field.to_owned(),
+            error,
+        }
+    }
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldError::UnknownField(name) => write!(f, "No field for attribute \"{name}\""),
+            FieldError::Required(name) => write!(f, "Attribute \"{name}\" can't be unset"),
+            FieldError::NotAnOption(name) => write!(
+                f,
+                "Value for attribute \"{name}\" isn't one of its allowed options"
+            ),
+            FieldError::InvalidValue { field, error } =>
+                write!(f, "Invalid value for attribute \"{field}\": {error}"),
+        }
+    }
+}
+
+impl Error for FieldError {}
@@ -0,0 +1,315 @@
+//! Moving rooms between maps
+//!
+//! A room is taken out of one [MapRoot] as a [CopiedRoom] with [take_room] or [copy_room]
+//! and added to another with [paste_room], which fixes anything that conflicts with the rooms already there.
+use std::collections::HashSet;
+
+use crate::{
+    maps::{
+        ErasedMapElement,
+        ExtraData,
+        MapManager,
+        ResolvableString,
+        elements::{Filler, MapRoot, Rect, level::Level},
+        transform::{
+            TransformError,
+            TransformReport,
+            edit_element,
+            edit_entity,
+            element_id,
+            level_ids,
+            room_name,
+        },
+        var_types::EncodedVar,
+    },
+    utils::num::Integer,
+};
+
+/// A room taken out of a map, with its tiles, entities, triggers and decals
+#[derive(Debug)]
+pub struct CopiedRoom {
+    pub level: Level,
+    /// The filler rects that intersect the room
+    pub filler: Vec<Rect>,
+}
+
+impl CopiedRoom {
+    fn bounds(&self) -> Bounds {
+        Bounds::of_level(&self.level)
+    }
+}
+
+/// Removes a room from a map by name
+///
+/// Filler rects that intersect the room are copied but are left in the map, as they may border other rooms.
+pub fn take_room(root: &mut MapRoot, name: &str) -> Option<CopiedRoom> {
+    let index = root
+        .levels
+        .levels
+        .iter()
+        .position(|l| room_name(l) == name)?;
+    let level = root.levels.levels.remove(index);
+    let filler = intersecting_filler(root, Bounds::of_level(&level));
+
+    Some(CopiedRoom { level, filler })
+}
+
+/// Copies a room out of a map by name, leaving the map unchanged
+pub fn copy_room(root: &MapRoot, name: &str) -> Option<CopiedRoom> {
+    let level = root
+        .levels
+        .levels
+        .iter()
+        .find(|l| room_name(l) == name)?
+        .clone();
+    let filler = intersecting_filler(root, Bounds::of_level(&level));
+
+    Some(CopiedRoom { level, filler })
+}
+
+/// Adds a copied room to a map
+///
+/// Anything that would conflict with the map is changed and listed in the report:
+/// - The room is renamed if the name is taken, by adding `-2`, `-3`... to the end
+/// - Entity and trigger ids that are already used in the map are given new ids above the highest one
+/// - The room is moved to the right of every other room if it overlaps one, its filler rects are moved with it
+///
+/// Edited entities and triggers are parsed again with the parsers registered on `manager`.
+///
+/// Returns [IdOutOfRange](TransformError::IdOutOfRange) without changing the map if the new ids don't fit in an `i32`.
+pub fn paste_room(
+    root: &mut MapRoot,
+    manager: &MapManager,
+    room: CopiedRoom,
+) -> Result<TransformReport, TransformError> {
+    let mut report = TransformReport::default();
+    let bounds = room.bounds();
+    let CopiedRoom { mut level, filler } = room;
+    let new_ids = NewIds::pick(root, &level)?;
+
+    let names = root
+        .levels
+        .levels
+        .iter()
+        .map(|l| room_name(l).to_owned())
+        .collect::<HashSet<_>>();
+    let original_name = room_name(&level).to_owned();
+
+    if names.contains(&original_name) {
+        let new_name = (2 ..)
+            .map(|i| format!("{original_name}-{i}"))
+            .find(|n| !names.contains(n))
+            .unwrap();
+
+        level.name = ResolvableString::String(new_name);
+        report.change(
+            Some(room_name(&level)),
+            "level",
+            None,
+            format!("renamed from {original_name}"),
+        );
+    }
+
+    new_ids.apply(manager, &mut level, &mut report);
+
+    let others = root
+        .levels
+        .levels
+        .iter()
+        .map(Bounds::of_level)
+        .collect::<Vec<_>>();
+    // Filler rects are in tiles, so the room only moves by whole tiles to keep them lined up
+    let mut offset_tiles = 0;
+
+    if others.iter().any(|b| b.intersects(&bounds)) {
+        let right = others.iter().map(|b| b.x + b.w).max().unwrap_or(0);
+        offset_tiles = (right - bounds.x + 7).div_euclid(8);
+
+        level.x += Integer::from(offset_tiles * 8);
+        report.change(
+            Some(room_name(&level)),
+            "level",
+            None,
+            format!("moved to ({}, {}) to avoid overlapping", level.x, level.y),
+        );
+    }
+
+    if !filler.is_empty() {
+        let rects = &mut root
+            .filler
+            .get_or_insert_with(|| Filler {
+                filler: Vec::new(),
+                extra: ExtraData::default(),
+            })
+            .filler;
+
+        for mut rect in filler {
+            rect.x += Integer::from(offset_tiles);
+
+            report.change(
+                None,
+                "rect",
+                None,
+                format!(
+                    "added at ({}, {}) for {}",
+                    rect.x,
+                    rect.y,
+                    room_name(&level)
+                ),
+            );
+            rects.push(rect);
+        }
+    }
+
+    root.levels.levels.push(level);
+
+    Ok(report)
+}
+
+/// The new ids for the entities and triggers of a pasted room, in the order they are in the room
+///
+/// `None` means the element keeps its id.
+struct NewIds {
+    entities: Vec<Option<i64>>,
+    triggers: Vec<Option<i64>>,
+}
+
+impl NewIds {
+    /// Gives every entity and trigger in `level` that uses an id from `root` a new id
+    fn pick(root: &MapRoot, level: &Level) -> Result<Self, TransformError> {
+        let mut used = root
+            .levels
+            .levels
+            .iter()
+            .flat_map(level_ids)
+            .collect::<HashSet<_>>();
+        let mut next_id = 1 + used
+            .iter()
+            .copied()
+            .chain(level_ids(level))
+            .max()
+            .unwrap_or(0);
+
+        let mut new_id = |id: i64| {
+            if used.insert(id) {
+                return None;
+            }
+
+            let new = next_id;
+            next_id += 1;
+            used.insert(new);
+            Some(new)
+        };
+
+        let entities = level
+            .entities
+            .iter()
+            .flat_map(|e| &e.entities)
+            .map(|e| new_id(e.id().as_i64()))
+            .collect::<Vec<_>>();
+        let triggers = level
+            .triggers
+            .iter()
+            .flat_map(|t| &t.triggers)
+            .map(|t| element_id(t.as_ref()).and_then(&mut new_id))
+            .collect::<Vec<_>>();
+
+        if let Some(&last_id) = entities.iter().chain(&triggers).flatten().max()
+            && i32::try_from(last_id).is_err()
+        {
+            return Err(TransformError::IdOutOfRange(last_id));
+        }
+
+        Ok(NewIds { entities, triggers })
+    }
+
+    fn apply(self, manager: &MapManager, level: &mut Level, report: &mut TransformReport) {
+        let room = room_name(level).to_owned();
+
+        let entities = level.entities.iter_mut().flat_map(|e| &mut e.entities);
+        for (entity, new) in entities.zip(self.entities) {
+            let Some(new) = new else {
+                continue;
+            };
+            let id = entity.id().as_i64();
+
+            edit_entity(entity, manager, report, |raw| {
+                // Checked to fit when the ids were picked
+                raw.set_attribute("id", EncodedVar::Int(new as i32));
+                true
+            });
+
+            report.change(
+                Some(&room),
+                entity.name(),
+                Some(new),
+                format!("remapped from {id}"),
+            );
+        }
+
+        let triggers = level.triggers.iter_mut().flat_map(|t| &mut t.triggers);
+        for (trigger, new) in triggers.zip(self.triggers) {
+            let (Some(id), Some(new)) = (element_id(trigger.as_ref()), new) else {
+                continue;
+            };
+
+            edit_element(trigger, manager, report, |raw| {
+                raw.set_attribute("id", EncodedVar::Int(new as i32));
+                true
+            });
+
+            report.change(
+                Some(&room),
+                trigger.name(),
+                Some(new),
+                format!("remapped from {id}"),
+            );
+        }
+    }
+}
+
+fn intersecting_filler(root: &MapRoot, bounds: Bounds) -> Vec<Rect> {
+    root.filler
+        .iter()
+        .flat_map(|f| &f.filler)
+        .filter(|r| Bounds::of_rect(r).intersects(&bounds))
+        .copied()
+        .collect()
+}
+
+/// A rectangle in pixels
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    x: i64,
+    y: i64,
+    w: i64,
+    h: i64,
+}
+
+impl Bounds {
+    fn of_level(level: &Level) -> Self {
+        Bounds {
+            x: level.x.as_f32() as i64,
+            y: level.y.as_f32() as i64,
+            w: level.width.as_i64(),
+            h: level.height.as_i64(),
+        }
+    }
+
+    /// Filler rects are in tiles
+    fn of_rect(rect: &Rect) -> Self {
+        Bounds {
+            x: rect.x.as_i64() * 8,
+            y: rect.y.as_i64() * 8,
+            w: rect.w.as_i64() * 8,
+            h: rect.h.as_i64() * 8,
+        }
+    }
+
+    fn intersects(&self, other: &Bounds) -> bool {
+        self.x < other.x + other.w
+            && other.x < self.x + self.w
+            && self.y < other.y + other.h
+            && other.y < self.y + self.h
+    }
+}
@@ -0,0 +1,573 @@
+//! Runtime descriptions of entities and triggers
+//!
+//! These allow validating and reading elements with no [EntityData](super::entities::EntityData)
+//! implementation, for example modded entities described by a Lönn plugin.
+//!
+//! Schemas can be registered with [MapManager::add_schema_parser](super::MapManager::add_schema_parser)
+//! to have matching elements parsed into [SchemaEntity]s.
+use std::{any::Any, borrow::Cow, error::Error, fmt::Display, sync::Arc};
+
+use crate::{
+    maps::{
+        DynMapElement,
+        ErasedMapElement,
+        ExtraData,
+        MapAttribute,
+        RawMapElement,
+        ResolvableString,
+        elements::{
+            Node,
+            entities::{DynEntity, ErasedEntity},
+        },
+        encoder::MapEncoder,
+        parser::{ElementParserImpl, MapElementParsingError, MapParser},
+        reflect::{FieldError, FieldInfo, FieldKind},
+        var_types::{EncodedVar, EncodedVarError},
+        visitor::{ChildElement, ChildElementMut},
+    },
+    utils::num::{Float, Integer},
+};
+
+/// Attributes every entity and trigger has, these are never checked against a schema
+pub const COMMON_ATTRIBUTES: [&str; 7] = ["id", "x", "y", "width", "height", "originX", "originY"];
+
+/// The type an attribute is expected to have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeKind {
+    Bool,
+    Integer,
+    /// Any number, integers are accepted as the map format stores whole floats as integers
+    Float,
+    String,
+}
+
+impl AttributeKind {
+    /// Gets the kind that best describes a value
+    pub fn of(var: &EncodedVar) -> Self {
+        match var {
+            EncodedVar::Bool(_) => AttributeKind::Bool,
+            EncodedVar::Byte(_) | EncodedVar::Short(_) | EncodedVar::Int(_) =>
+                AttributeKind::Integer,
+            EncodedVar::Float(_) => AttributeKind::Float,
+            EncodedVar::LookupIndex(_)
+            | EncodedVar::String(_)
+            | EncodedVar::LengthEncodedString(_) => AttributeKind::String,
+        }
+    }
+
+    /// Checks if a value can be read as this kind
+    pub fn matches(&self, var: &EncodedVar) -> bool {
+        match self {
+            AttributeKind::Bool => matches!(var, EncodedVar::Bool(_)),
+            AttributeKind::Integer => var.int().is_ok(),
+            AttributeKind::Float => var.float().is_ok(),
+            AttributeKind::String => AttributeKind::of(var) == AttributeKind::String,
+        }
+    }
+
+    /// The [FieldKind] reported by [SchemaEntity]s for the attribute
+    pub fn field_kind(&self) -> FieldKind {
+        match self {
+            AttributeKind::Bool => FieldKind::Bool,
+            AttributeKind::Integer => FieldKind::Integer,
+            AttributeKind::Float => FieldKind::Float,
+            AttributeKind::String => FieldKind::String,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AttributeKind::Bool => "bool",
+            AttributeKind::Integer => "integer",
+            AttributeKind::Float => "float",
+            AttributeKind::String => "string",
+        }
+    }
+}
+
+/// The description of a single attribute in an [EntitySchema]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeSchema {
+    pub name: String,
+    pub kind: AttributeKind,
+    /// Whether the attribute has to be present
+    ///
+    /// Optional attributes without a default are simply left out when missing.
+    pub required: bool,
+    pub default: Option<EncodedVar>,
+    /// The values editors suggest for the attribute, empty if there are no suggestions
+    pub options: Vec<EncodedVar>,
+    /// Whether values other than the [options](AttributeSchema::options) are allowed
+    pub editable: bool,
+}
+
+impl AttributeSchema {
+    /// Creates an optional attribute with a default, the kind is taken from the default
+    pub fn with_default(name: impl ToString, default: impl Into<EncodedVar>) -> Self {
+        let default = default.into();
+        AttributeSchema {
+            name: name.to_string(),
+            kind: AttributeKind::of(&default),
+            required: false,
+            default: Some(default),
+            options: Vec::new(),
+            editable: true,
+        }
+    }
+
+    /// Creates a required attribute
+    pub fn required(name: impl ToString, kind: AttributeKind) -> Self {
+        AttributeSchema {
+            name: name.to_string(),
+            kind,
+            required: true,
+            default: None,
+            options: Vec::new(),
+            editable: true,
+        }
+    }
+
+    /// Checks if a value is allowed by the attribute's options
+    ///
+    /// Numbers are compared by value, so `1` matches an option of `1.0`.
+    pub fn allows(&self, var: &EncodedVar) -> bool {
+        if self.editable || self.options.is_empty() {
+            return true;
+        }
+
+        self.options.iter().any(|option| match (option, var) {
+            (EncodedVar::Bool(a), EncodedVar::Bool(b)) => a == b,
+            _ => match (option.float(), var.float()) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => matches!((option.string(), var.string()), (Ok(a), Ok(b)) if a == b),
+            },
+        })
+    }
+}
+
+/// A runtime description of an entity or trigger
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntitySchema {
+    /// The element name, e.g. `FrostHelper/IceSpinner`
+    pub name: String,
+    pub is_trigger: bool,
+    /// The attributes of the entity, not including the [COMMON_ATTRIBUTES]
+    pub attributes: Vec<AttributeSchema>,
+    pub min_nodes: usize,
+    /// The maximum amount of nodes, `None` if there is no limit
+    pub max_nodes: Option<usize>,
+}
+
+impl EntitySchema {
+    /// Creates a schema with no attributes and no nodes
+    pub fn new(name: impl ToString, is_trigger: bool) -> Self {
+        EntitySchema {
+            name: name.to_string(),
+            is_trigger,
+            attributes: Vec::new(),
+            min_nodes: 0,
+            max_nodes: Some(0),
+        }
+    }
+
+    /// Adds an attribute, replacing any existing attribute with the same name
+    pub fn add_attribute(&mut self, attribute: AttributeSchema) {
+        if let Some(existing) = self
+            .attributes
+            .iter_mut()
+            .find(|a| a.name == attribute.name)
+        {
+            *existing = attribute;
+        } else {
+            self.attributes.push(attribute);
+        }
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&AttributeSchema> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    /// Gets the value of an attribute from an element, falling back to the default
+    pub fn get<'a>(&'a self, raw: &'a RawMapElement, name: &str) -> Option<&'a EncodedVar> {
+        raw.get_attribute(name)
+            .or_else(|| self.get_attribute(name).and_then(|a| a.default.as_ref()))
+    }
+
+    /// Checks an element against the schema, returning every problem found
+    pub fn validate(&self, raw: &RawMapElement) -> Vec<SchemaError> {
+        let mut errors = Vec::new();
+
+        let name = raw.name.as_str().unwrap_or_default();
+        if name != self.name {
+            errors.push(SchemaError::NameMismatch {
+                expected: self.name.clone(),
+                found: name.to_owned(),
+            });
+        }
+
+        for attribute in &self.attributes {
+            match raw.get_attribute(&attribute.name) {
+                Some(value) if !attribute.kind.matches(value) =>
+                    errors.push(SchemaError::WrongKind {
+                        name: attribute.name.clone(),
+                        expected: attribute.kind,
+                        found: value.kind(),
+                    }),
+                Some(value) if !attribute.allows(value) =>
+                    errors.push(SchemaError::NotAnOption(attribute.name.clone())),
+                None if attribute.required =>
+                    errors.push(SchemaError::MissingAttribute(attribute.name.clone())),
+                _ => {}
+            }
+        }
+
+        for attribute in &raw.attributes {
+            let name = attribute.name.as_str().unwrap_or_default();
+
+            if !COMMON_ATTRIBUTES.contains(&name) && self.get_attribute(name).is_none() {
+                errors.push(SchemaError::UnknownAttribute(name.to_owned()));
+            }
+        }
+
+        let nodes = raw
+            .children
+            .iter()
+            .filter(|c| c.name.as_str() == Some("node"))
+            .count();
+
+        if nodes < self.min_nodes {
+            errors.push(SchemaError::TooFewNodes {
+                min: self.min_nodes,
+                found: nodes,
+            });
+        }
+
+        if let Some(max) = self.max_nodes
+            && nodes > max
+        {
+            errors.push(SchemaError::TooManyNodes { max, found: nodes });
+        }
+
+        errors
+    }
+
+    /// Adds the defaults of any missing attributes to the element
+    pub fn fill_defaults(&self, raw: &mut RawMapElement) {
+        for attribute in &self.attributes {
+            if let Some(default) = &attribute.default
+                && raw.get_attribute(&attribute.name).is_none()
+            {
+                raw.attributes.push(MapAttribute::new(
+                    ResolvableString::String(attribute.name.clone()),
+                    default.clone(),
+                ));
+            }
+        }
+    }
+}
+
+/// A problem found when validating an element against an [EntitySchema]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    NameMismatch {
+        expected: String,
+        found: String,
+    },
+    MissingAttribute(String),
+    WrongKind {
+        name: String,
+        expected: AttributeKind,
+        found: &'static str,
+    },
+    /// The attribute isn't one of its options, and the schema doesn't allow other values
+    NotAnOption(String),
+    /// The element has an attribute the schema doesn't know about
+    ///
+    /// This usually means the schema is out of date rather than the element being invalid.
+    UnknownAttribute(String),
+    TooFewNodes {
+        min: usize,
+        found: usize,
+    },
+    TooManyNodes {
+        max: usize,
+        found: usize,
+    },
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::NameMismatch { expected, found } =>
+                write!(f, "Expected element \"{expected}\", found \"{found}\""),
+            SchemaError::MissingAttribute(name) => write!(f, "Missing attribute \"{name}\""),
+            SchemaError::WrongKind {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Attribute \"{name}\" should be a {}, found a {found}",
+                expected.name()
+            ),
+            SchemaError::NotAnOption(name) =>
+                write!(f, "Attribute \"{name}\" isn't one of its allowed options"),
+            SchemaError::UnknownAttribute(name) => write!(f, "Unknown attribute \"{name}\""),
+            SchemaError::TooFewNodes { min, found } =>
+                write!(f, "Expected at least {min} nodes, found {found}"),
+            SchemaError::TooManyNodes { max, found } =>
+                write!(f, "Expected at most {max} nodes, found {found}"),
+        }
+    }
+}
+
+impl Error for SchemaError {}
+
+impl SchemaError {
+    /// Whether the error means the element can't be read with the schema
+    ///
+    /// Only [UnknownAttribute](SchemaError::UnknownAttribute) isn't fatal.
+    pub fn is_fatal(&self) -> bool {
+        !matches!(self, SchemaError::UnknownAttribute(_))
+    }
+}
+
+/// An entity or trigger parsed using an [EntitySchema]
+///
+/// The raw element is kept exactly as it was in the map, so saving an unchanged entity doesn't add
+/// any attributes. [get](SchemaEntity::get) falls back to the schema's defaults for missing attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaEntity {
+    pub schema: Arc<EntitySchema>,
+    pub raw: RawMapElement,
+}
+
+impl SchemaEntity {
+    /// Gets the value of an attribute, or its default if the map doesn't set it
+    pub fn get(&self, name: &str) -> Option<&EncodedVar> {
+        self.schema.get(&self.raw, name)
+    }
+
+    /// Gets the value of an attribute converted to `T`
+    pub fn get_as<'a, T: TryFrom<&'a EncodedVar, Error = EncodedVarError>>(
+        &'a self,
+        name: &str,
+    ) -> Option<Result<T, EncodedVarError>> {
+        self.get(name).map(T::try_from)
+    }
+
+    /// Sets the value of an attribute, adding it if it isn't there yet
+    pub fn set(&mut self, name: &str, value: impl Into<EncodedVar>) {
+        let value = value.into();
+
+        match self
+            .raw
+            .attributes
+            .iter_mut()
+            .find(|a| a.name.as_str() == Some(name))
+        {
+            Some(attribute) => attribute.value = value,
+            None => self.raw.attributes.push(MapAttribute::new(
+                ResolvableString::String(name.to_owned()),
+                value,
+            )),
+        }
+    }
+
+    /// Gets the positions of the entity's nodes
+    pub fn nodes(&self) -> Vec<Node> {
+        self.raw
+            .children
+            .iter()
+            .filter(|c| c.name.as_str() == Some("node"))
+            .map(|n| Node {
+                x: n.x(),
+                y: n.y(),
+                extra: ExtraData::default(),
+            })
+            .collect()
+    }
+}
+
+impl ErasedMapElement for SchemaEntity {
+    fn name(&self) -> &str {
+        &self.schema.name
+    }
+
+    fn from_raw(_parser: MapParser) -> Result<Self, MapElementParsingError>
+    where Self: Sized {
+        Err(MapElementParsingError::custom(
+            "schema entities need to be parsed by a SchemaParser",
+        ))
+    }
+
+    fn to_raw(&self, encoder: &mut MapEncoder) {
+        encoder.from_raw(&self.raw)
+    }
+
+    fn clone_dyn(&self) -> DynMapElement {
+        Box::new(self.clone())
+    }
+
+    fn eq_dyn(&self, other: &dyn ErasedMapElement) -> bool {
+        (other as &dyn Any).downcast_ref::<Self>() == Some(self)
+    }
+
+    fn child_elements(&self) -> Vec<ChildElement<'_>> {
+        self.raw.child_elements()
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<ChildElementMut<'_>> {
+        self.raw.child_elements_mut()
+    }
+
+    /// The attributes of the schema, the [COMMON_ATTRIBUTES] aren't listed but can still be read and written
+    fn field_info(&self) -> Vec<FieldInfo> {
+        self.schema
+            .attributes
+            .iter()
+            .map(|attribute| FieldInfo {
+                name: Cow::Owned(attribute.name.clone()),
+                attribute: Cow::Owned(attribute.name.clone()),
+                kind: attribute.kind.field_kind(),
+                optional: !attribute.required,
+            })
+            .collect()
+    }
+
+    /// Falls back to the schema's default like [get](SchemaEntity::get)
+    fn get_field(&self, name: &str) -> Option<EncodedVar> {
+        self.get(name).cloned()
+    }
+
+    /// Values are checked against the schema, attributes it doesn't know about are set without any checks
+    fn set_field(&mut self, name: &str, value: Option<EncodedVar>) -> Result<(), FieldError> {
+        if let Some(attribute) = self.schema.get_attribute(name) {
+            match &value {
+                Some(value) if !attribute.kind.matches(value) =>
+                    return Err(FieldError::invalid_value(
+                        name,
+                        EncodedVarError::new(attribute.kind.name(), value.kind()),
+                    )),
+                Some(value) if !attribute.allows(value) =>
+                    return Err(FieldError::NotAnOption(name.to_owned())),
+                None if attribute.required => return Err(FieldError::Required(name.to_owned())),
+                _ => {}
+            }
+        }
+
+        self.raw.set_field(name, value)
+    }
+}
+
+impl ErasedEntity for SchemaEntity {
+    fn id(&self) -> Integer {
+        self.raw.id()
+    }
+
+    fn x(&self) -> Float {
+        self.raw.x()
+    }
+
+    fn y(&self) -> Float {
+        self.raw.y()
+    }
+
+    fn width(&self) -> Option<Integer> {
+        self.raw.width()
+    }
+
+    fn height(&self) -> Option<Integer> {
+        self.raw.height()
+    }
+
+    fn origin_x(&self) -> Float {
+        self.raw.origin_x()
+    }
+
+    fn origin_y(&self) -> Float {
+        self.raw.origin_y()
+    }
+
+    /// Schema entities don't have a static name, use [ErasedMapElement::name] instead
+    fn kind(&self) -> &'static str {
+        "SchemaEntity"
+    }
+
+    fn clone_entity(&self) -> DynEntity {
+        Box::new(self.clone())
+    }
+}
+
+/// A parser that reads elements into [SchemaEntity]s
+///
+/// Elements that don't match the schema fail to parse, unknown attributes are allowed.
+pub struct SchemaParser {
+    schema: Arc<EntitySchema>,
+}
+
+impl SchemaParser {
+    pub fn new(schema: impl Into<Arc<EntitySchema>>) -> Self {
+        SchemaParser {
+            schema: schema.into(),
+        }
+    }
+
+    pub fn schema(&self) -> &EntitySchema {
+        &self.schema
+    }
+}
+
+impl ElementParserImpl for SchemaParser {
+    fn element_name(&self) -> &str {
+        &self.schema.name
+    }
+
+    fn element_from_raw(&self, parser: MapParser) -> Result<DynMapElement, MapElementParsingError> {
+        let mut raw = parser.raw.clone();
+        raw.resolve_strings(parser.lookup);
+
+        let mut errors = self
+            .schema
+            .validate(&raw)
+            .into_iter()
+            .filter(SchemaError::is_fatal)
+            .map(|e| MapElementParsingError::WithPath {
+                path: parser.path(),
+                error: Box::new(MapElementParsingError::custom(e)),
+            })
+            .collect::<Vec<_>>();
+
+        match errors.len() {
+            0 => {}
+            1 => return Err(errors.remove(0)),
+            _ =>
+                return Err(MapElementParsingError::MultiError {
+                    errors: errors
+                        .into_iter()
+                        .map(|e| (self.schema.name.clone(), e))
+                        .collect(),
+                }),
+        }
+
+        Ok(Box::new(SchemaEntity {
+            schema: self.schema.clone(),
+            raw,
+        }))
+    }
+
+    fn element_to_raw(&self, element: &dyn ErasedMapElement, encoder: &mut MapEncoder) {
+        element.to_raw(encoder)
+    }
+
+    fn is_entity(&self) -> bool {
+        !self.schema.is_trigger
+    }
+
+    fn cast_to_entity(&self, element: Box<dyn ErasedMapElement>) -> Option<Box<dyn ErasedEntity>> {
+        (element as Box<dyn Any>)
+            .downcast::<SchemaEntity>()
+            .ok()
+            .map(|d| d as Box<dyn ErasedEntity>)
+    }
+}
@@ -0,0 +1,486 @@
+//! A small selector language for finding elements in a map
+//!
+//! Selectors are a list of `/` separated steps, each matching the children of the elements matched by the last step.
+//! The first step matches the children of the root `Map` element.
+//!
+//! - `name` matches children with that element name
+//! - `*` matches any child
+//! - `**` matches any amount of levels of descendants, including none
+//! - `[attr]` only matches elements with the attribute
+//! - `[attr=value]` compares the attribute, supported operators are `=`, `!=`, `^=` (starts with),
+//!   `$=` (ends with), `*=` (contains), `<`, `<=`, `>` and `>=`.<br>
+//!   Values can be quoted with `"` to include `]` or `/`.
+//!
+//! For example `levels/level[name^=b-]/entities/strawberry` gets all the strawberries in rooms
+//! starting with `b-` and `**/*[flag=my_flag]` gets every element with `flag` set to `my_flag`.
+//!
+//! Selectors work over [RawMapElement] trees with [Selector::select_raw] and parsed maps with [Selector::select].
+//! Parsed maps are walked through [child_elements](ErasedMapElement::child_elements), so unknown children kept
+//! in `extra` fields are matched too. Attributes are read with [get_field](ErasedMapElement::get_field).
+use std::{collections::HashSet, error::Error, fmt::Display, str::FromStr};
+
+use crate::maps::{
+    ErasedMapElement,
+    LookupTable,
+    RawMap,
+    RawMapElement,
+    elements::MapRoot,
+    var_types::EncodedVar,
+    visitor::ChildElement,
+};
+
+/// A parsed selector, created with [Selector::parse] or [FromStr]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    /// Matches direct children by name, `None` matches any name
+    Child {
+        name: Option<String>,
+        filters: Vec<Filter>,
+    },
+    /// Matches any depth of descendants
+    Descendants,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    attribute: String,
+    /// `None` only checks that the attribute exists
+    comparison: Option<(Operator, String)>,
+}
+
+/// A comparison in a selector filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Equals,
+    NotEquals,
+    StartsWith,
+    EndsWith,
+    Contains,
+    LessThan,
+    LessOrEqual,
+    GreaterThan,
+    GreaterOrEqual,
+}
+
+impl Operator {
+    // Ordered so that longer operators are checked first
+    const ALL: [(&'static str, Operator); 9] = [
+        ("!=", Operator::NotEquals),
+        ("^=", Operator::StartsWith),
+        ("$=", Operator::EndsWith),
+        ("*=", Operator::Contains),
+        ("<=", Operator::LessOrEqual),
+        (">=", Operator::GreaterOrEqual),
+        ("=", Operator::Equals),
+        ("<", Operator::LessThan),
+        (">", Operator::GreaterThan),
+    ];
+
+    fn compare(&self, value: &str, expected: &str) -> bool {
+        let numbers = || Some((value.parse::<f64>().ok()?, expected.parse::<f64>().ok()?));
+
+        match self {
+            Operator::Equals =>
+                value == expected || numbers().is_some_and(|(value, expected)| value == expected),
+            Operator::NotEquals =>
+                value != expected && numbers().is_none_or(|(value, expected)| value != expected),
+            Operator::StartsWith => value.starts_with(expected),
+            Operator::EndsWith => value.ends_with(expected),
+            Operator::Contains => value.contains(expected),
+            Operator::LessThan => numbers().is_some_and(|(value, expected)| value < expected),
+            Operator::LessOrEqual => numbers().is_some_and(|(value, expected)| value <= expected),
+            Operator::GreaterThan => numbers().is_some_and(|(value, expected)| value > expected),
+            Operator::GreaterOrEqual =>
+                numbers().is_some_and(|(value, expected)| value >= expected),
+        }
+    }
+}
+
+/// The location of a selected element, a list of element names and their index in their parent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElementPath<'a>(pub Vec<(&'a str, usize)>);
+
+impl Display for ElementPath<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (name, index)) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{name}[{index}]")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An element matched by a [Selector]
+#[derive(Debug, Clone)]
+pub struct SelectorMatch<'a, T: ?Sized> {
+    pub element: &'a T,
+    pub path: ElementPath<'a>,
+}
+
+impl Selector {
+    /// Parses a selector, see the [module docs](self) for the syntax
+    pub fn parse(str: &str) -> Result<Self, SelectorParseError> {
+        let mut steps = Vec::new();
+        let mut chars = str.trim().chars().peekable();
+
+        while chars.peek().is_some() {
+            let mut name = String::new();
+            let mut filters = Vec::new();
+
+            while let Some(&c) = chars.peek() {
+                if c == '/' || c == '[' {
+                    break;
+                }
+                name.push(c);
+                chars.next();
+            }
+
+            while chars.peek() == Some(&'[') {
+                chars.next();
+                let mut content = String::new();
+                let mut quoted = false;
+
+                loop {
+                    match chars.next() {
+                        // Quotes are kept so the filter can tell what's quoted
+                        Some('"') => {
+                            quoted = !quoted;
+                            content.push('"');
+                        }
+                        Some(']') if !quoted => break,
+                        Some(c) => content.push(c),
+                        None => return Err(SelectorParseError::UnclosedFilter(name)),
+                    }
+                }
+
+                filters.push(Filter::parse(&content)?);
+            }
+
+            match chars.next() {
+                Some('/') | None => {}
+                Some(c) => return Err(SelectorParseError::UnexpectedCharacter(c)),
+            }
+
+            let name = name.trim();
+            steps.push(match name {
+                "" => return Err(SelectorParseError::EmptyStep),
+                "**" if filters.is_empty() => Step::Descendants,
+                "**" => return Err(SelectorParseError::FilteredDescendants),
+                "*" => Step::Child {
+                    name: None,
+                    filters,
+                },
+                _ => Step::Child {
+                    name: Some(name.to_owned()),
+                    filters,
+                },
+            });
+        }
+
+        if steps.is_empty() {
+            return Err(SelectorParseError::EmptyStep);
+        }
+
+        Ok(Selector { steps })
+    }
+
+    /// Finds every matching element under a [RawMapElement]
+    ///
+    /// The lookup table is used for any strings that haven't been resolved.
+    pub fn select_raw<'a>(
+        &self,
+        root: &'a RawMapElement,
+        lookup: &'a LookupTable,
+    ) -> Vec<SelectorMatch<'a, RawMapElement>> {
+        let mut matches = Vec::new();
+        self.walk(
+            RawNode { raw: root, lookup },
+            0,
+            &mut Vec::new(),
+            &mut matches,
+        );
+        self.dedup(&mut matches);
+
+        matches
+            .into_iter()
+            .map(|(node, path)| SelectorMatch {
+                element: node.raw,
+                path,
+            })
+            .collect()
+    }
+
+    /// Finds every matching element in a parsed map
+    ///
+    /// Only the elements listed by [child_elements](ErasedMapElement::child_elements) are walked,
+    /// so the nodes of typed entities can't be selected.<br>
+    /// The indices in the paths are the order of the parsed children, which can differ from the raw map.
+    pub fn select<'a>(&self, root: &'a MapRoot) -> Vec<SelectorMatch<'a, dyn ErasedMapElement>> {
+        let mut matches = Vec::new();
+        self.walk(ParsedNode(root), 0, &mut Vec::new(), &mut matches);
+        self.dedup(&mut matches);
+
+        matches
+            .into_iter()
+            .map(|(node, path)| SelectorMatch {
+                element: node.0,
+                path,
+            })
+            .collect()
+    }
+
+    /// Removes elements reached more than once
+    ///
+    /// This can only happen when there are multiple `**` steps, like in `**/entities/**/node`.
+    fn dedup<N>(&self, matches: &mut Vec<(N, ElementPath<'_>)>) {
+        let descendant_steps = self
+            .steps
+            .iter()
+            .filter(|s| matches!(s, Step::Descendants))
+            .count();
+
+        if descendant_steps > 1 {
+            let mut seen = HashSet::new();
+            matches.retain(|(_, path)| seen.insert(path.0.clone()));
+        }
+    }
+
+    /// Matches the children of `node` against the steps starting at `step`
+    fn walk<'a, N: SelectorNode<'a>>(
+        &self,
+        node: N,
+        step: usize,
+        path: &mut Vec<(&'a str, usize)>,
+        matches: &mut Vec<(N, ElementPath<'a>)>,
+    ) {
+        match &self.steps[step] {
+            Step::Descendants => {
+                // `**` matching nothing
+                if step + 1 == self.steps.len() {
+                    // A trailing `**` selects everything below
+                    for (i, child) in node.children().into_iter().enumerate() {
+                        path.push((child.name(), i));
+                        matches.push((child, ElementPath(path.clone())));
+                        self.walk(child, step, path, matches);
+                        path.pop();
+                    }
+                    return;
+                }
+
+                self.walk(node, step + 1, path, matches);
+
+                for (i, child) in node.children().into_iter().enumerate() {
+                    path.push((child.name(), i));
+                    self.walk(child, step, path, matches);
+                    path.pop();
+                }
+            }
+            Step::Child { name, filters } =>
+                for (i, child) in node.children().into_iter().enumerate() {
+                    if name.as_ref().is_some_and(|n| n != child.name()) {
+                        continue;
+                    }
+
+                    if !filters.iter().all(|f| f.matches(&child)) {
+                        continue;
+                    }
+
+                    path.push((child.name(), i));
+
+                    if step + 1 == self.steps.len() {
+                        matches.push((child, ElementPath(path.clone())));
+                    } else {
+                        self.walk(child, step + 1, path, matches);
+                    }
+
+                    path.pop();
+                },
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = SelectorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Selector::parse(s)
+    }
+}
+
+impl Filter {
+    fn parse(content: &str) -> Result<Self, SelectorParseError> {
+        let unquote = |str: &str| str.trim().replace('"', "");
+        let mut quoted = false;
+
+        for (i, c) in content.char_indices() {
+            if c == '"' {
+                quoted = !quoted;
+            }
+
+            if quoted {
+                continue;
+            }
+
+            let Some((str, operator)) = Operator::ALL
+                .into_iter()
+                .find(|(str, _)| content[i ..].starts_with(str))
+            else {
+                continue;
+            };
+
+            let attribute = unquote(&content[.. i]);
+
+            if attribute.is_empty() {
+                return Err(SelectorParseError::EmptyAttribute);
+            }
+
+            return Ok(Filter {
+                attribute,
+                comparison: Some((operator, unquote(&content[i + str.len() ..]))),
+            });
+        }
+
+        match unquote(content) {
+            attribute if attribute.is_empty() => Err(SelectorParseError::EmptyAttribute),
+            attribute => Ok(Filter {
+                attribute,
+                comparison: None,
+            }),
+        }
+    }
+
+    fn matches<'a>(&self, node: &impl SelectorNode<'a>) -> bool {
+        let Some(value) = node.attribute(&self.attribute) else {
+            return false;
+        };
+
+        match &self.comparison {
+            Some((operator, expected)) => operator.compare(&value, expected),
+            None => true,
+        }
+    }
+}
+
+/// Something a [Selector] can walk over
+trait SelectorNode<'a>: Copy {
+    fn name(&self) -> &'a str;
+    /// Gets the value of an attribute as a string, `None` if it isn't set
+    fn attribute(&self, name: &str) -> Option<String>;
+    fn children(&self) -> Vec<Self>;
+}
+
+#[derive(Clone, Copy)]
+struct RawNode<'a> {
+    raw: &'a RawMapElement,
+    lookup: &'a LookupTable,
+}
+
+impl<'a> SelectorNode<'a> for RawNode<'a> {
+    fn name(&self) -> &'a str {
+        self.raw.name.to_string(self.lookup)
+    }
+
+    fn attribute(&self, name: &str) -> Option<String> {
+        self.raw
+            .attributes
+            .iter()
+            .find(|a| a.name.to_string(self.lookup) == name)
+            .map(|a| value_string(&a.value, self.lookup))
+    }
+
+    fn children(&self) -> Vec<Self> {
+        self.raw
+            .children
+            .iter()
+            .map(|raw| RawNode {
+                raw,
+                lookup: self.lookup,
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ParsedNode<'a>(&'a dyn ErasedMapElement);
+
+impl<'a> SelectorNode<'a> for ParsedNode<'a> {
+    fn name(&self) -> &'a str {
+        self.0.name()
+    }
+
+    fn attribute(&self, name: &str) -> Option<String> {
+        self.0
+            .get_field(name)
+            .map(|value| value_string(&value, &LookupTable::new()))
+    }
+
+    fn children(&self) -> Vec<Self> {
+        self.0
+            .child_elements()
+            .into_iter()
+            .map(|child| match child {
+                ChildElement::Child(element) | ChildElement::DynChild(element) =>
+                    ParsedNode(element),
+                ChildElement::Entity(entity) => ParsedNode(entity),
+            })
+            .collect()
+    }
+}
+
+/// Gets the value of an attribute as a plain string, without the type suffixes of [EncodedVar::to_string]
+fn value_string(var: &EncodedVar, lookup: &LookupTable) -> String {
+    match var {
+        EncodedVar::Bool(b) => b.to_string(),
+        EncodedVar::Byte(b) => b.to_string(),
+        EncodedVar::Short(s) => s.to_string(),
+        EncodedVar::Int(i) => i.to_string(),
+        EncodedVar::Float(f) => f.to_string(),
+        EncodedVar::LookupIndex(i) => lookup.get(*i).cloned().unwrap_or_default(),
+        EncodedVar::String(s) | EncodedVar::LengthEncodedString(s) => s.clone(),
+    }
+}
+
+impl RawMap {
+    /// Finds every element matching a [Selector] in the map
+    pub fn select(&self, selector: &Selector) -> Vec<SelectorMatch<'_, RawMapElement>> {
+        selector.select_raw(&self.root_element, &self.lookup_table)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectorParseError {
+    /// A step has no name, like in `levels//level`
+    EmptyStep,
+    /// A filter with no attribute name, like `[]` or `[=x]`
+    EmptyAttribute,
+    /// A filter was never closed, holds the name of the step
+    UnclosedFilter(String),
+    /// Filters can't be used on `**`
+    FilteredDescendants,
+    UnexpectedCharacter(char),
+}
+
+impl Display for SelectorParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectorParseError::EmptyStep => write!(f, "Selector has an empty step"),
+            SelectorParseError::EmptyAttribute => write!(f, "Selector filter has no attribute"),
+            SelectorParseError::UnclosedFilter(step) =>
+                write!(f, "Selector filter on \"{step}\" is missing a ']'"),
+            SelectorParseError::FilteredDescendants =>
+                write!(f, "Selector filters can't be used on \"**\""),
+            SelectorParseError::UnexpectedCharacter(c) =>
+                write!(f, "Unexpected character '{c}' in selector"),
+        }
+    }
+}
+
+impl Error for SelectorParseError {}
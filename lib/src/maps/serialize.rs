@@ -0,0 +1,400 @@
+//! [serde] support for map elements, enabled with the `serde` feature
+//!
+//! Typed elements derive [Serialize] and [Deserialize] directly.
+//! Everything that doesn't have a fixed structure is written as a map of attribute names to values:
+//! - [RawMapElement]s store their name under `_name` and their children under `_children`
+//! - [ExtraData] is flattened into the element it's on, with its children under `_children`
+//! - [DynEntity]s and [DynMapElement]s are written as their raw element, so they are tagged by `_name`
+//!
+//! Dynamic elements are deserialized with the parsers from [MapManager::default_parsers],
+//! use [MapManager::deserialize_with_parsers] to deserialize with the parsers registered on a manager instead.
+//! Elements without a parser are kept as [RawMapElement]s, while elements that fail to parse are returned as an error.
+//! Stylegrounds are looked up the same way as [MapManager::parse_styleground].
+//!
+//! Only self-describing formats are supported, and for elements that aren't typed the difference between
+//! [String](EncodedVar::String) and [LengthEncodedString](EncodedVar::LengthEncodedString)
+//! attributes is lost, as is the size of integers, which are all read back as [Int](EncodedVar::Int).
+use std::{cell::RefCell, fmt::Formatter};
+
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+    de::{self, MapAccess, Visitor},
+    ser::{Error, SerializeMap},
+};
+
+use crate::{
+    maps::{
+        DynMapElement,
+        ErasedMapElement,
+        ExtraData,
+        MapAttribute,
+        MapManager,
+        RawMapElement,
+        ResolvableString,
+        entities::DynEntity,
+        var_types::{Character, EncodedVar},
+    },
+    utils::num::{Float, Integer},
+};
+
+const NAME_KEY: &str = "_name";
+const CHILDREN_KEY: &str = "_children";
+
+thread_local! {
+    /// The manager used to parse dynamic elements
+    ///
+    /// This has the default parsers, except during [MapManager::deserialize_with_parsers]
+    /// where they are swapped with the parsers of the manager it was called on.
+    static MANAGER: RefCell<MapManager> = RefCell::new({
+        let mut manager = MapManager::empty();
+        manager.default_parsers();
+        manager
+    });
+}
+
+impl MapManager {
+    /// Deserializes a `T` using the parsers registered on this manager for any dynamic elements
+    ///
+    /// Without this, dynamic elements are parsed with [default_parsers](Self::default_parsers).
+    pub fn deserialize_with_parsers<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let _guard = ParserSwap::new(self);
+        T::deserialize(deserializer)
+    }
+
+    fn swap_parsers(&mut self, other: &mut MapManager) {
+        std::mem::swap(&mut self.parsers, &mut other.parsers);
+        std::mem::swap(&mut self.effect_parsers, &mut other.effect_parsers);
+    }
+}
+
+/// Swaps a manager's parsers into [MANAGER], swapping them back when dropped
+struct ParserSwap<'a>(&'a mut MapManager);
+
+impl<'a> ParserSwap<'a> {
+    fn new(manager: &'a mut MapManager) -> Self {
+        MANAGER.with_borrow_mut(|current| manager.swap_parsers(current));
+        ParserSwap(manager)
+    }
+}
+
+impl Drop for ParserSwap<'_> {
+    fn drop(&mut self) {
+        MANAGER.with_borrow_mut(|current| self.0.swap_parsers(current));
+    }
+}
+
+impl Serialize for ResolvableString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ResolvableString::String(s) => serializer.serialize_str(s),
+            ResolvableString::LookupIndex(_) =>
+                Err(S::Error::custom("can't serialize an unresolved string")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ResolvableString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(ResolvableString::String)
+    }
+}
+
+impl Serialize for Integer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Integer::U8(b) => serializer.serialize_u8(b),
+            Integer::I16(s) => serializer.serialize_i16(s),
+            Integer::I32(i) => serializer.serialize_i32(i),
+            Integer::I64(l) => serializer.serialize_i64(l),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Integer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = i64::deserialize(deserializer)?;
+
+        Ok(i32::try_from(value).map_or(Integer::I64(value), Integer::I32))
+    }
+}
+
+impl Serialize for Float {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Float::U8(b) => serializer.serialize_u8(b),
+            Float::I16(s) => serializer.serialize_i16(s),
+            Float::I32(i) => serializer.serialize_i32(i),
+            Float::I64(l) => serializer.serialize_i64(l),
+            Float::F32(f) => serializer.serialize_f32(f),
+            Float::F64(d) => serializer.serialize_f64(d),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Float {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(FloatVisitor)
+    }
+}
+
+/// Keeps whole numbers as integers so they are encoded the same way they were read
+struct FloatVisitor;
+
+impl<'de> Visitor<'de> for FloatVisitor {
+    type Value = Float;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "a number")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(i32::try_from(v).map_or(Float::I64(v), Float::I32))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        i64::try_from(v)
+            .map_err(|_| E::custom(format!("{v} is too large for a float")))
+            .and_then(|v| self.visit_i64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Float::F32(v as f32))
+    }
+}
+
+impl Serialize for EncodedVar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            EncodedVar::Bool(b) => serializer.serialize_bool(*b),
+            EncodedVar::Byte(b) => serializer.serialize_u8(*b),
+            EncodedVar::Short(s) => serializer.serialize_i16(*s),
+            EncodedVar::Int(i) => serializer.serialize_i32(*i),
+            EncodedVar::Float(f) => serializer.serialize_f32(*f),
+            EncodedVar::String(s) | EncodedVar::LengthEncodedString(s) =>
+                serializer.serialize_str(s),
+            EncodedVar::LookupIndex(_) =>
+                Err(S::Error::custom("can't serialize an unresolved string")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EncodedVar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(EncodedVarVisitor)
+    }
+}
+
+struct EncodedVarVisitor;
+
+impl<'de> Visitor<'de> for EncodedVarVisitor {
+    type Value = EncodedVar;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "a bool, number or string")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(EncodedVar::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        i32::try_from(v)
+            .map(EncodedVar::Int)
+            .map_err(|_| E::custom(format!("{v} is too large for a map attribute")))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        i32::try_from(v)
+            .map(EncodedVar::Int)
+            .map_err(|_| E::custom(format!("{v} is too large for a map attribute")))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(EncodedVar::Float(v as f32))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(EncodedVar::String(v.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(EncodedVar::String(v))
+    }
+}
+
+impl Serialize for Character {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Character::String(s) => s.serialize(serializer),
+            Character::Byte(b) => serializer.serialize_u8(*b),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Character {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match EncodedVar::deserialize(deserializer)? {
+            EncodedVar::Int(i) => u8::try_from(i)
+                .map(Character::Byte)
+                .map_err(|_| de::Error::custom(format!("{i} is not a valid character"))),
+            EncodedVar::String(s) => Ok(Character::String(ResolvableString::String(s))),
+            var => Err(de::Error::custom(format!(
+                "expected a character, found {}",
+                var.kind()
+            ))),
+        }
+    }
+}
+
+/// Writes attributes and children as map entries
+fn serialize_entries<M: SerializeMap>(
+    map: &mut M,
+    attributes: &[MapAttribute],
+    children: &[RawMapElement],
+) -> Result<(), M::Error> {
+    for attribute in attributes {
+        map.serialize_entry(&attribute.name, &attribute.value)?;
+    }
+
+    if !children.is_empty() {
+        map.serialize_entry(CHILDREN_KEY, children)?;
+    }
+
+    Ok(())
+}
+
+impl Serialize for RawMapElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(NAME_KEY, &self.name)?;
+        serialize_entries(&mut map, &self.attributes, &self.children)?;
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for RawMapElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(ElementVisitor { named: true })
+    }
+}
+
+impl Serialize for ExtraData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        serialize_entries(&mut map, &self.attributes, &self.children)?;
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ExtraData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let element = deserializer.deserialize_map(ElementVisitor { named: false })?;
+
+        // The positions of the children aren't serialized, so they're written after the known children
+        Ok(ExtraData {
+            attributes: element.attributes,
+            children: element.children,
+            child_positions: Vec::new(),
+        })
+    }
+}
+
+/// Reads a map of attributes into a [RawMapElement], requiring a `_name` entry if `named` is set
+struct ElementVisitor {
+    named: bool,
+}
+
+impl<'de> Visitor<'de> for ElementVisitor {
+    type Value = RawMapElement;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "a map of attributes")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut element = RawMapElement {
+            name: ResolvableString::String(String::new()),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        };
+        let mut found_name = false;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                NAME_KEY if self.named => {
+                    element.name = map.next_value()?;
+                    found_name = true;
+                }
+                CHILDREN_KEY => element.children = map.next_value()?,
+                _ => element.attributes.push(MapAttribute {
+                    name: ResolvableString::String(key),
+                    value: map.next_value()?,
+                }),
+            }
+        }
+
+        if self.named && !found_name {
+            return Err(de::Error::missing_field(NAME_KEY));
+        }
+
+        Ok(element)
+    }
+}
+
+impl Serialize for DynMapElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_ref().encode_raw().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DynMapElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawMapElement::deserialize(deserializer)?;
+
+        MANAGER
+            .with_borrow(|manager| manager.parse_dyn_element(&raw))
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes a list of stylegrounds, used with `deserialize_with` on `dyn_stylegrounds` fields
+///
+/// [DynMapElement]'s own [Deserialize] impl looks the name up like [MapManager::parse_dyn_element],
+/// which doesn't use the effect parsers.
+pub(crate) fn deserialize_stylegrounds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<DynMapElement>, D::Error> {
+    let raw = Vec::<RawMapElement>::deserialize(deserializer)?;
+
+    MANAGER
+        .with_borrow(|manager| {
+            raw.iter()
+                .map(|raw| manager.parse_styleground(raw))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(de::Error::custom)
+}
+
+impl Serialize for DynEntity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.as_ref() as &dyn ErasedMapElement)
+            .encode_raw()
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DynEntity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawMapElement::deserialize(deserializer)?;
+
+        MANAGER
+            .with_borrow(|manager| manager.parse_entity(&raw))
+            .map_err(de::Error::custom)
+    }
+}
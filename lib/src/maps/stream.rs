@@ -0,0 +1,324 @@
+//! A pull based reader for map binaries that doesn't load the whole map into memory
+//!
+//! [MapReader](super::reader::MapReader) needs all the bytes of the map and
+//! [RawMap](super::RawMap) builds the entire element tree.
+//! [MapStreamReader] instead reads elements one [MapEvent] at a time from any [Read],
+//! letting you skip over elements you don't care about.
+//!
+//! ```ignore
+//! let mut reader = MapStreamReader::new(File::open("map.bin")?)?;
+//! let mut path = Vec::new();
+//!
+//! while let Some(event) = reader.next_event()? {
+//!     match event {
+//!         MapEvent::Enter { name } if name == "solids" || name == "entities" => reader.skip_element()?,
+//!         MapEvent::Enter { name } => path.push(name),
+//!         MapEvent::Attribute { name, value } if path.last().is_some_and(|p| p == "level") =>
+//!             println!("{name}: {value:?}"),
+//!         MapEvent::Leave => drop(path.pop()),
+//!         _ => {}
+//!     }
+//! }
+//! ```
+use std::io::Read;
+
+use crate::{
+    maps::{
+        LookupIndex,
+        LookupTable,
+        MapAttribute,
+        RawMapElement,
+        ResolvableString,
+        reader::{MapReadError, MapReader},
+        var_types::EncodedVar,
+    },
+    utils::binary::BinReadError,
+};
+
+/// How many bytes are read from the stream at least when more are needed
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// A single step through a map binary, returned by [MapStreamReader::next_event]
+#[derive(Debug, Clone)]
+pub enum MapEvent {
+    /// The start of an element, its attributes and then its children come after this
+    Enter { name: String },
+    /// An attribute of the most recently entered element
+    ///
+    /// Strings from the lookup table are resolved to [EncodedVar::String].
+    Attribute { name: String, value: EncodedVar },
+    /// The end of the most recently entered element
+    Leave,
+}
+
+/// The state of an element that hasn't been left yet
+struct OpenElement {
+    name: String,
+    attributes_left: u8,
+    /// `None` until all the attributes have been read
+    children_left: Option<u16>,
+}
+
+/// Reads a map binary as a stream of [MapEvent]s
+pub struct MapStreamReader<R: Read> {
+    reader: R,
+    /// Bytes read from `reader`, everything before `pos` has already been decoded
+    buf: Vec<u8>,
+    pos: usize,
+    eof: bool,
+    name: String,
+    lookup: LookupTable,
+    stack: Vec<OpenElement>,
+    started: bool,
+}
+
+impl<R: Read> MapStreamReader<R> {
+    /// Creates a reader, reading in the header and lookup table
+    pub fn new(reader: R) -> Result<Self, MapReadError> {
+        let mut reader = MapStreamReader {
+            reader,
+            buf: Vec::new(),
+            pos: 0,
+            eof: false,
+            name: String::new(),
+            lookup: LookupTable::new(),
+            stack: Vec::new(),
+            started: false,
+        };
+
+        let check_string = reader.decode(|r| Ok(r.read_string()?))?;
+
+        if check_string != "CELESTE MAP" {
+            return Err(MapReadError::InvalidHeader(check_string));
+        }
+
+        reader.name = reader.decode(|r| Ok(r.read_string()?))?;
+        reader.lookup = reader.decode(|r| r.read_lookup_table())?;
+
+        Ok(reader)
+    }
+
+    /// The name of the map stored in its header
+    pub fn map_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn lookup_table(&self) -> &LookupTable {
+        &self.lookup
+    }
+
+    /// How many elements are currently entered
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// The names of every element currently entered, starting from the root
+    pub fn path(&self) -> impl Iterator<Item = &str> {
+        self.stack.iter().map(|e| e.name.as_str())
+    }
+
+    /// Reads the next event, returns `None` once the root element has been left
+    pub fn next_event(&mut self) -> Result<Option<MapEvent>, MapReadError> {
+        if !self.started {
+            self.started = true;
+            return self.enter().map(Some);
+        }
+
+        let Some(current) = self.stack.last_mut() else {
+            return Ok(None);
+        };
+
+        if current.attributes_left > 0 {
+            current.attributes_left -= 1;
+            let attribute = self.decode(|r| r.read_attribute())?;
+            let (name, value) = self.resolve_attribute(attribute)?;
+
+            return Ok(Some(MapEvent::Attribute { name, value }));
+        }
+
+        let children_left = match current.children_left {
+            Some(children) => children,
+            None => self.decode(|r| Ok(r.read_u16()?))?,
+        };
+
+        let current = self.stack.last_mut().unwrap();
+
+        if children_left > 0 {
+            current.children_left = Some(children_left - 1);
+            self.enter().map(Some)
+        } else {
+            self.stack.pop();
+            Ok(Some(MapEvent::Leave))
+        }
+    }
+
+    /// Skips the rest of the current element, including its [Leave](MapEvent::Leave) event
+    pub fn skip_element(&mut self) -> Result<(), MapReadError> {
+        let depth = self.stack.len();
+
+        while self.stack.len() >= depth && depth > 0 {
+            self.next_event()?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the rest of the current element into a [RawMapElement], including its [Leave](MapEvent::Leave) event
+    ///
+    /// Call this directly after [Enter](MapEvent::Enter) to get the whole element.
+    /// Any attributes already read as events won't be included.<br>
+    /// All the strings in the element are resolved.
+    pub fn read_element(&mut self) -> Result<Option<RawMapElement>, MapReadError> {
+        let Some(mut current) = self.stack.pop() else {
+            return Ok(None);
+        };
+
+        let mut element = RawMapElement {
+            name: ResolvableString::String(std::mem::take(&mut current.name)),
+            attributes: Vec::with_capacity(current.attributes_left as usize),
+            children: Vec::new(),
+        };
+
+        for _ in 0 .. current.attributes_left {
+            let attribute = self.decode(|r| r.read_attribute())?;
+            let (name, value) = self.resolve_attribute(attribute)?;
+            element
+                .attributes
+                .push(MapAttribute::new(ResolvableString::String(name), value));
+        }
+
+        let children = match current.children_left {
+            Some(children) => children,
+            None => self.decode(|r| Ok(r.read_u16()?))?,
+        };
+
+        element.children.reserve(children as usize);
+
+        for _ in 0 .. children {
+            let child = self.decode(|r| r.read_element())?;
+            element.children.push(self.resolve_element(child)?);
+        }
+
+        Ok(Some(element))
+    }
+
+    /// Reads the whole map into a [RawMapElement] tree with the strings resolved
+    ///
+    /// If any events were already read this is the same as [read_element](Self::read_element).
+    pub fn read_root(mut self) -> Result<RawMapElement, MapReadError> {
+        if !self.started {
+            self.next_event()?;
+        }
+
+        self.read_element()?
+            .ok_or(MapReadError::BinError(BinReadError::EndOfBuffer))
+    }
+
+    fn enter(&mut self) -> Result<MapEvent, MapReadError> {
+        let (index, attributes_left) =
+            self.decode(|r| Ok((r.read_lookup_index()?, r.read_u8()?)))?;
+        let name = self.lookup_string(index)?;
+
+        self.stack.push(OpenElement {
+            name: name.clone(),
+            attributes_left,
+            children_left: None,
+        });
+
+        Ok(MapEvent::Enter { name })
+    }
+
+    /// Runs `f` on the buffered bytes, reading more from the stream whenever it runs out
+    ///
+    /// The bytes `f` read are only consumed if it succeeds.
+    fn decode<T>(
+        &mut self,
+        f: impl Fn(&mut MapReader) -> Result<T, MapReadError>,
+    ) -> Result<T, MapReadError> {
+        loop {
+            if self.pos < self.buf.len() {
+                let mut reader = MapReader::new(&self.buf[self.pos ..]);
+
+                match f(&mut reader) {
+                    Ok(value) => {
+                        self.pos = self.buf.len() - reader.remaining();
+                        return Ok(value);
+                    }
+                    Err(MapReadError::BinError(BinReadError::EndOfBuffer)) if !self.eof => (),
+                    Err(e) => return Err(e),
+                }
+            } else if self.eof {
+                return Err(MapReadError::BinError(BinReadError::EndOfBuffer));
+            }
+
+            self.fill()?;
+        }
+    }
+
+    /// Drops the decoded bytes and reads at least as many bytes as are still buffered
+    ///
+    /// Growing the buffer like this means an element larger than [CHUNK_SIZE] is only retried a few times.
+    fn fill(&mut self) -> Result<(), MapReadError> {
+        self.buf.drain(.. self.pos);
+        self.pos = 0;
+
+        let wanted = self.buf.len().max(CHUNK_SIZE);
+        let read = (&mut self.reader)
+            .take(wanted as u64)
+            .read_to_end(&mut self.buf)?;
+
+        if read < wanted {
+            self.eof = true;
+        }
+
+        Ok(())
+    }
+
+    fn lookup_string(&self, index: LookupIndex) -> Result<String, MapReadError> {
+        self.lookup
+            .lookup_strings
+            .get(index.0 as usize)
+            .cloned()
+            .ok_or(MapReadError::InvalidLookupIndex(index.0))
+    }
+
+    fn resolve_string(&self, string: ResolvableString) -> Result<String, MapReadError> {
+        match string {
+            ResolvableString::LookupIndex(index) => self.lookup_string(index),
+            ResolvableString::String(string) => Ok(string),
+        }
+    }
+
+    fn resolve_attribute(
+        &self,
+        MapAttribute { name, value }: MapAttribute,
+    ) -> Result<(String, EncodedVar), MapReadError> {
+        let value = match value {
+            EncodedVar::LookupIndex(index) => EncodedVar::String(self.lookup_string(index)?),
+            value => value,
+        };
+
+        Ok((self.resolve_string(name)?, value))
+    }
+
+    /// Resolves every string in `element` and its children, unlike
+    /// [RawMap::resolve_strings](super::RawMap::resolve_strings) invalid indices are an error
+    fn resolve_element(&self, element: RawMapElement) -> Result<RawMapElement, MapReadError> {
+        Ok(RawMapElement {
+            name: ResolvableString::String(self.resolve_string(element.name)?),
+            attributes: element
+                .attributes
+                .into_iter()
+                .map(|attribute| {
+                    let (name, value) = self.resolve_attribute(attribute)?;
+                    Ok(MapAttribute::new(ResolvableString::String(name), value))
+                })
+                .collect::<Result<_, MapReadError>>()?,
+            children: element
+                .children
+                .into_iter()
+                .map(|child| self.resolve_element(child))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
@@ -0,0 +1,570 @@
+//! Bulk edits over a parsed map, like renaming a tileset or replacing an entity everywhere
+//!
+//! Every operation returns a [TransformReport] listing what it changed.
+//!
+//! Entities and triggers are edited by encoding them into a [RawMapElement], changing that and parsing
+//! the result again with the parsers registered on a [MapManager].
+//! If the edited element doesn't parse it's kept as a [RawMapElement] and the error is added to the report.
+use std::{collections::HashSet, error::Error, fmt::Display};
+
+use crate::{
+    maps::{
+        DynMapElement,
+        ErasedMapElement,
+        MapManager,
+        RawMapElement,
+        ResolvableString,
+        elements::{MapRoot, entities::DynEntity, level::Level},
+        parser::MapElementParsingError,
+        var_types::EncodedVar,
+    },
+    utils::num::Integer,
+};
+
+/// Everything changed by a transform
+#[derive(Debug, Default)]
+pub struct TransformReport {
+    pub changes: Vec<Change>,
+    /// Elements that were changed but failed to parse again, these are kept as [RawMapElement]s
+    pub errors: Vec<(String, MapElementParsingError)>,
+}
+
+impl TransformReport {
+    /// The number of elements changed
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub(crate) fn change(
+        &mut self,
+        room: Option<&str>,
+        element: &str,
+        id: Option<i64>,
+        description: impl Into<String>,
+    ) {
+        self.changes.push(Change {
+            room: room.map(str::to_owned),
+            element: element.to_owned(),
+            id,
+            description: description.into(),
+        });
+    }
+}
+
+impl Display for TransformReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+
+        for (element, error) in &self.errors {
+            writeln!(f, "{element}: failed to parse after editing: {error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A single changed element
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// The name of the room the element is in, `None` for elements outside of rooms like filler rects
+    pub room: Option<String>,
+    /// The element name after the change
+    pub element: String,
+    /// The id of the element, for entities and triggers
+    pub id: Option<i64>,
+    pub description: String,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(room) = &self.room {
+            write!(f, "{room}/")?;
+        }
+
+        write!(f, "{}", self.element)?;
+
+        if let Some(id) = self.id {
+            write!(f, "#{id}")?;
+        }
+
+        write!(f, ": {}", self.description)
+    }
+}
+
+/// An error that stopped a transform before it changed anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransformError {
+    /// A new id would be larger than the map format can store, holds the largest id needed
+    IdOutOfRange(i64),
+}
+
+impl Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformError::IdOutOfRange(id) =>
+                write!(f, "Id {id} is too large to be stored in the map"),
+        }
+    }
+}
+
+impl Error for TransformError {}
+
+/// The tile grids of a room
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileLayer {
+    /// The `solids` grid
+    Foreground,
+    /// The `bg` grid
+    Background,
+}
+
+/// How the attributes of an entity change when replacing it with [replace_entity]
+///
+/// Renames are applied first, then removals, then set values.
+#[derive(Debug, Clone, Default)]
+pub struct AttributeMapping {
+    pub rename: Vec<(String, String)>,
+    pub remove: Vec<String>,
+    /// Values added to the entity, replacing any existing value
+    pub set: Vec<(String, EncodedVar)>,
+}
+
+impl AttributeMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rename(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.rename.push((from.into(), to.into()));
+        self
+    }
+
+    pub fn remove(mut self, name: impl Into<String>) -> Self {
+        self.remove.push(name.into());
+        self
+    }
+
+    pub fn set(mut self, name: impl Into<String>, value: impl Into<EncodedVar>) -> Self {
+        self.set.push((name.into(), value.into()));
+        self
+    }
+
+    fn apply(&self, raw: &mut RawMapElement) {
+        for (from, to) in &self.rename {
+            for attr in &mut raw.attributes {
+                if attr.name.as_str() == Some(from) {
+                    attr.name = ResolvableString::String(to.clone());
+                }
+            }
+        }
+
+        raw.attributes
+            .retain(|a| !self.remove.iter().any(|r| a.name.as_str() == Some(r)));
+
+        for (name, value) in &self.set {
+            raw.set_attribute(name, value.clone());
+        }
+    }
+}
+
+/// Replaces every `from` character in a tile grid of every room with `to`
+///
+/// Tileset characters are the ones set in the map's `ForegroundTiles.xml` or `BackgroundTiles.xml`, `0` is air.
+pub fn rename_tile(root: &mut MapRoot, layer: TileLayer, from: char, to: char) -> TransformReport {
+    let mut report = TransformReport::default();
+
+    for level in &mut root.levels.levels {
+        let (grid, element) = match layer {
+            TileLayer::Foreground => (&mut level.solids.inner_text, "solids"),
+            TileLayer::Background => (&mut level.bg.inner_text, "bg"),
+        };
+
+        let Some(grid) = grid else {
+            continue;
+        };
+
+        let count = grid.chars().filter(|c| *c == from).count();
+
+        if count > 0 {
+            *grid = grid.replace(from, to.encode_utf8(&mut [0; 4]));
+            report.change(
+                Some(room_name(level)),
+                element,
+                None,
+                format!("replaced {count} '{from}' tiles with '{to}'"),
+            );
+        }
+    }
+
+    report
+}
+
+/// Replaces every entity named `from` with a `to` entity, changing its attributes with `mapping`
+///
+/// The new entities are parsed with the parsers registered on `manager`.
+pub fn replace_entity(
+    root: &mut MapRoot,
+    manager: &MapManager,
+    from: &str,
+    to: &str,
+    mapping: &AttributeMapping,
+) -> TransformReport {
+    let mut report = TransformReport::default();
+
+    for level in &mut root.levels.levels {
+        let room = room_name(level).to_owned();
+        let Some(entities) = &mut level.entities else {
+            continue;
+        };
+
+        for entity in &mut entities.entities {
+            if entity.name() != from {
+                continue;
+            }
+
+            let id = entity.id().as_i64();
+
+            edit_entity(entity, manager, &mut report, |raw| {
+                raw.name = ResolvableString::String(to.to_owned());
+                mapping.apply(raw);
+                true
+            });
+
+            report.change(Some(&room), to, Some(id), format!("replaced {from}"));
+        }
+    }
+
+    report
+}
+
+/// Renames a flag in every entity and trigger
+///
+/// Any string attribute with `flag` in its name is checked, these can hold a comma separated list of flags
+/// which can be inverted with a leading `!`, like `flag_a,!flag_b`.
+pub fn rename_flag(
+    root: &mut MapRoot,
+    manager: &MapManager,
+    from: &str,
+    to: &str,
+) -> TransformReport {
+    let mut report = TransformReport::default();
+
+    let rename = |raw: &mut RawMapElement, renamed: &mut Vec<String>| {
+        for attr in &mut raw.attributes {
+            let Some(name) = attr.name.as_str() else {
+                continue;
+            };
+
+            if !name.to_ascii_lowercase().contains("flag") {
+                continue;
+            }
+
+            let EncodedVar::String(value) = &attr.value else {
+                continue;
+            };
+
+            let mut changed = false;
+            let flags = value
+                .split(',')
+                .map(|flag| {
+                    let trimmed = flag.trim();
+                    let (prefix, name) = match trimmed.strip_prefix('!') {
+                        Some(name) => ("!", name),
+                        None => ("", trimmed),
+                    };
+
+                    if name == from {
+                        changed = true;
+                        format!("{prefix}{to}")
+                    } else {
+                        flag.to_owned()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+
+            if changed {
+                renamed.push(name.to_owned());
+                attr.value = EncodedVar::String(flags);
+            }
+        }
+    };
+
+    for level in &mut root.levels.levels {
+        let room = room_name(level).to_owned();
+
+        if let Some(entities) = &mut level.entities {
+            for entity in &mut entities.entities {
+                let mut renamed = Vec::new();
+                let id = entity.id().as_i64();
+
+                edit_entity(entity, manager, &mut report, |raw| {
+                    rename(raw, &mut renamed);
+                    !renamed.is_empty()
+                });
+
+                if !renamed.is_empty() {
+                    report.change(
+                        Some(&room),
+                        entity.name(),
+                        Some(id),
+                        format!("renamed flag in {}", renamed.join(", ")),
+                    );
+                }
+            }
+        }
+
+        if let Some(triggers) = &mut level.triggers {
+            for trigger in &mut triggers.triggers {
+                let mut renamed = Vec::new();
+                let id = element_id(trigger.as_ref());
+
+                edit_element(trigger, manager, &mut report, |raw| {
+                    rename(raw, &mut renamed);
+                    !renamed.is_empty()
+                });
+
+                if !renamed.is_empty() {
+                    report.change(
+                        Some(&room),
+                        trigger.name(),
+                        id,
+                        format!("renamed flag in {}", renamed.join(", ")),
+                    );
+                }
+            }
+        }
+    }
+
+    report
+}
+
+/// Moves every room and filler rect by an amount of pixels
+///
+/// Filler rects are positioned in tiles, so the offset is snapped to the nearest multiple of 8,
+/// this is added to the report when it happens.<br>
+/// Entities, triggers and decals are positioned relative to their room and don't need to change.
+pub fn offset_rooms(root: &mut MapRoot, x: i32, y: i32) -> TransformReport {
+    let mut report = TransformReport::default();
+    let (tiles_x, tiles_y) = (to_tiles(x), to_tiles(y));
+
+    if (tiles_x * 8, tiles_y * 8) != (x, y) {
+        report.change(
+            None,
+            "Map",
+            None,
+            format!(
+                "snapped the offset ({x}, {y}) to ({}, {})",
+                tiles_x * 8,
+                tiles_y * 8
+            ),
+        );
+    }
+
+    for level in &mut root.levels.levels {
+        level.x += Integer::from(tiles_x * 8);
+        level.y += Integer::from(tiles_y * 8);
+
+        report.change(
+            Some(room_name(level)),
+            "level",
+            None,
+            format!("moved to ({}, {})", level.x, level.y),
+        );
+    }
+
+    if let Some(filler) = &mut root.filler {
+        for rect in &mut filler.filler {
+            rect.x += Integer::from(tiles_x);
+            rect.y += Integer::from(tiles_y);
+
+            report.change(
+                None,
+                "rect",
+                None,
+                format!("moved to ({}, {})", rect.x, rect.y),
+            );
+        }
+    }
+
+    report
+}
+
+/// Gives every entity and trigger a unique id
+///
+/// The first element using an id keeps it, any later ones are given new ids above the highest id in the map.
+///
+/// Returns [IdOutOfRange](TransformError::IdOutOfRange) without changing anything if the new ids don't fit in an `i32`.
+pub fn renumber_ids(
+    root: &mut MapRoot,
+    manager: &MapManager,
+) -> Result<TransformReport, TransformError> {
+    let mut report = TransformReport::default();
+
+    let ids = root
+        .levels
+        .levels
+        .iter()
+        .flat_map(level_ids)
+        .collect::<Vec<_>>();
+    let duplicates = ids.len() - ids.iter().collect::<HashSet<_>>().len();
+
+    if duplicates == 0 {
+        return Ok(report);
+    }
+
+    let first_id = 1 + ids.iter().max().copied().unwrap_or(0);
+    let last_id = first_id + duplicates as i64 - 1;
+    let (Ok(mut next_id), Ok(_)) = (i32::try_from(first_id), i32::try_from(last_id)) else {
+        return Err(TransformError::IdOutOfRange(last_id));
+    };
+
+    let mut used = HashSet::new();
+
+    for level in &mut root.levels.levels {
+        let room = room_name(level).to_owned();
+
+        if let Some(entities) = &mut level.entities {
+            for entity in &mut entities.entities {
+                let id = entity.id().as_i64();
+
+                if used.insert(id) {
+                    continue;
+                }
+
+                let new_id = next_id;
+                next_id += 1;
+                used.insert(new_id as i64);
+
+                edit_entity(entity, manager, &mut report, |raw| {
+                    raw.set_attribute("id", EncodedVar::Int(new_id));
+                    true
+                });
+
+                report.change(
+                    Some(&room),
+                    entity.name(),
+                    Some(new_id as i64),
+                    format!("renumbered from {id}"),
+                );
+            }
+        }
+
+        if let Some(triggers) = &mut level.triggers {
+            for trigger in &mut triggers.triggers {
+                let Some(id) = element_id(trigger.as_ref()) else {
+                    continue;
+                };
+
+                if used.insert(id) {
+                    continue;
+                }
+
+                let new_id = next_id;
+                next_id += 1;
+                used.insert(new_id as i64);
+
+                edit_element(trigger, manager, &mut report, |raw| {
+                    raw.set_attribute("id", EncodedVar::Int(new_id));
+                    true
+                });
+
+                report.change(
+                    Some(&room),
+                    trigger.name(),
+                    Some(new_id as i64),
+                    format!("renumbered from {id}"),
+                );
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Encodes an entity, runs `edit` on it and parses it again if `edit` returns `true`
+pub(crate) fn edit_entity(
+    entity: &mut DynEntity,
+    manager: &MapManager,
+    report: &mut TransformReport,
+    edit: impl FnOnce(&mut RawMapElement) -> bool,
+) {
+    let mut raw = (entity.as_ref() as &dyn ErasedMapElement).encode_raw();
+
+    if !edit(&mut raw) {
+        return;
+    }
+
+    *entity = match manager.parse_entity(&raw) {
+        Ok(entity) => entity,
+        Err(e) => {
+            report
+                .errors
+                .push((raw.name.as_str().unwrap_or_default().to_owned(), e));
+            Box::new(raw)
+        }
+    };
+}
+
+/// Encodes an element, runs `edit` on it and parses it again if `edit` returns `true`
+pub(crate) fn edit_element(
+    element: &mut DynMapElement,
+    manager: &MapManager,
+    report: &mut TransformReport,
+    edit: impl FnOnce(&mut RawMapElement) -> bool,
+) {
+    let mut raw = element.as_ref().encode_raw();
+
+    if !edit(&mut raw) {
+        return;
+    }
+
+    *element = match manager.parse_dyn_element(&raw) {
+        Ok(element) => element,
+        Err(e) => {
+            report
+                .errors
+                .push((raw.name.as_str().unwrap_or_default().to_owned(), e));
+            Box::new(raw)
+        }
+    };
+}
+
+/// Gets the `id` attribute of an entity or trigger
+pub(crate) fn element_id(element: &dyn ErasedMapElement) -> Option<i64> {
+    element
+        .get_field("id")
+        .and_then(|v| v.int().ok())
+        .map(|i| i.as_i64())
+}
+
+/// The ids of every entity and trigger in a level
+pub(crate) fn level_ids(level: &Level) -> Vec<i64> {
+    let entities = level
+        .entities
+        .iter()
+        .flat_map(|e| &e.entities)
+        .map(|e| Some(e.id().as_i64()));
+    let triggers = level
+        .triggers
+        .iter()
+        .flat_map(|t| &t.triggers)
+        .map(|t| element_id(t.as_ref()));
+
+    entities.chain(triggers).flatten().collect()
+}
+
+/// Converts pixels to the nearest amount of tiles
+pub(crate) fn to_tiles(pixels: i32) -> i32 {
+    pixels.saturating_add(4).div_euclid(8)
+}
+
+pub(crate) fn room_name(level: &Level) -> &str {
+    level.name.as_str().unwrap_or_default()
+}
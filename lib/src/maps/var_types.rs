@@ -8,7 +8,7 @@ use crate::{
     utils::num::{Float, Integer},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum EncodedVar {
     Bool(bool),
     Byte(u8),
@@ -151,7 +151,7 @@ pub struct EncodedVarError {
 }
 
 impl EncodedVarError {
-    fn new(expected: &'static str, found: &'static str) -> Self {
+    pub(crate) fn new(expected: &'static str, found: &'static str) -> Self {
         EncodedVarError { expected, found }
     }
 }
@@ -282,7 +282,7 @@ impl<'a> TryFrom<&'a EncodedVar> for ResolvableString {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 /// A character
 ///
 /// Implementation wise it is possible for the `String` variant to not be length one.
@@ -0,0 +1,175 @@
+//! Traits for walking the parsed map element tree
+//!
+//! Implement [MapVisitor] or [MapVisitorMut], overriding only the methods for the elements you care about,
+//! and start the walk with [visit] or [visit_mut]. Every method walks into the element's children by default,
+//! so when overriding one call [walk_element] (or [walk_element_mut]) to keep walking below it.
+//!
+//! ```ignore
+//! struct CountSpinners(usize);
+//!
+//! impl MapVisitor for CountSpinners {
+//!     fn visit_entity(&mut self, entity: &dyn ErasedEntity) {
+//!         if entity.name() == "spinner" {
+//!             self.0 += 1;
+//!         }
+//!     }
+//! }
+//!
+//! let mut counter = CountSpinners(0);
+//! visit(&mut counter, &map_root);
+//! ```
+//!
+//! The children of an element come from [MapElement::child_elements](super::MapElement::child_elements),
+//! which the [MapElement](celeste_rs_macros::MapElement) derive fills in from the `child`, `dyn_child`,
+//! `dyn_entities` and `extra` fields.
+use std::any::Any;
+
+use crate::maps::{
+    ErasedMapElement,
+    RawMapElement,
+    elements::{
+        entities::ErasedEntity,
+        level::{Decal, Level, Triggers},
+    },
+};
+
+/// A reference to a child of a map element
+pub enum ChildElement<'a> {
+    /// An element stored in a `child` field, or an unknown child kept in an `extra` field
+    Child(&'a dyn ErasedMapElement),
+    /// An element stored in a `dyn_child` field
+    DynChild(&'a dyn ErasedMapElement),
+    /// An entity stored in a `dyn_entities` field
+    Entity(&'a dyn ErasedEntity),
+}
+
+/// A mutable reference to a child of a map element
+pub enum ChildElementMut<'a> {
+    /// An element stored in a `child` field, or an unknown child kept in an `extra` field
+    Child(&'a mut dyn ErasedMapElement),
+    /// An element stored in a `dyn_child` field
+    DynChild(&'a mut dyn ErasedMapElement),
+    /// An entity stored in a `dyn_entities` field
+    Entity(&'a mut dyn ErasedEntity),
+}
+
+/// Walks through a map element tree
+///
+/// Triggers are the dynamic children of [Triggers], raw elements are any elements that weren't parsed into a typed struct.
+pub trait MapVisitor {
+    /// Called for any element that doesn't have a more specific method
+    fn visit_element(&mut self, element: &dyn ErasedMapElement) {
+        walk_element(self, element)
+    }
+
+    fn visit_level(&mut self, level: &Level) {
+        walk_element(self, level)
+    }
+
+    fn visit_entity(&mut self, entity: &dyn ErasedEntity) {
+        walk_element(self, entity)
+    }
+
+    fn visit_trigger(&mut self, trigger: &dyn ErasedMapElement) {
+        walk_element(self, trigger)
+    }
+
+    fn visit_decal(&mut self, decal: &Decal) {
+        walk_element(self, decal)
+    }
+
+    fn visit_raw(&mut self, raw: &RawMapElement) {
+        walk_element(self, raw)
+    }
+}
+
+/// Walks through a map element tree, allowing the elements to be changed
+///
+/// Works the same as [MapVisitor].
+pub trait MapVisitorMut {
+    /// Called for any element that doesn't have a more specific method
+    fn visit_element(&mut self, element: &mut dyn ErasedMapElement) {
+        walk_element_mut(self, element)
+    }
+
+    fn visit_level(&mut self, level: &mut Level) {
+        walk_element_mut(self, level)
+    }
+
+    fn visit_entity(&mut self, entity: &mut dyn ErasedEntity) {
+        walk_element_mut(self, entity)
+    }
+
+    fn visit_trigger(&mut self, trigger: &mut dyn ErasedMapElement) {
+        walk_element_mut(self, trigger)
+    }
+
+    fn visit_decal(&mut self, decal: &mut Decal) {
+        walk_element_mut(self, decal)
+    }
+
+    fn visit_raw(&mut self, raw: &mut RawMapElement) {
+        walk_element_mut(self, raw)
+    }
+}
+
+/// Calls the most specific method of `visitor` for `element`
+pub fn visit<V: MapVisitor + ?Sized>(visitor: &mut V, element: &dyn ErasedMapElement) {
+    let any = element as &dyn Any;
+
+    if let Some(level) = any.downcast_ref::<Level>() {
+        visitor.visit_level(level)
+    } else if let Some(decal) = any.downcast_ref::<Decal>() {
+        visitor.visit_decal(decal)
+    } else if let Some(raw) = any.downcast_ref::<RawMapElement>() {
+        visitor.visit_raw(raw)
+    } else {
+        visitor.visit_element(element)
+    }
+}
+
+/// Visits every child of `element`
+pub fn walk_element<V: MapVisitor + ?Sized>(visitor: &mut V, element: &dyn ErasedMapElement) {
+    let in_triggers = (element as &dyn Any).is::<Triggers>();
+
+    for child in element.child_elements() {
+        match child {
+            ChildElement::Child(child) => visit(visitor, child),
+            ChildElement::DynChild(trigger) if in_triggers => visitor.visit_trigger(trigger),
+            ChildElement::DynChild(child) => visit(visitor, child),
+            ChildElement::Entity(entity) => visitor.visit_entity(entity),
+        }
+    }
+}
+
+/// Calls the most specific method of `visitor` for `element`
+pub fn visit_mut<V: MapVisitorMut + ?Sized>(visitor: &mut V, element: &mut dyn ErasedMapElement) {
+    let any = element as &mut dyn Any;
+
+    if let Some(level) = any.downcast_mut::<Level>() {
+        visitor.visit_level(level)
+    } else if let Some(decal) = any.downcast_mut::<Decal>() {
+        visitor.visit_decal(decal)
+    } else if let Some(raw) = any.downcast_mut::<RawMapElement>() {
+        visitor.visit_raw(raw)
+    } else {
+        visitor.visit_element(element)
+    }
+}
+
+/// Visits every child of `element`, allowing them to be changed
+pub fn walk_element_mut<V: MapVisitorMut + ?Sized>(
+    visitor: &mut V,
+    element: &mut dyn ErasedMapElement,
+) {
+    let in_triggers = (element as &dyn Any).is::<Triggers>();
+
+    for child in element.child_elements_mut() {
+        match child {
+            ChildElementMut::Child(child) => visit_mut(visitor, child),
+            ChildElementMut::DynChild(trigger) if in_triggers => visitor.visit_trigger(trigger),
+            ChildElementMut::DynChild(child) => visit_mut(visitor, child),
+            ChildElementMut::Entity(entity) => visitor.visit_entity(entity),
+        }
+    }
+}
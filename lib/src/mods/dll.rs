@@ -4,7 +4,28 @@ use std::{
 };
 
 use aliasable::boxed::AliasableBox;
-use dotnetdll::{dll::DLLError, prelude::Resolution};
+use dotnetdll::{
+    dll::DLLError,
+    prelude::{
+        AlwaysFailsResolver,
+        Attribute,
+        BaseType,
+        FixedArg,
+        Instruction,
+        MemberType,
+        MethodReferenceParent,
+        MethodSource,
+        MethodType,
+        Resolution,
+        TypeIndex,
+        TypeSource,
+        UserMethod,
+        UserType,
+    },
+};
+
+/// The full name of the `[Tracked]` attribute, which lives in Monocle rather than Celeste
+const TRACKED_ATTRIBUTE: &str = "Monocle.Tracked";
 
 /// A self-referential type to hold the data used by [Resolution]
 ///
@@ -47,6 +68,254 @@ impl BufferedDLL {
     }
 }
 
+/// A type marked with Everest's `[CustomEntity]` attribute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomEntityType {
+    /// The full name of the type, including the namespace
+    pub type_name: String,
+    /// The names this type is registered under, which is what appears in map files
+    ///
+    /// Any generator method (`"name = Method"`) is stripped from these.
+    pub names: Vec<String>,
+    /// Whether the type inherits from `Celeste.Trigger`
+    pub is_trigger: bool,
+    /// Whether the type is also marked `[Tracked]`
+    pub tracked: bool,
+}
+
+/// Whether a hook is an `On` hook or an `IL` hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    On,
+    IL,
+}
+
+/// A MonoMod hook a mod adds, found from calls to `add_*` methods on `On.*` and `IL.*` types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hook {
+    pub kind: HookKind,
+    /// The hooked type, with the `On.` or `IL.` prefix removed, e.g. `Celeste.Player`
+    pub target_type: String,
+    /// The name of the hooked method
+    pub method: String,
+}
+
+impl BufferedDLL {
+    /// Gets the full names of every type that subclasses `Celeste.Mod.EverestModule`
+    ///
+    /// Mods should only have one of these, but nothing stops them from having more.
+    pub fn everest_modules(&self) -> Vec<String> {
+        self.enumerate_type_definitions()
+            .filter(|(idx, _)| self.inherits_from(*idx, "Celeste.Mod.EverestModule"))
+            .map(|(_, ty)| ty.type_name())
+            .collect()
+    }
+
+    /// Gets every type marked with `[CustomEntity]`
+    pub fn custom_entities(&self) -> Vec<CustomEntityType> {
+        let mut output = Vec::new();
+
+        for (idx, ty) in self.enumerate_type_definitions() {
+            let mut names = Vec::new();
+            let mut tracked = false;
+
+            for attr in &ty.attributes {
+                match self.attribute_name(attr).as_deref() {
+                    Some("Celeste.Mod.Entities.CustomEntityAttribute") =>
+                        names.extend(self.attribute_strings(attr)),
+                    Some(TRACKED_ATTRIBUTE) => tracked = true,
+                    _ => {}
+                }
+            }
+
+            if names.is_empty() {
+                continue;
+            }
+
+            output.push(CustomEntityType {
+                type_name: ty.type_name(),
+                names: names
+                    .iter()
+                    .map(|n| n.split('=').next().unwrap_or_default().trim().to_owned())
+                    .collect(),
+                is_trigger: self.inherits_from(idx, "Celeste.Trigger"),
+                tracked,
+            });
+        }
+
+        output
+    }
+
+    /// Gets the full names of every type marked with `[Tracked]`
+    pub fn tracked_types(&self) -> Vec<String> {
+        self.type_definitions
+            .iter()
+            .filter(|ty| {
+                ty.attributes.iter().any(|a| {
+                    self.attribute_name(a)
+                        .is_some_and(|n| n == TRACKED_ATTRIBUTE)
+                })
+            })
+            .map(|ty| ty.type_name())
+            .collect()
+    }
+
+    /// Gets every entity name the mod registers, not including triggers
+    pub fn entity_names(&self) -> Vec<String> {
+        self.custom_entities()
+            .into_iter()
+            .filter(|e| !e.is_trigger)
+            .flat_map(|e| e.names)
+            .collect()
+    }
+
+    /// Gets every trigger name the mod registers
+    pub fn trigger_names(&self) -> Vec<String> {
+        self.custom_entities()
+            .into_iter()
+            .filter(|e| e.is_trigger)
+            .flat_map(|e| e.names)
+            .collect()
+    }
+
+    /// Finds every `On` and `IL` hook the mod adds in any of its methods
+    pub fn hooks(&self) -> Vec<Hook> {
+        let mut output = Vec::new();
+
+        let instructions = self
+            .type_definitions
+            .iter()
+            .flat_map(|ty| &ty.methods)
+            .filter_map(|m| m.body.as_ref())
+            .flat_map(|b| &b.instructions);
+
+        for instruction in instructions {
+            let (Instruction::Call { param0: source, .. }
+            | Instruction::CallVirtual { param0: source, .. }
+            | Instruction::CallVirtualTail(source)) = instruction
+            else {
+                continue;
+            };
+
+            let UserMethod::Reference(method) = (match source {
+                MethodSource::User(m) => m,
+                MethodSource::Generic(g) => &g.base,
+            }) else {
+                continue;
+            };
+
+            let method = &self[*method];
+            let MethodReferenceParent::Type(MethodType::Base(base)) = &method.parent else {
+                continue;
+            };
+            let BaseType::Type { source, .. } = &**base else {
+                continue;
+            };
+            let Some(hooked) = method.name.strip_prefix("add_") else {
+                continue;
+            };
+
+            let type_name = self.source_name(source);
+            let (kind, target_type) = if let Some(target) = type_name.strip_prefix("On.") {
+                (HookKind::On, target)
+            } else if let Some(target) = type_name.strip_prefix("IL.") {
+                (HookKind::IL, target)
+            } else {
+                continue;
+            };
+
+            let hook = Hook {
+                kind,
+                target_type: target_type.to_owned(),
+                method: hooked.to_owned(),
+            };
+
+            if !output.contains(&hook) {
+                output.push(hook);
+            }
+        }
+
+        output
+    }
+
+    /// Checks if a type inherits from `base_name`, following base types defined in this dll
+    fn inherits_from(&self, idx: TypeIndex, base_name: &str) -> bool {
+        let mut current = idx;
+
+        // Bounded by the type count, so a malformed dll with an inheritance loop can't hang us
+        for _ in 0 .. self.type_definitions.len() {
+            let Some(extends) = &self[current].extends else {
+                return false;
+            };
+
+            if self.member_source_name(extends) == base_name {
+                return true;
+            }
+
+            match extends {
+                TypeSource::User(UserType::Definition(next))
+                | TypeSource::Generic {
+                    base: UserType::Definition(next),
+                    ..
+                } => current = *next,
+                _ => return false,
+            }
+        }
+
+        false
+    }
+
+    fn member_source_name(&self, source: &TypeSource<MemberType>) -> String {
+        match source {
+            TypeSource::User(u) | TypeSource::Generic { base: u, .. } => u.type_name(self),
+        }
+    }
+
+    fn source_name(&self, source: &TypeSource<MethodType>) -> String {
+        match source {
+            TypeSource::User(u) | TypeSource::Generic { base: u, .. } => u.type_name(self),
+        }
+    }
+
+    /// Gets the full name of the type an attribute constructs
+    fn attribute_name(&self, attr: &Attribute) -> Option<String> {
+        match &attr.constructor {
+            UserMethod::Definition(m) => Some(self[m.parent_type()].type_name()),
+            UserMethod::Reference(r) => match &self[*r].parent {
+                MethodReferenceParent::Type(MethodType::Base(base)) => match &**base {
+                    BaseType::Type { source, .. } => Some(self.source_name(source)),
+                    _ => None,
+                },
+                _ => None,
+            },
+        }
+    }
+
+    /// Gets all the string constructor arguments of an attribute, including ones in `params` arrays
+    fn attribute_strings(&self, attr: &Attribute) -> Vec<String> {
+        let Ok(data) = attr.instantiation_data(&AlwaysFailsResolver, self) else {
+            return Vec::new();
+        };
+
+        let mut output = Vec::new();
+        for arg in data.constructor_args {
+            match arg {
+                FixedArg::String(Some(s)) => output.push(s.to_owned()),
+                FixedArg::Array(Some(args)) => output.extend(args.into_iter().filter_map(|a| {
+                    if let FixedArg::String(Some(s)) = a {
+                        Some(s.to_owned())
+                    } else {
+                        None
+                    }
+                })),
+                _ => {}
+            }
+        }
+
+        output
+    }
+}
+
 impl Deref for BufferedDLL {
     type Target = Resolution<'static>;
 
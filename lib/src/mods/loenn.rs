@@ -0,0 +1,914 @@
+//! Imports entity and trigger definitions from Lönn plugins
+//!
+//! Lönn plugins are lua files in `Loenn/entities` and `Loenn/triggers`. We don't run any lua,
+//! instead we read the table literals assigned to the plugin's `name`, `placements`, `nodeLimits`,
+//! `fieldInformation` and `fieldOrder` fields. Anything computed at runtime is skipped, so plugins which
+//! generate their placements in loops will produce schemas with fewer attributes.
+use std::{error::Error, fmt::Display};
+
+use crate::maps::{
+    schema::{AttributeKind, AttributeSchema, COMMON_ATTRIBUTES, EntitySchema},
+    var_types::EncodedVar,
+};
+
+/// Reads the schemas of every entity or trigger defined in a Lönn plugin
+///
+/// A single plugin file can define multiple handlers by returning a list of them.<br>
+/// Code we can't evaluate is skipped, but source that isn't valid lua returns an error.
+pub fn schemas_from_lua(src: &str, is_trigger: bool) -> Result<Vec<EntitySchema>, LoennError> {
+    let mut parser = LuaParser {
+        tokens: tokenize(src)?,
+        pos: 0,
+        depth: 0,
+        locals: LuaTable::default(),
+        error: None,
+    };
+
+    let returned = parser.parse_chunk();
+
+    if let Some(error) = parser.error {
+        return Err(error);
+    }
+
+    let handlers = match returned {
+        Some(LuaValue::Table(table)) if table.get("name").is_some() => vec![table],
+        Some(LuaValue::Table(table)) => table
+            .array
+            .into_iter()
+            .filter_map(|v| match v {
+                LuaValue::Table(t) => Some(t),
+                _ => None,
+            })
+            .collect(),
+        // Without a return value we fall back to every local that looks like a handler
+        _ => parser
+            .locals
+            .fields
+            .into_iter()
+            .filter_map(|(_, v)| match v {
+                LuaValue::Table(t) if matches!(t.get("name"), Some(LuaValue::String(_))) => Some(t),
+                _ => None,
+            })
+            .collect(),
+    };
+
+    Ok(handlers
+        .iter()
+        .filter_map(|h| handler_to_schema(h, is_trigger))
+        .collect())
+}
+
+/// Errors from reading a Lönn plugin that isn't valid lua
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoennError {
+    /// A quoted string is missing its closing quote before the end of the line
+    UnterminatedString { line: usize },
+    /// A long string or comment (`[[ ]]`, `--[[ ]]`) is never closed
+    UnterminatedLongBracket { line: usize },
+    /// The file ended inside a table, bracket, or block
+    UnexpectedEof,
+    /// Tables are nested deeper than we're willing to parse
+    TooDeep,
+}
+
+impl Display for LoennError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoennError::UnterminatedString { line } =>
+                write!(f, "Unterminated string on line {line}"),
+            LoennError::UnterminatedLongBracket { line } => write!(
+                f,
+                "Unterminated long string or comment starting on line {line}"
+            ),
+            LoennError::UnexpectedEof => write!(f, "Unexpected end of file"),
+            LoennError::TooDeep => write!(f, "Tables are nested more than {MAX_DEPTH} deep"),
+        }
+    }
+}
+
+impl Error for LoennError {}
+
+fn handler_to_schema(handler: &LuaTable, is_trigger: bool) -> Option<EntitySchema> {
+    let LuaValue::String(name) = handler.get("name")? else {
+        return None;
+    };
+
+    let mut schema = EntitySchema::new(name, is_trigger);
+
+    match handler.get("nodeLimits") {
+        Some(LuaValue::Table(limits)) => {
+            if let Some(LuaValue::Number(min, _)) = limits.array.first() {
+                schema.min_nodes = min.max(0.0) as usize;
+            }
+
+            schema.max_nodes = match limits.array.get(1) {
+                Some(LuaValue::Number(max, _)) if *max >= 0.0 => Some(*max as usize),
+                _ => None,
+            };
+        }
+        // Lönn defaults to no nodes, but if the limits are computed we can't know them
+        Some(_) => schema.max_nodes = None,
+        None => {}
+    }
+
+    let placements = match handler.get("placements") {
+        Some(LuaValue::Table(t)) if t.get("data").is_some() || t.get("name").is_some() => vec![t],
+        Some(LuaValue::Table(t)) => t
+            .array
+            .iter()
+            .filter_map(|p| match p {
+                LuaValue::Table(t) => Some(t),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    for placement in placements {
+        let Some(LuaValue::Table(data)) = placement.get("data") else {
+            continue;
+        };
+
+        for (key, value) in &data.fields {
+            if COMMON_ATTRIBUTES.contains(&key.as_str()) || schema.get_attribute(key).is_some() {
+                continue;
+            }
+
+            let Some(default) = lua_to_var(value) else {
+                continue;
+            };
+
+            schema.add_attribute(AttributeSchema::with_default(key, default));
+        }
+    }
+
+    if let Some(LuaValue::Table(info)) = handler.get("fieldInformation") {
+        for (key, value) in &info.fields {
+            let LuaValue::Table(field) = value else {
+                continue;
+            };
+            let Some(attribute) = schema.attributes.iter_mut().find(|a| &a.name == key) else {
+                continue;
+            };
+
+            if let Some(LuaValue::String(field_type)) = field.get("fieldType") {
+                attribute.kind = match field_type.as_str() {
+                    "integer" => AttributeKind::Integer,
+                    "number" => AttributeKind::Float,
+                    "boolean" => AttributeKind::Bool,
+                    _ => AttributeKind::String,
+                };
+            }
+
+            // Options are either a list of values, a list of `{name, value}` pairs,
+            // or a table from display names to values
+            if let Some(LuaValue::Table(options)) = field.get("options") {
+                attribute.options = options
+                    .array
+                    .iter()
+                    .map(|option| match option {
+                        LuaValue::Table(pair) => pair.array.get(1).unwrap_or(&LuaValue::Nil),
+                        value => value,
+                    })
+                    .chain(options.fields.iter().map(|(_, value)| value))
+                    .filter_map(lua_to_var)
+                    .collect();
+            }
+
+            if let Some(LuaValue::Bool(editable)) = field.get("editable") {
+                attribute.editable = *editable;
+            }
+
+            if attribute.kind == AttributeKind::Float {
+                for var in attribute.default.iter_mut().chain(&mut attribute.options) {
+                    if let EncodedVar::Int(i) = *var {
+                        *var = EncodedVar::Float(i as f32);
+                    }
+                }
+            }
+        }
+    }
+
+    // Attributes in `fieldOrder` come first, in that order
+    if let Some(LuaValue::Table(order)) = handler.get("fieldOrder") {
+        schema.attributes.sort_by_key(|a| {
+            order
+                .array
+                .iter()
+                .position(|v| matches!(v, LuaValue::String(name) if *name == a.name))
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    Some(schema)
+}
+
+/// Converts a lua value to the [EncodedVar] it would be saved as in a map
+fn lua_to_var(value: &LuaValue) -> Option<EncodedVar> {
+    Some(match value {
+        LuaValue::Bool(b) => EncodedVar::Bool(*b),
+        LuaValue::String(s) => EncodedVar::String(s.clone()),
+        LuaValue::Number(n, false) if n.fract() == 0.0 && n.abs() <= i32::MAX as f64 =>
+            EncodedVar::Int(*n as i32),
+        LuaValue::Number(n, _) => EncodedVar::Float(*n as f32),
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    /// The value and whether it was written as a float
+    Num(f64, bool),
+    Sym(&'static str),
+}
+
+// Longer symbols come first so they get matched before their prefixes
+const SYMBOLS: [&str; 28] = [
+    "...", "..", "==", "~=", "<=", ">=", "::", "//", "{", "}", "(", ")", "[", "]", "=", ",", ";",
+    ".", ":", "+", "-", "*", "/", "%", "^", "#", "<", ">",
+];
+
+/// How deep tables can be nested before we give up, so malicious plugins can't overflow the stack
+const MAX_DEPTH: usize = 200;
+
+fn tokenize(src: &str) -> Result<Vec<Token>, LoennError> {
+    let chars = src.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let line_of = |i: usize| chars[.. i].iter().filter(|c| **c == '\n').count() + 1;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            i += 2;
+            match long_bracket_end(&chars, i) {
+                Some(LongBracket::Closed(end)) => i = end,
+                Some(LongBracket::Unterminated) =>
+                    return Err(LoennError::UnterminatedLongBracket { line: line_of(i) }),
+                None =>
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    },
+            }
+        } else if c == '[' && matches!(chars.get(i + 1), Some('[' | '=')) {
+            let start = i;
+            match long_bracket_end(&chars, i) {
+                Some(LongBracket::Closed(end)) => {
+                    let level = chars[start + 1 ..]
+                        .iter()
+                        .take_while(|c| **c == '=')
+                        .count();
+                    let content = chars[start + level + 2 .. end - level - 2]
+                        .iter()
+                        .collect::<String>();
+                    // A newline directly after the opening bracket is skipped
+                    let content = content.strip_prefix('\n').unwrap_or(&content);
+                    tokens.push(Token::Str(content.to_owned()));
+                    i = end;
+                }
+                Some(LongBracket::Unterminated) =>
+                    return Err(LoennError::UnterminatedLongBracket {
+                        line: line_of(start),
+                    }),
+                None => {
+                    tokens.push(Token::Sym("["));
+                    i += 1;
+                }
+            }
+        } else if c == '"' || c == '\'' {
+            let start = i;
+            let mut buf = String::new();
+            i += 1;
+
+            loop {
+                match chars.get(i) {
+                    Some(q) if *q == c => break,
+                    None | Some('\n') =>
+                        return Err(LoennError::UnterminatedString {
+                            line: line_of(start),
+                        }),
+                    Some('\\') => i = read_escape(&chars, i + 1, &mut buf),
+                    Some(other) => {
+                        buf.push(*other);
+                        i += 1;
+                    }
+                }
+            }
+
+            tokens.push(Token::Str(buf));
+            i += 1;
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = i;
+            let hex = c == '0' && matches!(chars.get(i + 1), Some('x' | 'X'));
+            if hex {
+                i += 2;
+            }
+
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric()
+                    || chars[i] == '.'
+                    || (!hex && matches!(chars[i], '+' | '-') && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+
+            let text = chars[start .. i].iter().collect::<String>();
+            let value = if hex {
+                i64::from_str_radix(&text[2 ..], 16).map(|v| v as f64).ok()
+            } else {
+                text.parse::<f64>().ok()
+            };
+
+            if let Some(value) = value {
+                tokens.push(Token::Num(value, !hex && text.contains(['.', 'e', 'E'])));
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+
+            tokens.push(Token::Ident(chars[start .. i].iter().collect()));
+        } else {
+            let rest = &chars[i ..];
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| s.len() <= rest.len() && s.chars().zip(rest).all(|(a, b)| a == *b));
+
+            // Unknown characters are skipped
+            if let Some(symbol) = symbol {
+                tokens.push(Token::Sym(symbol));
+                i += symbol.len();
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Reads the escape sequence after a `\\` at `start` into `buf`, returning the index after it
+fn read_escape(chars: &[char], start: usize, buf: &mut String) -> usize {
+    let mut i = start + 1;
+
+    let escaped = match chars.get(start) {
+        Some('n') => '\n',
+        Some('t') => '\t',
+        Some('r') => '\r',
+        Some('a') => '\u{7}',
+        Some('b') => '\u{8}',
+        Some('f') => '\u{c}',
+        Some('v') => '\u{b}',
+        // `\z` skips the whitespace after it
+        Some('z') => {
+            while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+                i += 1;
+            }
+            return i;
+        }
+        Some('x') => {
+            let hex = chars[i ..].iter().take(2).collect::<String>();
+            let Ok(value) = u8::from_str_radix(&hex, 16) else {
+                return i;
+            };
+            i += 2;
+            char::from(value)
+        }
+        Some('u') if chars.get(i) == Some(&'{') => {
+            let Some(len) = chars[i ..].iter().position(|c| *c == '}') else {
+                return i;
+            };
+            let hex = chars[i + 1 .. i + len].iter().collect::<String>();
+            i += len + 1;
+            match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                Some(c) => c,
+                None => return i,
+            }
+        }
+        Some(c) if c.is_ascii_digit() => {
+            let len = chars[start ..]
+                .iter()
+                .take(3)
+                .take_while(|c| c.is_ascii_digit())
+                .count();
+            let digits = chars[start .. start + len].iter().collect::<String>();
+            i = start + len;
+            match digits.parse::<u8>() {
+                Ok(value) => char::from(value),
+                Err(_) => return i,
+            }
+        }
+        // This includes `\\`, quotes, and escaped newlines
+        Some(other) => *other,
+        None => return start,
+    };
+
+    buf.push(escaped);
+    i
+}
+
+enum LongBracket {
+    /// The index after the closing bracket
+    Closed(usize),
+    Unterminated,
+}
+
+/// Finds the end of a long bracket (`[[ ]]`, `[==[ ]==]`) starting at `start`
+///
+/// Returns `None` if there's no long bracket at `start`.
+fn long_bracket_end(chars: &[char], start: usize) -> Option<LongBracket> {
+    if chars.get(start) != Some(&'[') {
+        return None;
+    }
+
+    let level = chars[start + 1 ..]
+        .iter()
+        .take_while(|c| **c == '=')
+        .count();
+    if chars.get(start + level + 1) != Some(&'[') {
+        return None;
+    }
+
+    let mut i = start + level + 2;
+    while i < chars.len() {
+        if chars[i] == ']'
+            && chars[i + 1 ..].iter().take(level).all(|c| *c == '=')
+            && chars.get(i + level + 1) == Some(&']')
+        {
+            return Some(LongBracket::Closed(i + level + 2));
+        }
+        i += 1;
+    }
+
+    Some(LongBracket::Unterminated)
+}
+
+#[derive(Debug, Clone)]
+enum LuaValue {
+    Nil,
+    Bool(bool),
+    /// The value and whether it was written as a float
+    Number(f64, bool),
+    String(String),
+    Table(LuaTable),
+    /// Anything we can't evaluate without running lua
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default)]
+struct LuaTable {
+    fields: Vec<(String, LuaValue)>,
+    array: Vec<LuaValue>,
+}
+
+impl LuaTable {
+    fn get(&self, key: &str) -> Option<&LuaValue> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    fn set(&mut self, key: String, value: LuaValue) {
+        if let Some((_, existing)) = self.fields.iter_mut().find(|(k, _)| *k == key) {
+            *existing = value;
+        } else {
+            self.fields.push((key, value));
+        }
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut LuaValue> {
+        self.fields
+            .iter_mut()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+}
+
+struct LuaParser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// How many tables deep we currently are
+    depth: usize,
+    /// Kept in the order they're declared so handlers found without a return value keep the file's order
+    locals: LuaTable,
+    /// The first error found, parsing keeps going until the tokens run out so we don't need to bail everywhere
+    error: Option<LoennError>,
+}
+
+impl LuaParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_sym(&self, sym: &str) -> bool {
+        matches!(self.peek(), Some(Token::Sym(s)) if *s == sym)
+    }
+
+    fn is_ident(&self, ident: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(i)) if i == ident)
+    }
+
+    fn fail(&mut self, error: LoennError) {
+        self.error.get_or_insert(error);
+        // Stop parsing, everything after the error is meaningless
+        self.pos = self.tokens.len();
+    }
+
+    /// Parses the top level statements, returning the value of the first `return`
+    fn parse_chunk(&mut self) -> Option<LuaValue> {
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Ident(ident) => match ident.as_str() {
+                    "local" => {
+                        self.next();
+                        if self.is_ident("function") {
+                            self.skip_function();
+                            continue;
+                        }
+
+                        let mut names = Vec::new();
+                        while let Some(Token::Ident(name)) = self.peek().cloned() {
+                            self.next();
+                            names.push(name);
+                            if !self.is_sym(",") {
+                                break;
+                            }
+                            self.next();
+                        }
+
+                        if self.is_sym("=") {
+                            self.next();
+                            for name in names {
+                                let value = self.parse_expr();
+                                self.locals.set(name, value);
+                                if !self.is_sym(",") {
+                                    break;
+                                }
+                                self.next();
+                            }
+                        } else {
+                            for name in names {
+                                self.locals.set(name, LuaValue::Nil);
+                            }
+                        }
+                    }
+                    "return" => {
+                        self.next();
+                        return Some(self.parse_expr());
+                    }
+                    "function" => self.skip_function(),
+                    "if" | "do" | "while" | "for" | "repeat" => self.skip_block(),
+                    _ => self.parse_assignment_or_call(),
+                },
+                _ => {
+                    self.next();
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Parses `a.b.c = value` or skips a function call statement
+    fn parse_assignment_or_call(&mut self) {
+        let Some(path) = self.parse_path() else {
+            return;
+        };
+
+        if self.is_sym("=") {
+            self.next();
+            let value = self.parse_expr();
+            self.assign(&path, value);
+        } else {
+            self.skip_call_suffix();
+        }
+    }
+
+    /// Parses a name followed by any field accesses
+    fn parse_path(&mut self) -> Option<Vec<String>> {
+        let Some(Token::Ident(root)) = self.next() else {
+            return None;
+        };
+
+        let mut path = vec![root];
+
+        loop {
+            if self.is_sym(".")
+                && let Some(Token::Ident(field)) = self.peek_at(1).cloned()
+            {
+                self.pos += 2;
+                path.push(field);
+            } else if self.is_sym("[")
+                && let Some(Token::Str(field)) = self.peek_at(1).cloned()
+                && matches!(self.peek_at(2), Some(Token::Sym("]")))
+            {
+                self.pos += 3;
+                path.push(field);
+            } else {
+                break;
+            }
+        }
+
+        Some(path)
+    }
+
+    fn assign(&mut self, path: &[String], value: LuaValue) {
+        let [root, fields @ ..] = path else {
+            return;
+        };
+
+        let Some((last, fields)) = fields.split_last() else {
+            self.locals.set(root.clone(), value);
+            return;
+        };
+
+        let mut current = self.locals.get_mut(root);
+        for field in fields {
+            current = match current {
+                Some(LuaValue::Table(t)) => t.get_mut(field),
+                _ => None,
+            };
+        }
+
+        if let Some(LuaValue::Table(table)) = current {
+            table.set(last.clone(), value);
+        }
+    }
+
+    fn lookup(&self, path: &[String]) -> LuaValue {
+        let [root, fields @ ..] = path else {
+            return LuaValue::Unknown;
+        };
+
+        let mut current = self.locals.get(root);
+        for field in fields {
+            current = match current {
+                Some(LuaValue::Table(t)) => t.get(field),
+                _ => None,
+            };
+        }
+
+        current.cloned().unwrap_or(LuaValue::Unknown)
+    }
+
+    fn parse_expr(&mut self) -> LuaValue {
+        let value = self.parse_primary();
+
+        let mut is_binary = false;
+        while matches!(
+            self.peek(),
+            Some(Token::Sym(
+                ".." | "+"
+                    | "-"
+                    | "*"
+                    | "/"
+                    | "//"
+                    | "%"
+                    | "^"
+                    | "=="
+                    | "~="
+                    | "<"
+                    | ">"
+                    | "<="
+                    | ">="
+            ))
+        ) || self.is_ident("and")
+            || self.is_ident("or")
+        {
+            self.next();
+            self.parse_primary();
+            is_binary = true;
+        }
+
+        if is_binary { LuaValue::Unknown } else { value }
+    }
+
+    fn parse_primary(&mut self) -> LuaValue {
+        match self.peek().cloned() {
+            Some(Token::Str(s)) => {
+                self.next();
+                LuaValue::String(s)
+            }
+            Some(Token::Num(n, float)) => {
+                self.next();
+                LuaValue::Number(n, float)
+            }
+            Some(Token::Sym("-")) => {
+                self.next();
+                match self.parse_primary() {
+                    LuaValue::Number(n, float) => LuaValue::Number(-n, float),
+                    _ => LuaValue::Unknown,
+                }
+            }
+            Some(Token::Sym("#")) => {
+                self.next();
+                self.parse_primary();
+                LuaValue::Unknown
+            }
+            Some(Token::Sym("{")) => LuaValue::Table(self.parse_table()),
+            Some(Token::Sym("(")) => {
+                self.skip_balanced();
+                self.skip_call_suffix();
+                LuaValue::Unknown
+            }
+            Some(Token::Ident(ident)) => match ident.as_str() {
+                "true" => {
+                    self.next();
+                    LuaValue::Bool(true)
+                }
+                "false" => {
+                    self.next();
+                    LuaValue::Bool(false)
+                }
+                "nil" => {
+                    self.next();
+                    LuaValue::Nil
+                }
+                "not" => {
+                    self.next();
+                    self.parse_primary();
+                    LuaValue::Unknown
+                }
+                "function" => {
+                    self.skip_function();
+                    LuaValue::Unknown
+                }
+                _ => {
+                    let Some(path) = self.parse_path() else {
+                        return LuaValue::Unknown;
+                    };
+
+                    if self.skip_call_suffix() {
+                        LuaValue::Unknown
+                    } else {
+                        self.lookup(&path)
+                    }
+                }
+            },
+            _ => {
+                self.next();
+                LuaValue::Unknown
+            }
+        }
+    }
+
+    fn parse_table(&mut self) -> LuaTable {
+        let mut table = LuaTable::default();
+        // Skip the opening brace
+        self.next();
+
+        if self.depth >= MAX_DEPTH {
+            self.fail(LoennError::TooDeep);
+            return table;
+        }
+        self.depth += 1;
+
+        loop {
+            let Some(token) = self.peek().cloned() else {
+                self.fail(LoennError::UnexpectedEof);
+                break;
+            };
+
+            match token {
+                Token::Sym("}") => {
+                    self.next();
+                    break;
+                }
+                Token::Sym("," | ";") => {
+                    self.next();
+                }
+                Token::Sym("[") => {
+                    self.next();
+                    let key = self.parse_expr();
+                    if self.is_sym("]") {
+                        self.next();
+                    }
+                    if self.is_sym("=") {
+                        self.next();
+                    }
+                    let value = self.parse_expr();
+
+                    match key {
+                        LuaValue::String(key) => table.set(key, value),
+                        LuaValue::Number(n, _) => table.set(n.to_string(), value),
+                        _ => {}
+                    }
+                }
+                Token::Ident(key) if matches!(self.peek_at(1), Some(Token::Sym("="))) => {
+                    self.pos += 2;
+                    let value = self.parse_expr();
+                    table.set(key, value);
+                }
+                _ => {
+                    let start = self.pos;
+                    let value = self.parse_expr();
+                    table.array.push(value);
+
+                    // Make sure we always make progress on unexpected tokens
+                    if self.pos == start {
+                        self.next();
+                    }
+                }
+            }
+        }
+
+        self.depth -= 1;
+        table
+    }
+
+    /// Skips any calls, indexing and method calls after an expression
+    ///
+    /// Returns whether anything was skipped.
+    fn skip_call_suffix(&mut self) -> bool {
+        let mut skipped = false;
+
+        loop {
+            match self.peek() {
+                Some(Token::Sym("(" | "[")) => self.skip_balanced(),
+                Some(Token::Sym("{")) => {
+                    self.parse_table();
+                }
+                Some(Token::Str(_)) => {
+                    self.next();
+                }
+                Some(Token::Sym("." | ":")) => {
+                    self.pos += 2;
+                }
+                _ => break,
+            }
+            skipped = true;
+        }
+
+        skipped
+    }
+
+    /// Skips a bracketed group, including nested brackets
+    fn skip_balanced(&mut self) {
+        let mut depth = 0;
+
+        loop {
+            let Some(token) = self.next() else {
+                self.fail(LoennError::UnexpectedEof);
+                break;
+            };
+
+            match token {
+                Token::Sym("(" | "[" | "{") => depth += 1,
+                Token::Sym(")" | "]" | "}") => {
+                    depth -= 1;
+                    if depth <= 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips a function definition, starting at the `function` keyword
+    fn skip_function(&mut self) {
+        self.skip_block();
+    }
+
+    /// Skips from a block opening keyword to its matching `end`
+    fn skip_block(&mut self) {
+        let mut depth = 0;
+
+        loop {
+            let Some(token) = self.next() else {
+                self.fail(LoennError::UnexpectedEof);
+                break;
+            };
+
+            if let Token::Ident(ident) = token {
+                match ident.as_str() {
+                    // `while` and `for` blocks are opened by their `do`
+                    "function" | "if" | "do" | "repeat" => depth += 1,
+                    "end" | "until" => {
+                        depth -= 1;
+                        if depth <= 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -287,7 +287,57 @@ pub struct MountainPosition {
     pub target: [Float; 3],
 }
 
-pub struct AltSidesMeta {}
+/// The contents of an AltSidesHelper `.altsideshelper.meta.yaml` file
+#[derive(FromYaml)]
+pub struct AltSidesMeta {
+    #[name = "Sides"]
+    pub sides: Vec<AltSidesMode>,
+}
+
+/// A single alt-side entry in an [AltSidesMeta]
+#[derive(FromYaml)]
+pub struct AltSidesMode {
+    /// The SID of the map used as this side
+    #[name = "Map"]
+    pub map: String,
+    /// The dialog key of the side's name on the chapter panel
+    #[name = "Label"]
+    pub label: Option<String>,
+    /// Whether this side replaces the B or C side it's in the place of instead of being added after them
+    #[name = "OverrideVanillaSideData"]
+    pub override_vanilla_side_data: Option<bool>,
+    /// How the side is unlocked, e.g. `consecutive`, `triggered`, `with_previous`, `c_side` or `always`
+    #[name = "UnlockMode"]
+    pub unlock_mode: Option<String>,
+
+    #[name = "ChapterPanelHeartIcon"]
+    pub chapter_panel_heart_icon: Option<String>,
+    #[name = "ChapterPanelTabIcon"]
+    pub chapter_panel_tab_icon: Option<String>,
+    #[name = "ChapterPanelSideIcon"]
+    pub chapter_panel_side_icon: Option<String>,
+    #[name = "ShowBerriesOnChapterPanel"]
+    pub show_berries_on_chapter_panel: Option<bool>,
+
+    #[name = "InWorldHeartIcon"]
+    pub in_world_heart_icon: Option<String>,
+    #[name = "JournalHeartIcon"]
+    pub journal_heart_icon: Option<String>,
+    #[name = "HeartColour"]
+    pub heart_colour: Option<String>,
+    #[name = "ShowHeartPoem"]
+    pub show_heart_poem: Option<bool>,
+    #[name = "PoemDisplayColor"]
+    pub poem_display_color: Option<String>,
+
+    #[name = "EndScreenTitle"]
+    pub end_screen_title: Option<String>,
+    #[name = "EndScreenClearTitle"]
+    pub end_screen_clear_title: Option<String>,
+
+    #[name = "CanFullClear"]
+    pub can_full_clear: Option<bool>,
+}
 
 #[derive(FromYaml)]
 pub enum IntroTypes {
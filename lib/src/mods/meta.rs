@@ -214,7 +214,7 @@ impl FromYaml for ModMeta {
         })
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         let mut hash = Mapping::new();
         ModMeta::name_version_to_yaml(&self.name, &Some(self.version), &mut hash);
 
@@ -11,12 +11,20 @@ use std::{
 use zip::{HasZipMetadata, ZipArchive, read::ZipFile, result::ZipError};
 
 use crate::{
-    maps::{MapManager, parser::MapElementParsingError, reader::MapReadError},
+    dialog::{Dialog, Localization},
+    maps::{
+        MapManager,
+        parser::MapElementParsingError,
+        reader::MapReadError,
+        schema::EntitySchema,
+    },
     mods::{
-        maps::{BundledMap, MapMeta},
+        loenn::LoennError,
+        maps::{AltSidesMeta, BundledMap, MapMeta},
         meta::ModMeta,
     },
     playbacks::{Playback, PlaybackReadError},
+    sprites::{SpriteBank, SpriteReadError},
     utils::{FromYaml, YamlReadError},
 };
 
@@ -29,8 +37,10 @@ use crate::mods::dll::BufferedDLL;
 
 #[cfg(not(target_family = "wasm"))]
 pub mod dll;
+pub mod loenn;
 pub mod maps;
 pub mod meta;
+pub mod usage;
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct FileProviderError<T: Error>(pub T);
@@ -68,6 +78,11 @@ pub trait FileProvider {
         &mut self,
         path: impl AsRef<Path>,
     ) -> Result<Vec<u8>, FileProviderError<Self::Err>>;
+
+    /// The path that every path from [get_paths](Self::get_paths) starts with, empty if they're relative to the mod root
+    fn root(&self) -> &Path {
+        Path::new("")
+    }
 }
 
 pub struct ZipBuf<R: Read + Seek> {
@@ -187,6 +202,10 @@ impl FileProvider for DirBuf {
         file.read_to_end(&mut buf)?;
         Ok(buf)
     }
+
+    fn root(&self) -> &Path {
+        &self.root
+    }
 }
 
 pub struct ModManager<T: FileProvider> {
@@ -234,11 +253,158 @@ impl<T: FileProvider> ModManager<T> {
     pub fn collection_mut(&mut self) -> &mut ModCollection {
         &mut self.mods
     }
+
+    /// Reads all the dialog files in the mod's `Dialog` directory
+    ///
+    /// Languages are keyed by the file name, so `Dialog/English.txt` is stored under `English`.<br>
+    /// To apply this over the vanilla dialog use [Localization::merge].
+    pub fn dialog(&mut self) -> Result<Localization, ModReadError<T::Err>> {
+        let paths = self
+            .mod_paths()
+            .filter(|(_, p)| {
+                p.extension().is_some_and(|e| e == "txt") && p.parent() == Some(Path::new("Dialog"))
+            })
+            .map(|(p, _)| p.to_owned())
+            .collect::<Box<[_]>>();
+
+        let mut localization = Localization::new();
+
+        for path in paths {
+            let Some(language) = path.file_stem().and_then(OsStr::to_str) else {
+                continue;
+            };
+
+            let dialog = Dialog::from_reader(self.provider.get_file(&path)?)?;
+            localization.add_dialog(language, dialog);
+        }
+
+        Ok(localization)
+    }
+
+    /// Resolves the sprite bank used in a map, or globally if `sid` is `None`
+    ///
+    /// `base` should usually be the vanilla `Sprites.xml`. The mod's `Graphics/Sprites.xml` is
+    /// applied over it, followed by the `Sprites` file from the map's meta.yaml.
+    pub fn sprites(
+        &mut self,
+        base: &SpriteBank,
+        sid: Option<&str>,
+    ) -> Result<SpriteBank, ModReadError<T::Err>> {
+        let map_path = sid
+            .and_then(|sid| self.mods.maps.get(sid))
+            .and_then(|m| m.meta.as_ref())
+            .and_then(|m| m.sprites.clone());
+
+        self.resolve_sprite_bank(base, "Graphics/Sprites.xml", map_path)
+    }
+
+    /// Resolves the portrait bank used in a map, or globally if `sid` is `None`
+    ///
+    /// Works the same as [sprites](Self::sprites) but for `Portraits.xml`.
+    pub fn portraits(
+        &mut self,
+        base: &SpriteBank,
+        sid: Option<&str>,
+    ) -> Result<SpriteBank, ModReadError<T::Err>> {
+        let map_path = sid
+            .and_then(|sid| self.mods.maps.get(sid))
+            .and_then(|m| m.meta.as_ref())
+            .and_then(|m| m.portraits.clone());
+
+        self.resolve_sprite_bank(base, "Graphics/Portraits.xml", map_path)
+    }
+
+    fn resolve_sprite_bank(
+        &mut self,
+        base: &SpriteBank,
+        global_path: &str,
+        map_path: Option<String>,
+    ) -> Result<SpriteBank, ModReadError<T::Err>> {
+        let mut bank = base.clone();
+
+        if let Some(global) = self.read_sprite_bank(Path::new(global_path))? {
+            bank.merge(&global);
+        }
+
+        if let Some(map_path) = map_path {
+            // Map metas can leave out the extension
+            let mut path = PathBuf::from(map_path);
+            if path.extension().is_none_or(|e| e != "xml") {
+                path.as_mut_os_string().push(".xml");
+            }
+
+            if let Some(map_bank) = self.read_sprite_bank(&path)? {
+                bank.merge(&map_bank);
+            }
+        }
+
+        Ok(bank)
+    }
+
+    /// Reads the schemas of every entity and trigger defined by the mod's Lönn plugins
+    ///
+    /// See [loenn] for the limitations of this.
+    pub fn loenn_schemas(&mut self) -> Result<Vec<EntitySchema>, ModReadError<T::Err>> {
+        let paths = self
+            .provider
+            .get_paths()
+            .filter_map(|p| {
+                let kind = p.parent()?;
+                let is_trigger = match kind.file_name()?.to_str()? {
+                    "entities" => false,
+                    "triggers" => true,
+                    _ => return None,
+                };
+
+                (p.extension()? == "lua" && kind.parent()?.file_name()? == "Loenn")
+                    .then(|| (p.to_owned(), is_trigger))
+            })
+            .collect::<Box<[_]>>();
+
+        let mut schemas = Vec::new();
+
+        for (path, is_trigger) in paths {
+            let mut src = String::new();
+            self.provider.get_file(&path)?.read_to_string(&mut src)?;
+
+            schemas.extend(loenn::schemas_from_lua(&src, is_trigger)?);
+        }
+
+        Ok(schemas)
+    }
+
+    fn read_sprite_bank(
+        &mut self,
+        path: &Path,
+    ) -> Result<Option<SpriteBank>, ModReadError<T::Err>> {
+        let Some(path) = self
+            .mod_paths()
+            .find(|(_, p)| *p == path)
+            .map(|(p, _)| p.to_owned())
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(SpriteBank::from_reader(
+            self.provider.get_file(path)?,
+        )?))
+    }
+
+    /// Iterates over every path in the mod along with the path relative to the mod root
+    fn mod_paths(&self) -> impl Iterator<Item = (&Path, &Path)> {
+        let root = self.provider.root();
+
+        self.provider
+            .get_paths()
+            .filter_map(move |p| Some((p, p.strip_prefix(root).ok()?)))
+    }
 }
 
 pub struct ModCollection {
     pub mod_defs: Vec<Mod>,
     pub tutorials: HashMap<String, Playback>,
+    /// Any files in `Tutorials` that looked like playbacks but couldn't be read
+    pub tutorial_errors: HashMap<String, PlaybackReadError>,
     pub maps: HashMap<String, BundledMap>,
 }
 
@@ -267,7 +433,9 @@ impl ModCollection {
             .collect();
 
         let mut tutorials = HashMap::new();
+        let mut tutorial_errors = HashMap::new();
         let mut map_metas = HashMap::new();
+        let mut altsides_metas = HashMap::new();
         let mut map_bins = HashMap::new();
 
         let paths = provider
@@ -336,11 +504,17 @@ impl ModCollection {
                 // Needed because people *love* fucking putting random shit
                 // in the Tutorials Folder
                 // Looking at you P2P Conlab
-                if let Err(PlaybackReadError::InvalidHeader) = &tutorial {
-                    continue;
-                }
                 // Safe since we check we can convert to String
-                tutorials.insert(path.to_str().unwrap().to_owned(), tutorial?);
+                let path = path.to_str().unwrap().to_owned();
+                match tutorial {
+                    Ok(tutorial) => {
+                        tutorials.insert(path, tutorial);
+                    }
+                    Err(PlaybackReadError::InvalidHeader) => continue,
+                    Err(e) => {
+                        tutorial_errors.insert(path, e);
+                    }
+                }
             } else if top_level_dir == "Maps" {
                 let mut file = provider.get_file(&path)?;
                 // Doesn't panic since we check starts_with
@@ -362,7 +536,8 @@ impl ModCollection {
                         map_bins.insert(sid.to_owned(), map);
                     }
                     ".altsideshelper.meta.yaml" => {
-                        // TODO: altsides helper stuff
+                        altsides_metas
+                            .insert(sid.to_owned(), AltSidesMeta::parse_from_reader(&mut file)?);
                     }
                     ".meta.yaml" => {
                         map_metas.insert(sid.to_owned(), MapMeta::parse_from_reader(&mut file)?);
@@ -375,9 +550,10 @@ impl ModCollection {
         let mut maps = HashMap::new();
         for (sid, map_bin) in map_bins {
             let map_meta = map_metas.remove(&sid);
+            let altsides_meta = altsides_metas.remove(&sid);
             maps.insert(sid.to_owned(), BundledMap {
                 meta: map_meta,
-                altsides_meta: None,
+                altsides_meta,
                 map: map_bin,
             });
         }
@@ -385,6 +561,7 @@ impl ModCollection {
         Ok(Self {
             mod_defs: mods,
             tutorials,
+            tutorial_errors,
             maps,
         })
     }
@@ -437,6 +614,8 @@ pub enum ModReadError<T: Error> {
     IoError(std::io::Error),
     PlaybackError(PlaybackReadError),
     YamlReadError(YamlReadError),
+    SpriteError(SpriteReadError),
+    LoennError(LoennError),
 }
 
 impl<T: Error> Error for ModReadError<T> {}
@@ -452,6 +631,8 @@ impl<T: Error> Display for ModReadError<T> {
             ModReadError::IoError(e) => Display::fmt(e, f),
             ModReadError::PlaybackError(e) => Display::fmt(e, f),
             ModReadError::YamlReadError(e) => Display::fmt(e, f),
+            ModReadError::SpriteError(e) => Display::fmt(e, f),
+            ModReadError::LoennError(e) => Display::fmt(e, f),
         }
     }
 }
@@ -498,3 +679,15 @@ impl<T: Error> From<YamlReadError> for ModReadError<T> {
         ModReadError::<T>::YamlReadError(value)
     }
 }
+
+impl<T: Error> From<SpriteReadError> for ModReadError<T> {
+    fn from(value: SpriteReadError) -> Self {
+        ModReadError::<T>::SpriteError(value)
+    }
+}
+
+impl<T: Error> From<LoennError> for ModReadError<T> {
+    fn from(value: LoennError) -> Self {
+        ModReadError::<T>::LoennError(value)
+    }
+}
@@ -0,0 +1,229 @@
+//! Reports on which entities and triggers are used in a [ModCollection]'s maps and where they come from
+use std::collections::BTreeMap;
+#[cfg(not(target_family = "wasm"))]
+use std::collections::HashSet;
+
+use crate::{
+    maps::{ErasedMapElement, MapManager, RawMapElement},
+    mods::{Mod, ModCollection},
+};
+
+/// Whether a map element is an entity or a trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ElementKind {
+    Entity,
+    Trigger,
+}
+
+/// Where an entity or trigger is believed to come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElementSource {
+    /// The element has no prefix, so it's assumed to be from the base game
+    Vanilla,
+    /// A mod's dll registers the element through `[CustomEntity]`
+    Dll(String),
+    /// The element's prefix matches the name of a mod
+    Prefix(String),
+    /// The element has a prefix that doesn't match any known mod
+    Unknown(String),
+}
+
+/// A usage of an element in a map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementLocation {
+    pub sid: String,
+    pub room: String,
+}
+
+/// Everything known about how an entity or trigger is used across all maps
+#[derive(Debug, Clone)]
+pub struct ElementUsage {
+    pub name: String,
+    pub kind: ElementKind,
+    /// The total amount of times the element appears
+    pub count: usize,
+    /// Every room the element appears in, rooms with multiple copies are only listed once
+    pub locations: Vec<ElementLocation>,
+    /// Whether a parser is registered for the element
+    pub parsed: bool,
+    /// How many times the element was left as a [RawMapElement] even though it has a parser,
+    /// such as when it failed to parse in [lenient](MapManager::set_lenient) mode or the map
+    /// was parsed before the parser was registered
+    pub fallbacks: usize,
+    pub source: ElementSource,
+}
+
+/// A report of every entity and trigger in a [ModCollection]
+///
+/// Created with [ModCollection::element_usage].
+#[derive(Debug, Clone, Default)]
+pub struct ElementReport {
+    pub elements: BTreeMap<(ElementKind, String), ElementUsage>,
+}
+
+impl ElementReport {
+    fn add(
+        &mut self,
+        kind: ElementKind,
+        element: &dyn ErasedMapElement,
+        manager: &MapManager,
+        sid: &str,
+        room: &str,
+    ) {
+        let name = element.name();
+        let usage = self
+            .elements
+            .entry((kind, name.to_owned()))
+            .or_insert_with(|| ElementUsage {
+                name: name.to_owned(),
+                kind,
+                count: 0,
+                locations: Vec::new(),
+                parsed: manager.has_parser(name),
+                fallbacks: 0,
+                source: ElementSource::Vanilla,
+            });
+
+        usage.count += 1;
+        if usage.parsed && (element as &dyn std::any::Any).is::<RawMapElement>() {
+            usage.fallbacks += 1;
+        }
+
+        if !usage
+            .locations
+            .iter()
+            .any(|l| l.sid == sid && l.room == room)
+        {
+            usage.locations.push(ElementLocation {
+                sid: sid.to_owned(),
+                room: room.to_owned(),
+            });
+        }
+    }
+
+    /// Attributes elements to the given mods, using dll metadata first and then name prefixes
+    ///
+    /// This can be called multiple times with different mods, such as helpers the maps depend on.
+    /// Elements that were already attributed to a mod are left alone.
+    pub fn attribute(&mut self, mods: &[Mod]) {
+        // Reading the names out of a dll walks all of its types, so only do it once per mod
+        #[cfg(not(target_family = "wasm"))]
+        let dll_names = mods
+            .iter()
+            .filter_map(|m| {
+                let dll = m.dll.as_ref()?;
+                Some((
+                    m,
+                    dll.entity_names().into_iter().collect::<HashSet<_>>(),
+                    dll.trigger_names().into_iter().collect::<HashSet<_>>(),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        for usage in self.elements.values_mut() {
+            if matches!(
+                usage.source,
+                ElementSource::Dll(_) | ElementSource::Prefix(_)
+            ) {
+                continue;
+            }
+
+            #[cfg(not(target_family = "wasm"))]
+            if let Some((m, ..)) =
+                dll_names
+                    .iter()
+                    .find(|(_, entities, triggers)| match usage.kind {
+                        ElementKind::Entity => entities.contains(&usage.name),
+                        ElementKind::Trigger => triggers.contains(&usage.name),
+                    })
+            {
+                usage.source = ElementSource::Dll(m.meta.name.clone());
+                continue;
+            }
+
+            let Some((prefix, _)) = usage.name.split_once('/') else {
+                continue;
+            };
+
+            usage.source = match mods
+                .iter()
+                .find(|m| m.meta.name.eq_ignore_ascii_case(prefix))
+            {
+                Some(m) => ElementSource::Prefix(m.meta.name.clone()),
+                None => ElementSource::Unknown(prefix.to_owned()),
+            };
+        }
+    }
+
+    /// Gets every element without a parser, most used first
+    pub fn unparsed(&self) -> Vec<&ElementUsage> {
+        let mut unparsed = self
+            .elements
+            .values()
+            .filter(|u| !u.parsed)
+            .collect::<Vec<_>>();
+        unparsed.sort_by_key(|u| std::cmp::Reverse(u.count));
+
+        unparsed
+    }
+
+    /// Gets every element that has a parser but was still left as a [RawMapElement] somewhere
+    pub fn fallbacks(&self) -> Vec<&ElementUsage> {
+        self.elements.values().filter(|u| u.fallbacks > 0).collect()
+    }
+
+    /// Gets every element that couldn't be attributed to vanilla or a known mod
+    pub fn unknown(&self) -> Vec<&ElementUsage> {
+        self.elements
+            .values()
+            .filter(|u| matches!(u.source, ElementSource::Unknown(_)))
+            .collect()
+    }
+
+    /// Gets every element that was attributed to a mod
+    pub fn from_mod(&self, name: &str) -> Vec<&ElementUsage> {
+        self.elements
+            .values()
+            .filter(|u| match &u.source {
+                ElementSource::Dll(m) | ElementSource::Prefix(m) => m == name,
+                _ => false,
+            })
+            .collect()
+    }
+}
+
+impl ModCollection {
+    /// Lists every entity and trigger used in all the maps, attributed to the mods in this collection
+    ///
+    /// Elements are checked against the [default parsers](MapManager::default_parsers) the maps are parsed with.
+    /// To attribute elements to helpers use [ElementReport::attribute] with the helper mods.
+    pub fn element_usage(&self) -> ElementReport {
+        let mut manager = MapManager::empty();
+        manager.default_parsers();
+
+        self.element_usage_with(&manager)
+    }
+
+    /// Lists every entity and trigger used in all the maps, checking them against the parsers registered on `manager`
+    pub fn element_usage_with(&self, manager: &MapManager) -> ElementReport {
+        let mut report = ElementReport::default();
+
+        for (sid, map) in &self.maps {
+            for level in &map.map.levels.levels {
+                let room = level.name.as_str().unwrap_or_default();
+
+                for entity in level.entities.iter().flat_map(|e| &e.entities) {
+                    report.add(ElementKind::Entity, entity.as_ref(), manager, sid, room);
+                }
+
+                for trigger in level.triggers.iter().flat_map(|t| &t.triggers) {
+                    report.add(ElementKind::Trigger, trigger.as_ref(), manager, sid, room);
+                }
+            }
+        }
+
+        report.attribute(&self.mod_defs);
+
+        report
+    }
+}
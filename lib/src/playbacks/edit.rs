@@ -0,0 +1,214 @@
+//! Tools for editing and inspecting [Playback]s
+//!
+//! Timestamps are always written relative to the first frame, so the playback starts right away.
+//! Positions are written as they are, they're offsets from where the playback is placed in game,
+//! so [translate](Playback::translate) and [set_origin](Playback::set_origin) move the playback relative to that.
+use std::{collections::BTreeMap, error::Error, fmt::Display};
+
+use crate::playbacks::{FRAME_TIME, Playback};
+
+/// The dash animation, used to tell apart dashes in the same direction
+const DASH_ANIMATION: &str = "dash";
+
+/// Errors from editing a [Playback]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaybackEditError {
+    /// The speed passed to [scale_speed](Playback::scale_speed) isn't a finite positive number
+    InvalidSpeed(f32),
+}
+
+impl Display for PlaybackEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlaybackEditError::InvalidSpeed(speed) => write!(
+                f,
+                "Playback speed has to be a finite positive number, found {speed}"
+            ),
+        }
+    }
+}
+
+impl Error for PlaybackEditError {}
+
+/// A summary of a [Playback], created with [Playback::stats]
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackStats {
+    pub frames: usize,
+    /// The time between the first and last frame in seconds
+    pub duration: f32,
+    /// The total distance travelled in pixels
+    pub distance: f32,
+    /// The amount of dashes, inferred from the dash direction and animation
+    ///
+    /// Playbacks recorded before dash directions were saved will always have 0 dashes.
+    pub dashes: usize,
+    /// The top left corner of the area the playback covers
+    pub min: [f32; 2],
+    /// The bottom right corner of the area the playback covers
+    pub max: [f32; 2],
+    /// Every animation used along with the amount of frames it is used for
+    pub animations: BTreeMap<String, usize>,
+}
+
+impl Playback {
+    /// The time between the first and last frame in seconds
+    pub fn duration(&self) -> f32 {
+        match (self.frames.first(), self.frames.last()) {
+            (Some(first), Some(last)) => last.timestamp - first.timestamp,
+            _ => 0.0,
+        }
+    }
+
+    /// Removes every frame outside of the time range
+    ///
+    /// `start` and `end` are in seconds relative to the first frame.<br>
+    /// Positions are left alone, use [set_origin](Self::set_origin) with `[0.0, 0.0]`
+    /// to make the trimmed playback start where it's placed.
+    pub fn trim(&mut self, start: f32, end: f32) {
+        let Some(base) = self.frames.first().map(|f| f.timestamp) else {
+            return;
+        };
+
+        self.frames.retain(|f| {
+            let time = f.timestamp - base;
+            time >= start && time <= end
+        });
+    }
+
+    /// Makes the playback play at a different speed
+    ///
+    /// A speed of `2.0` makes it play twice as fast.
+    pub fn scale_speed(&mut self, speed: f32) -> Result<(), PlaybackEditError> {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(PlaybackEditError::InvalidSpeed(speed));
+        }
+
+        let Some(base) = self.frames.first().map(|f| f.timestamp) else {
+            return Ok(());
+        };
+
+        for frame in &mut self.frames {
+            frame.timestamp = base + (frame.timestamp - base) / speed;
+        }
+
+        Ok(())
+    }
+
+    /// Spaces the frames evenly, `frame_time` seconds apart
+    ///
+    /// Use [FRAME_TIME] to get rid of any lag frames in a recording.
+    pub fn retime(&mut self, frame_time: f32) {
+        let Some(base) = self.frames.first().map(|f| f.timestamp) else {
+            return;
+        };
+
+        for (i, frame) in self.frames.iter_mut().enumerate() {
+            frame.timestamp = base + i as f32 * frame_time;
+        }
+    }
+
+    /// Mirrors the playback horizontally around `axis`
+    ///
+    /// This flips the position, facing, scale and dash direction of every frame.
+    pub fn mirror(&mut self, axis: f32) {
+        for frame in &mut self.frames {
+            frame.position[0] = 2.0 * axis - frame.position[0];
+            frame.facing = frame.facing.flipped();
+            frame.scale[0] = -frame.scale[0];
+            frame.dash_direction[0] = -frame.dash_direction[0];
+        }
+    }
+
+    /// Moves every frame by `offset`, relative to where the playback is placed
+    pub fn translate(&mut self, offset: [f32; 2]) {
+        for frame in &mut self.frames {
+            frame.position[0] += offset[0];
+            frame.position[1] += offset[1];
+        }
+    }
+
+    /// Moves the playback so the first frame is `origin` away from where the playback is placed
+    pub fn set_origin(&mut self, origin: [f32; 2]) {
+        if let Some(first) = self.frames.first().map(|f| f.position) {
+            self.translate([origin[0] - first[0], origin[1] - first[1]]);
+        }
+    }
+
+    /// Adds the frames of `other` to the end of this playback
+    ///
+    /// The timestamps of `other` are shifted to start one frame after the last frame.<br>
+    /// Positions are kept as they are, use [translate](Self::translate) on `other` to line them up.
+    pub fn append(&mut self, other: &Playback) {
+        let (Some(last), Some(other_first)) = (self.frames.last(), other.frames.first()) else {
+            self.frames.extend(other.frames.iter().cloned());
+            return;
+        };
+
+        let offset = last.timestamp + FRAME_TIME - other_first.timestamp;
+
+        self.frames.extend(other.frames.iter().map(|f| {
+            let mut frame = f.clone();
+            frame.timestamp += offset;
+            frame
+        }));
+    }
+
+    /// Gets the indices of the frames where a dash starts
+    ///
+    /// Dashes are inferred from the dash direction changing to a new non-zero direction.
+    /// The direction stays the same between dashes in the same direction, so those are found
+    /// from the dash animation starting again.
+    pub fn dash_frames(&self) -> Vec<usize> {
+        let mut last_dash = [0.0, 0.0];
+        let mut last_animation = "";
+        let mut dashes = Vec::new();
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            let restarted = frame.animation == DASH_ANIMATION && last_animation != DASH_ANIMATION;
+
+            if frame.dash_direction != [0.0, 0.0]
+                && (frame.dash_direction != last_dash || restarted)
+            {
+                dashes.push(i);
+            }
+
+            last_dash = frame.dash_direction;
+            last_animation = &frame.animation;
+        }
+
+        dashes
+    }
+
+    /// Creates a summary of the playback
+    pub fn stats(&self) -> PlaybackStats {
+        let mut stats = PlaybackStats {
+            frames: self.frames.len(),
+            duration: self.duration(),
+            dashes: self.dash_frames().len(),
+            min: [f32::MAX; 2],
+            max: [f32::MIN; 2],
+            ..Default::default()
+        };
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            if i > 0 {
+                let prev = self.frames[i - 1].position;
+                stats.distance += (frame.position[0] - prev[0]).hypot(frame.position[1] - prev[1]);
+            }
+
+            for axis in 0 .. 2 {
+                stats.min[axis] = stats.min[axis].min(frame.position[axis]);
+                stats.max[axis] = stats.max[axis].max(frame.position[axis]);
+            }
+
+            *stats.animations.entry(frame.animation.clone()).or_default() += 1;
+        }
+
+        if self.frames.is_empty() {
+            stats.min = [0.0; 2];
+            stats.max = [0.0; 2];
+        }
+
+        stats
+    }
+}
@@ -5,6 +5,13 @@ use std::{
 
 use crate::utils::binary::{BinReadError, BinReader, BinWriter};
 
+pub mod edit;
+pub mod render;
+pub mod trace;
+
+/// The time between frames in seconds, the game records playbacks at 60fps
+pub const FRAME_TIME: f32 = 1.0 / 60.0;
+
 /// A playback tutorial.
 ///
 /// This is a list of frames that give the offset and actions
@@ -13,11 +20,52 @@ use crate::utils::binary::{BinReadError, BinReader, BinWriter};
 /// This is used for the silhouette players in Farewell.
 ///
 /// In mods, these are stored in the `Tutorials` directory
+#[derive(Debug, Clone)]
 pub struct Playback {
+    /// The format the playback was read from, this is kept when writing
+    pub version: PlaybackVersion,
     pub frames: Vec<PlaybackFrame>,
 }
 
+/// The versions of the playback format
+///
+/// Only [V2](PlaybackVersion::V2) stores the scale and dash direction of frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaybackVersion {
+    /// No `TIMELINE` header, used by the playbacks in the base game
+    Legacy,
+    /// A `TIMELINE` header with version 1
+    V1,
+    /// A `TIMELINE` header with version 2
+    #[default]
+    V2,
+}
+
+impl PlaybackVersion {
+    /// Whether frames in this version store their scale and dash direction
+    pub fn has_scale(&self) -> bool {
+        *self == PlaybackVersion::V2
+    }
+
+    /// The number written after the `TIMELINE` header, `None` for [Legacy](PlaybackVersion::Legacy)
+    pub fn header_version(&self) -> Option<i32> {
+        match self {
+            PlaybackVersion::Legacy => None,
+            PlaybackVersion::V1 => Some(1),
+            PlaybackVersion::V2 => Some(2),
+        }
+    }
+}
+
 impl Playback {
+    /// Creates a playback using the newest version of the format
+    pub fn new(frames: Vec<PlaybackFrame>) -> Self {
+        Playback {
+            version: PlaybackVersion::default(),
+            frames,
+        }
+    }
+
     pub fn from_reader(mut reader: impl Read) -> Result<Self, PlaybackReadError> {
         let mut buf = Vec::new();
         reader.read_to_end(&mut buf)?;
@@ -26,31 +74,59 @@ impl Playback {
     }
 
     /// Reads a [Playback] from binary data
+    ///
+    /// Returns [InvalidHeader](PlaybackReadError::InvalidHeader) if the data doesn't look like a playback at all.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, PlaybackReadError> {
-        let mut reader = BinReader::new(bytes);
+        if bytes.is_empty() {
+            return Err(PlaybackReadError::InvalidHeader);
+        }
 
-        let mut skip_scale = true;
+        let mut reader = BinReader::new(bytes);
 
-        match reader.read_string().as_deref() {
-            Ok("TIMELINE") => {
-                skip_scale = reader.read_i32()? == 1;
+        let version = match reader.read_string().as_deref() {
+            Ok("TIMELINE") => match reader.read_i32()? {
+                1 => PlaybackVersion::V1,
+                2 => PlaybackVersion::V2,
+                v => return Err(PlaybackReadError::UnknownVersion(v)),
+            },
+            // Legacy playbacks start with the frame count, which can happen to be a valid string
+            _ => {
+                reader.restart();
+                PlaybackVersion::Legacy
             }
-            // Okay so I'm not sure why I thought this
-            // but apparently the varint could be invalid while the rest is readable
-            // so we restart and just keep reading
-            // Can't find the documentation for PlaybackData rn so leaving it
-            Ok(_header) => reader.restart(),
-            Err(_) => Err(PlaybackReadError::InvalidHeader)?,
+        };
+
+        let count = reader
+            .read_i32()
+            .map_err(|_| PlaybackReadError::InvalidHeader)?;
+
+        // Without a header the only thing marking a playback is the frame count being sensible
+        if count < 0
+            || (version == PlaybackVersion::Legacy
+                && count as usize > reader.remaining() / PlaybackFrame::MIN_SIZE)
+        {
+            return Err(match version {
+                PlaybackVersion::Legacy => PlaybackReadError::InvalidHeader,
+                _ => PlaybackReadError::InvalidFrameCount(count),
+            });
         }
 
-        let frames = reader.read_i32()?;
-        let mut buf = Vec::with_capacity(frames as usize);
+        let count = count as usize;
+        let mut frames =
+            Vec::with_capacity(count.min(reader.remaining() / PlaybackFrame::MIN_SIZE));
 
-        for _ in 0 .. frames {
-            buf.push(PlaybackFrame::from_reader(&mut reader, skip_scale)?);
+        for i in 0 .. count {
+            if reader.remaining() == 0 {
+                return Err(PlaybackReadError::FrameCountMismatch {
+                    expected: count,
+                    found: i,
+                });
+            }
+
+            frames.push(PlaybackFrame::from_reader(&mut reader, version, i)?);
         }
 
-        Ok(Self { frames: buf })
+        Ok(Self { version, frames })
     }
 
     fn write<T: Write>(&self, writer: &mut BinWriter<T>) -> std::io::Result<()> {
@@ -58,16 +134,16 @@ impl Playback {
             return Ok(());
         }
 
-        let first_frame = &self.frames[0];
-        let base_timestamp = first_frame.timestamp;
-        let base_position = first_frame.position;
+        let base_timestamp = self.frames[0].timestamp;
 
-        writer.write_string("TIMELINE")?;
-        writer.write_i32(2)?;
+        if let Some(version) = self.version.header_version() {
+            writer.write_string("TIMELINE")?;
+            writer.write_i32(version)?;
+        }
         writer.write_i32(self.frames.len() as i32)?;
 
         for frame in &self.frames {
-            frame.write(writer, base_position, base_timestamp)?;
+            frame.write(writer, base_timestamp, self.version)?;
         }
         Ok(())
     }
@@ -89,6 +165,7 @@ impl Playback {
 }
 
 /// A single frame of a [Playback]
+#[derive(Debug, Clone)]
 pub struct PlaybackFrame {
     pub position: [f32; 2],
     pub timestamp: f32,
@@ -104,12 +181,32 @@ pub struct PlaybackFrame {
 }
 
 impl PlaybackFrame {
-    fn from_reader(reader: &mut BinReader, skip_scale: bool) -> Result<Self, BinReadError> {
+    /// The smallest amount of bytes a frame can take up, the scale and dash direction add 16 bytes
+    const MIN_SIZE: usize = 25;
+
+    fn from_reader(
+        reader: &mut BinReader,
+        version: PlaybackVersion,
+        index: usize,
+    ) -> Result<Self, PlaybackReadError> {
+        Self::read(reader, version).map_err(|e| match e {
+            FrameReadError::Bin(BinReadError::EndOfBuffer) =>
+                PlaybackReadError::TruncatedFrame(index),
+            FrameReadError::Bin(e) => PlaybackReadError::BinError(e),
+            FrameReadError::InvalidFacing(value) => PlaybackReadError::InvalidFacing {
+                frame: index,
+                value,
+            },
+        })
+    }
+
+    fn read(reader: &mut BinReader, version: PlaybackVersion) -> Result<Self, FrameReadError> {
         let position = [reader.read_f32()?, reader.read_f32()?];
         let timestamp = reader.read_f32()?;
         let animation = reader.read_string()?;
-        // TODO: make this an actual error
-        let facing = PlaybackFacing::from_int(reader.read_i32()?).unwrap();
+        let facing = reader.read_i32()?;
+        let facing =
+            PlaybackFacing::from_int(facing).ok_or(FrameReadError::InvalidFacing(facing))?;
         let on_ground = reader.read_bool()?;
         let hair_color = [reader.read_u8()?, reader.read_u8()?, reader.read_u8()?];
         let depth = reader.read_i32()?;
@@ -117,12 +214,12 @@ impl PlaybackFrame {
         let scale;
         let dash_direction;
 
-        if skip_scale {
-            scale = [(facing as i32) as f32, 1.0];
-            dash_direction = [0.0, 0.0];
-        } else {
+        if version.has_scale() {
             scale = [reader.read_f32()?, reader.read_f32()?];
             dash_direction = [reader.read_f32()?, reader.read_f32()?];
+        } else {
+            scale = [(facing as i32) as f32, 1.0];
+            dash_direction = [0.0, 0.0];
         }
 
         Ok(PlaybackFrame {
@@ -142,11 +239,11 @@ impl PlaybackFrame {
     fn write<T: Write>(
         &self,
         writer: &mut BinWriter<T>,
-        base_pos: [f32; 2],
         base_timestamp: f32,
+        version: PlaybackVersion,
     ) -> std::io::Result<()> {
-        writer.write_f32(self.position[0] - base_pos[0])?;
-        writer.write_f32(self.position[1] - base_pos[1])?;
+        writer.write_f32(self.position[0])?;
+        writer.write_f32(self.position[1])?;
         writer.write_f32(self.timestamp - base_timestamp)?;
         writer.write_string(&self.animation)?;
         writer.write_i32(self.facing as i32)?;
@@ -155,10 +252,13 @@ impl PlaybackFrame {
         writer.write_u8(self.hair_color[1])?;
         writer.write_u8(self.hair_color[2])?;
         writer.write_i32(self.depth)?;
-        writer.write_f32(self.scale[0])?;
-        writer.write_f32(self.scale[1])?;
-        writer.write_f32(self.dash_direction[0])?;
-        writer.write_f32(self.dash_direction[1])?;
+
+        if version.has_scale() {
+            writer.write_f32(self.scale[0])?;
+            writer.write_f32(self.scale[1])?;
+            writer.write_f32(self.dash_direction[0])?;
+            writer.write_f32(self.dash_direction[1])?;
+        }
         Ok(())
     }
 }
@@ -178,13 +278,49 @@ impl PlaybackFacing {
             _ => None,
         }
     }
+
+    /// Gets the opposite direction
+    pub fn flipped(self) -> PlaybackFacing {
+        match self {
+            PlaybackFacing::Left => PlaybackFacing::Right,
+            PlaybackFacing::Right => PlaybackFacing::Left,
+        }
+    }
+}
+
+/// Errors from reading a single frame, converted into a [PlaybackReadError] with the frame index
+enum FrameReadError {
+    Bin(BinReadError),
+    InvalidFacing(i32),
+}
+
+impl From<BinReadError> for FrameReadError {
+    fn from(value: BinReadError) -> Self {
+        FrameReadError::Bin(value)
+    }
 }
 
 #[derive(Debug)]
 pub enum PlaybackReadError {
     BinError(BinReadError),
     IoError(std::io::Error),
+    /// The data isn't a playback
     InvalidHeader,
+    /// The `TIMELINE` header has a version other than 1 or 2
+    UnknownVersion(i32),
+    InvalidFrameCount(i32),
+    /// A frame has a facing other than -1 or 1
+    InvalidFacing {
+        frame: usize,
+        value: i32,
+    },
+    /// The data ended partway through a frame, holds the index of the frame
+    TruncatedFrame(usize),
+    /// The data ended before all the frames in the header were read
+    FrameCountMismatch {
+        expected: usize,
+        found: usize,
+    },
 }
 
 impl Display for PlaybackReadError {
@@ -193,6 +329,18 @@ impl Display for PlaybackReadError {
             PlaybackReadError::BinError(bin_read_error) => bin_read_error.fmt(f),
             PlaybackReadError::IoError(error) => error.fmt(f),
             PlaybackReadError::InvalidHeader => write!(f, "Invalid Playback header"),
+            PlaybackReadError::UnknownVersion(version) =>
+                write!(f, "Unknown Playback version {version}"),
+            PlaybackReadError::InvalidFrameCount(count) =>
+                write!(f, "Invalid Playback frame count {count}"),
+            PlaybackReadError::InvalidFacing { frame, value } =>
+                write!(f, "Frame {frame} has invalid facing {value}"),
+            PlaybackReadError::TruncatedFrame(frame) =>
+                write!(f, "Playback ended partway through frame {frame}"),
+            PlaybackReadError::FrameCountMismatch { expected, found } => write!(
+                f,
+                "Playback header says it has {expected} frames but only {found} were found"
+            ),
         }
     }
 }
@@ -0,0 +1,434 @@
+//! Renders the route of a [Playback] as an SVG or PNG
+//!
+//! The path is colored by whether the player is on the ground and dashes are marked.
+//! It can be drawn on its own or over the solids of a room, which is useful for checking that
+//! a `playbackTutorial` still lines up after the room was edited.
+use std::{
+    error::Error,
+    fmt::{Display, Write},
+};
+
+use crate::{maps::elements::level::Level, playbacks::Playback};
+
+const BACKGROUND: [u8; 3] = [0x1E, 0x1E, 0x28];
+const SOLID: [u8; 3] = [0x5A, 0x5A, 0x6E];
+const GROUND: [u8; 3] = [0x4C, 0xAF, 0x50];
+const AIR: [u8; 3] = [0x21, 0x96, 0xF3];
+const DASH: [u8; 3] = [0xF4, 0x43, 0x36];
+
+/// The space left around a path when it is drawn without a room
+const MARGIN: f32 = 16.0;
+
+/// The most pixels a rendered PNG can have, which is 256MB of image data
+pub const MAX_PNG_PIXELS: u64 = 1 << 26;
+
+/// Errors from rendering a [Playback] as a PNG
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderError {
+    /// The image would have more than [MAX_PNG_PIXELS] pixels
+    TooLarge { width: u64, height: u64 },
+    /// The image would have no pixels, which PNGs can't store
+    Empty,
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::TooLarge { width, height } => write!(
+                f,
+                "A {width}x{height} image is larger than the limit of {MAX_PNG_PIXELS} pixels"
+            ),
+            RenderError::Empty => write!(f, "Can't render an image with no pixels"),
+        }
+    }
+}
+
+impl Error for RenderError {}
+
+/// The solid tiles of a room, drawn behind a rendered playback
+#[derive(Debug, Clone, Default)]
+pub struct RoomPreview {
+    /// The width of the room in pixels
+    pub width: u32,
+    /// The height of the room in pixels
+    pub height: u32,
+    /// Each row of 8x8 tiles, `true` if the tile is solid
+    pub solids: Vec<Vec<bool>>,
+}
+
+impl RoomPreview {
+    /// Creates a preview from the foreground tiles of a level
+    pub fn from_level(level: &Level) -> Self {
+        let solids = level
+            .solids
+            .inner_text
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .map(|line| {
+                line.trim_end_matches('\r')
+                    .chars()
+                    .map(|c| c != '0')
+                    .collect()
+            })
+            .collect();
+
+        RoomPreview {
+            width: level.width.as_i64().max(0) as u32,
+            height: level.height.as_i64().max(0) as u32,
+            solids,
+        }
+    }
+
+    /// Whether the tile at a tile position is solid
+    pub fn is_solid(&self, x: usize, y: usize) -> bool {
+        self.solids
+            .get(y)
+            .and_then(|row| row.get(x))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Gets each run of solid tiles as `(x, y, length)` in tiles
+    fn solid_runs(&self) -> Vec<(usize, usize, usize)> {
+        let mut runs = Vec::new();
+
+        for (y, row) in self.solids.iter().enumerate() {
+            let mut x = 0;
+
+            while x < row.len() {
+                if row[x] {
+                    let start = x;
+                    while x < row.len() && row[x] {
+                        x += 1;
+                    }
+                    runs.push((start, y, x - start));
+                } else {
+                    x += 1;
+                }
+            }
+        }
+
+        runs
+    }
+}
+
+/// The area an image covers, in room pixels
+struct Canvas {
+    offset: [f32; 2],
+    width: u32,
+    height: u32,
+}
+
+impl Playback {
+    /// Gets the position of every frame with the first frame moved to `origin`
+    ///
+    /// For a `playbackTutorial` the origin is the position of the entity in the room.
+    pub fn path(&self, origin: [f32; 2]) -> Vec<[f32; 2]> {
+        let Some(first) = self.frames.first().map(|f| f.position) else {
+            return Vec::new();
+        };
+
+        self.frames
+            .iter()
+            .map(|f| {
+                [
+                    origin[0] + f.position[0] - first[0],
+                    origin[1] + f.position[1] - first[1],
+                ]
+            })
+            .collect()
+    }
+
+    fn canvas(&self, path: &[[f32; 2]], room: Option<&RoomPreview>) -> Canvas {
+        if let Some(room) = room {
+            return Canvas {
+                offset: [0.0, 0.0],
+                width: room.width,
+                height: room.height,
+            };
+        }
+
+        let mut min = [0.0f32; 2];
+        let mut max = [0.0f32; 2];
+
+        for (i, point) in path.iter().enumerate() {
+            for axis in 0 .. 2 {
+                min[axis] = if i == 0 {
+                    point[axis]
+                } else {
+                    min[axis].min(point[axis])
+                };
+                max[axis] = if i == 0 {
+                    point[axis]
+                } else {
+                    max[axis].max(point[axis])
+                };
+            }
+        }
+
+        Canvas {
+            offset: [min[0] - MARGIN, min[1] - MARGIN],
+            width: (max[0] - min[0] + MARGIN * 2.0).ceil() as u32,
+            height: (max[1] - min[1] + MARGIN * 2.0).ceil() as u32,
+        }
+    }
+
+    /// Renders the path of the playback as an SVG
+    ///
+    /// If a room is given the path is drawn over its solids and `origin` is the position
+    /// of the first frame in the room, otherwise the image is sized to fit the path.
+    pub fn to_svg(&self, room: Option<&RoomPreview>, origin: [f32; 2]) -> String {
+        let path = self.path(origin);
+        let canvas = self.canvas(&path, room);
+        let [ox, oy] = canvas.offset;
+        let mut svg = String::new();
+
+        // Writing to a String can't fail
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="{ox} {oy} {w} {h}">"#,
+            w = canvas.width,
+            h = canvas.height,
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{ox}" y="{oy}" width="{}" height="{}" fill="{}"/>"#,
+            canvas.width,
+            canvas.height,
+            hex(BACKGROUND)
+        );
+
+        if let Some(room) = room {
+            for (x, y, len) in room.solid_runs() {
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="8" fill="{}"/>"#,
+                    x * 8,
+                    y * 8,
+                    len * 8,
+                    hex(SOLID)
+                );
+            }
+        }
+
+        // Split the path into runs with the same on_ground so each can get its own color
+        let mut start = 0;
+        while start + 1 < path.len() {
+            let on_ground = self.frames[start + 1].on_ground;
+            let mut end = start + 1;
+            while end + 1 < path.len() && self.frames[end + 1].on_ground == on_ground {
+                end += 1;
+            }
+
+            let points = path[start ..= end]
+                .iter()
+                .map(|[x, y]| format!("{x},{y}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = writeln!(
+                svg,
+                r#"<polyline points="{points}" fill="none" stroke="{}" stroke-width="1"/>"#,
+                hex(if on_ground { GROUND } else { AIR })
+            );
+
+            start = end;
+        }
+
+        for i in self.dash_frames() {
+            let [x, y] = path[i];
+            let _ = writeln!(
+                svg,
+                r#"<circle cx="{x}" cy="{y}" r="2" fill="{}"/>"#,
+                hex(DASH)
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Renders the path of the playback as a PNG
+    ///
+    /// Works the same as [to_svg](Self::to_svg), with every room pixel drawn as a `scale` sized square.<br>
+    /// Fails if the scaled image would have more than [MAX_PNG_PIXELS] pixels.
+    pub fn to_png(
+        &self,
+        room: Option<&RoomPreview>,
+        origin: [f32; 2],
+        scale: u32,
+    ) -> Result<Vec<u8>, RenderError> {
+        let path = self.path(origin);
+        let canvas = self.canvas(&path, room);
+        let mut image = Image::new(canvas, scale.max(1))?;
+
+        if let Some(room) = room {
+            for (x, y, len) in room.solid_runs() {
+                image.fill_rect(
+                    [(x * 8) as f32, (y * 8) as f32],
+                    [(len * 8) as f32, 8.0],
+                    SOLID,
+                );
+            }
+        }
+
+        for i in 1 .. path.len() {
+            let color = if self.frames[i].on_ground {
+                GROUND
+            } else {
+                AIR
+            };
+            image.line(path[i - 1], path[i], color);
+        }
+
+        for i in self.dash_frames() {
+            let [x, y] = path[i];
+            image.fill_rect([x - 1.5, y - 1.5], [3.0, 3.0], DASH);
+        }
+
+        Ok(image.encode())
+    }
+}
+
+struct Image {
+    canvas: Canvas,
+    scale: u32,
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    fn new(canvas: Canvas, scale: u32) -> Result<Self, RenderError> {
+        let (Some(width), Some(height)) = (
+            canvas.width.checked_mul(scale),
+            canvas.height.checked_mul(scale),
+        ) else {
+            return Err(RenderError::TooLarge {
+                width: canvas.width as u64 * scale as u64,
+                height: canvas.height as u64 * scale as u64,
+            });
+        };
+
+        // Checked before allocating, a playback that flies off into the distance could need gigabytes
+        if width as u64 * height as u64 > MAX_PNG_PIXELS {
+            return Err(RenderError::TooLarge {
+                width: width as u64,
+                height: height as u64,
+            });
+        }
+
+        if width == 0 || height == 0 {
+            return Err(RenderError::Empty);
+        }
+
+        let pixels = [BACKGROUND[0], BACKGROUND[1], BACKGROUND[2], 0xFF]
+            .repeat(width as usize * height as usize);
+
+        Ok(Image {
+            canvas,
+            scale,
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Fills a rectangle given in room pixels
+    fn fill_rect(&mut self, pos: [f32; 2], size: [f32; 2], color: [u8; 3]) {
+        let scale = self.scale as f32;
+        let x0 = ((pos[0] - self.canvas.offset[0]) * scale).floor().max(0.0) as u32;
+        let y0 = ((pos[1] - self.canvas.offset[1]) * scale).floor().max(0.0) as u32;
+        let x1 = (((pos[0] + size[0] - self.canvas.offset[0]) * scale)
+            .ceil()
+            .max(0.0) as u32)
+            .min(self.width);
+        let y1 = (((pos[1] + size[1] - self.canvas.offset[1]) * scale)
+            .ceil()
+            .max(0.0) as u32)
+            .min(self.height);
+
+        for y in y0 .. y1 {
+            for x in x0 .. x1 {
+                let i = (y * self.width + x) as usize * 4;
+                self.pixels[i .. i + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Encodes the image as an RGBA PNG
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        // The size was checked when creating the image and there are no write errors when writing to a vec
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&self.pixels).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    fn line(&mut self, from: [f32; 2], to: [f32; 2], color: [u8; 3]) {
+        // Frames far outside the canvas would otherwise take millions of steps that draw nothing
+        let Some((from, to)) = self.clip(from, to) else {
+            return;
+        };
+
+        let steps = (to[0] - from[0])
+            .abs()
+            .max((to[1] - from[1]).abs())
+            .ceil()
+            .max(1.0) as u32;
+
+        for step in 0 ..= steps {
+            let t = step as f32 / steps as f32;
+            let x = from[0] + (to[0] - from[0]) * t;
+            let y = from[1] + (to[1] - from[1]) * t;
+            self.fill_rect([x.floor(), y.floor()], [1.0, 1.0], color);
+        }
+    }
+
+    /// Clips a line in room pixels to the canvas, returning `None` if none of it is on the canvas
+    fn clip(&self, from: [f32; 2], to: [f32; 2]) -> Option<([f32; 2], [f32; 2])> {
+        let min = self.canvas.offset;
+        let max = [
+            min[0] + self.canvas.width as f32,
+            min[1] + self.canvas.height as f32,
+        ];
+        let (mut start, mut end) = (0.0f32, 1.0f32);
+
+        for axis in 0 .. 2 {
+            let delta = to[axis] - from[axis];
+
+            if delta == 0.0 {
+                if from[axis] < min[axis] || from[axis] > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let a = (min[axis] - from[axis]) / delta;
+            let b = (max[axis] - from[axis]) / delta;
+            start = start.max(a.min(b));
+            end = end.min(a.max(b));
+        }
+
+        if start > end {
+            return None;
+        }
+
+        let lerp = |t: f32| {
+            [
+                from[0] + (to[0] - from[0]) * t,
+                from[1] + (to[1] - from[1]) * t,
+            ]
+        };
+        Some((lerp(start), lerp(end)))
+    }
+}
+
+fn hex(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
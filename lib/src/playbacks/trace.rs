@@ -0,0 +1,365 @@
+//! Converts recorded position traces into [Playback]s
+//!
+//! Traces list the state of the player on each frame, either as CSV or JSON.<br>
+//! Only the position is required, everything else falls back to a default:
+//!
+//! | CSV column    | JSON key         | Default  |
+//! |---------------|------------------|----------|
+//! | `frame`       | `frame`          | the row  |
+//! | `x`, `y`      | `x`, `y` or `position: [x, y]` | required |
+//! | `animation`   | `animation`      | `idle`   |
+//! | `facing`      | `facing`         | `right`  |
+//! | `hair_color`  | `hair_color`     | `ac3232` |
+//! | `dash_x`, `dash_y` | `dash_direction: [x, y]` | `[0, 0]` |
+//! | `on_ground`   | `on_ground`      | `false`  |
+//! | `depth`       | `depth`          | `0`      |
+//! | `scale_x`, `scale_y` | `scale: [x, y]` | `[facing, 1]` |
+//!
+//! Facing can be `left`, `right`, `-1` or `1` and hair colors are hex strings, JSON also allows `[r, g, b]`.
+//! Timestamps are taken from the frame number, assuming the recording was at 60fps.
+//! Positions are made relative to the first frame, so the playback starts where it's placed.
+use std::{error::Error, fmt::Display};
+
+use saphyr::{LoadableYamlNode, Yaml};
+
+use crate::{
+    playbacks::{FRAME_TIME, Playback, PlaybackFacing, PlaybackFrame},
+    utils::{YamlExt, YamlParseError},
+};
+
+/// Madeline's hair color when she has a dash
+const DEFAULT_HAIR_COLOR: [u8; 3] = [0xAC, 0x32, 0x32];
+
+impl Playback {
+    /// Creates a playback from a CSV trace
+    ///
+    /// The first line has to be a header naming the columns, see the [module docs](self) for the column names.<br>
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn from_trace_csv(csv: &str) -> Result<Self, TraceError> {
+        let mut lines = csv
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'));
+
+        let header = lines
+            .next()
+            .ok_or(TraceError::MissingColumn("x"))?
+            .split(',')
+            .map(|c| c.trim().to_ascii_lowercase())
+            .collect::<Vec<_>>();
+
+        for required in ["x", "y"] {
+            if !header.iter().any(|c| c == required) {
+                return Err(TraceError::MissingColumn(required));
+            }
+        }
+
+        let mut builder = TraceBuilder::default();
+
+        for (row, line) in lines.enumerate() {
+            let values = line.split(',').map(str::trim).collect::<Vec<_>>();
+            let get = |name: &str| {
+                header
+                    .iter()
+                    .position(|c| c == name)
+                    .and_then(|i| values.get(i).copied())
+                    .filter(|v| !v.is_empty())
+            };
+            let float = |name: &'static str| {
+                get(name)
+                    .map(|v| {
+                        v.parse::<f32>()
+                            .map_err(|_| TraceError::invalid(row, name, v))
+                    })
+                    .transpose()
+            };
+
+            let facing = get("facing")
+                .map(|v| parse_facing(v).ok_or_else(|| TraceError::invalid(row, "facing", v)))
+                .transpose()?
+                .unwrap_or(PlaybackFacing::Right);
+
+            let frame = TraceFrame {
+                frame: get("frame")
+                    .map(|v| v.parse().map_err(|_| TraceError::invalid(row, "frame", v)))
+                    .transpose()?,
+                position: [
+                    float("x")?.ok_or(TraceError::MissingField(row, "x"))?,
+                    float("y")?.ok_or(TraceError::MissingField(row, "y"))?,
+                ],
+                animation: get("animation").map(ToOwned::to_owned),
+                facing,
+                hair_color: get("hair_color")
+                    .map(|v| parse_hex(v).ok_or_else(|| TraceError::invalid(row, "hair_color", v)))
+                    .transpose()?,
+                dash_direction: [
+                    float("dash_x")?.unwrap_or(0.0),
+                    float("dash_y")?.unwrap_or(0.0),
+                ],
+                on_ground: get("on_ground")
+                    .map(|v| parse_bool(v).ok_or_else(|| TraceError::invalid(row, "on_ground", v)))
+                    .transpose()?,
+                depth: get("depth")
+                    .map(|v| v.parse().map_err(|_| TraceError::invalid(row, "depth", v)))
+                    .transpose()?,
+                scale: match (float("scale_x")?, float("scale_y")?) {
+                    (None, None) => None,
+                    (x, y) => Some([x.unwrap_or(facing as i32 as f32), y.unwrap_or(1.0)]),
+                },
+            };
+
+            builder.push(row, frame)?;
+        }
+
+        Ok(builder.finish())
+    }
+
+    /// Creates a playback from a JSON trace
+    ///
+    /// The trace can either be a list of frames or an object with a `frames` list,
+    /// see the [module docs](self) for the keys of each frame.
+    pub fn from_trace_json(json: &str) -> Result<Self, TraceError> {
+        let docs = Yaml::load_from_str(json).map_err(YamlParseError::from)?;
+        let root = docs.first().ok_or(YamlParseError::MissingField("frames"))?;
+
+        let frames = match root.as_mapping_get("frames") {
+            Some(frames) => frames.try_as_vec()?,
+            None => root.try_as_vec()?,
+        };
+
+        let mut builder = TraceBuilder::default();
+
+        for (row, yaml) in frames.iter().enumerate() {
+            let float = |yaml: &Yaml, name: &'static str| {
+                yaml.as_integer()
+                    .map(|i| i as f32)
+                    .or(yaml.as_floating_point().map(|f| f as f32))
+                    .ok_or_else(|| TraceError::invalid(row, name, yaml_str(yaml)))
+            };
+            let get_float = |name: &'static str| {
+                yaml.as_mapping_get(name)
+                    .map(|v| float(v, name))
+                    .transpose()
+            };
+            let get_vec2 = |name: &'static str| {
+                yaml.as_mapping_get(name)
+                    .map(|v| match v.as_vec().map(Vec::as_slice) {
+                        Some([x, y]) => Ok([float(x, name)?, float(y, name)?]),
+                        _ => Err(TraceError::invalid(row, name, yaml_str(v))),
+                    })
+                    .transpose()
+            };
+
+            let position = match get_vec2("position")? {
+                Some(position) => position,
+                None => [
+                    get_float("x")?.ok_or(TraceError::MissingField(row, "x"))?,
+                    get_float("y")?.ok_or(TraceError::MissingField(row, "y"))?,
+                ],
+            };
+
+            let facing = match yaml.as_mapping_get("facing") {
+                Some(v) => v
+                    .as_integer()
+                    .and_then(|i| PlaybackFacing::from_int(i as i32))
+                    .or(v.as_str().and_then(parse_facing))
+                    .ok_or_else(|| TraceError::invalid(row, "facing", yaml_str(v)))?,
+                None => PlaybackFacing::Right,
+            };
+
+            let hair_color = match yaml.as_mapping_get("hair_color") {
+                Some(v) => Some(
+                    match v.as_vec().map(Vec::as_slice) {
+                        Some([r, g, b]) => [r, g, b]
+                            .map(|c| c.as_integer().and_then(|c| u8::try_from(c).ok()))
+                            .into_iter()
+                            .collect::<Option<Vec<_>>>()
+                            .map(|c| [c[0], c[1], c[2]]),
+                        _ => v.as_str().and_then(parse_hex),
+                    }
+                    .ok_or_else(|| TraceError::invalid(row, "hair_color", yaml_str(v)))?,
+                ),
+                None => None,
+            };
+
+            let frame = TraceFrame {
+                frame: yaml
+                    .as_mapping_get("frame")
+                    .map(|v| {
+                        v.as_integer()
+                            .and_then(|i| u64::try_from(i).ok())
+                            .ok_or_else(|| TraceError::invalid(row, "frame", yaml_str(v)))
+                    })
+                    .transpose()?,
+                position,
+                animation: yaml
+                    .as_mapping_get("animation")
+                    .map(|v| {
+                        v.as_str()
+                            .map(ToOwned::to_owned)
+                            .ok_or_else(|| TraceError::invalid(row, "animation", yaml_str(v)))
+                    })
+                    .transpose()?,
+                facing,
+                hair_color,
+                dash_direction: get_vec2("dash_direction")?.unwrap_or([0.0, 0.0]),
+                on_ground: yaml
+                    .as_mapping_get("on_ground")
+                    .map(|v| {
+                        v.try_as_bool()
+                            .map_err(|_| TraceError::invalid(row, "on_ground", yaml_str(v)))
+                    })
+                    .transpose()?,
+                depth: yaml
+                    .as_mapping_get("depth")
+                    .map(|v| {
+                        v.as_integer()
+                            .and_then(|i| i32::try_from(i).ok())
+                            .ok_or_else(|| TraceError::invalid(row, "depth", yaml_str(v)))
+                    })
+                    .transpose()?,
+                scale: get_vec2("scale")?,
+            };
+
+            builder.push(row, frame)?;
+        }
+
+        Ok(builder.finish())
+    }
+}
+
+/// A frame read from a trace before defaults are applied
+struct TraceFrame {
+    frame: Option<u64>,
+    position: [f32; 2],
+    animation: Option<String>,
+    facing: PlaybackFacing,
+    hair_color: Option<[u8; 3]>,
+    dash_direction: [f32; 2],
+    on_ground: Option<bool>,
+    depth: Option<i32>,
+    scale: Option<[f32; 2]>,
+}
+
+#[derive(Default)]
+struct TraceBuilder {
+    frames: Vec<PlaybackFrame>,
+    last_frame: Option<u64>,
+}
+
+impl TraceBuilder {
+    fn push(&mut self, row: usize, trace: TraceFrame) -> Result<(), TraceError> {
+        let frame = trace.frame.unwrap_or(row as u64);
+
+        if self.last_frame.is_some_and(|last| frame <= last) {
+            return Err(TraceError::FramesOutOfOrder(row));
+        }
+        self.last_frame = Some(frame);
+
+        self.frames.push(PlaybackFrame {
+            position: trace.position,
+            timestamp: frame as f32 * FRAME_TIME,
+            animation: trace.animation.unwrap_or_else(|| "idle".to_owned()),
+            facing: trace.facing,
+            on_ground: trace.on_ground.unwrap_or(false),
+            hair_color: trace.hair_color.unwrap_or(DEFAULT_HAIR_COLOR),
+            depth: trace.depth.unwrap_or(0),
+            sounds: 0,
+            scale: trace.scale.unwrap_or([trace.facing as i32 as f32, 1.0]),
+            dash_direction: trace.dash_direction,
+        });
+
+        Ok(())
+    }
+
+    fn finish(self) -> Playback {
+        let mut playback = Playback::new(self.frames);
+        // Traces are recorded in room coordinates, but playbacks are offsets from where they're placed
+        playback.set_origin([0.0, 0.0]);
+        playback
+    }
+}
+
+fn parse_facing(str: &str) -> Option<PlaybackFacing> {
+    match str.to_ascii_lowercase().as_str() {
+        "left" | "-1" => Some(PlaybackFacing::Left),
+        "right" | "1" => Some(PlaybackFacing::Right),
+        _ => None,
+    }
+}
+
+fn parse_bool(str: &str) -> Option<bool> {
+    match str.to_ascii_lowercase().as_str() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_hex(str: &str) -> Option<[u8; 3]> {
+    let hex = str.trim_start_matches('#');
+
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i .. i + 2], 16).ok();
+
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn yaml_str(yaml: &Yaml) -> String {
+    match yaml.as_str() {
+        Some(str) => str.to_owned(),
+        None => format!("{yaml:?}"),
+    }
+}
+
+#[derive(Debug)]
+pub enum TraceError {
+    YamlError(YamlParseError),
+    /// A CSV trace is missing a required column
+    MissingColumn(&'static str),
+    /// A frame is missing a required field, holds the index of the frame and the field name
+    MissingField(usize, &'static str),
+    InvalidValue {
+        frame: usize,
+        name: &'static str,
+        value: String,
+    },
+    /// The frame numbers have to be increasing, holds the index of the first frame out of order
+    FramesOutOfOrder(usize),
+}
+
+impl TraceError {
+    fn invalid(frame: usize, name: &'static str, value: impl ToString) -> Self {
+        TraceError::InvalidValue {
+            frame,
+            name,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl Display for TraceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceError::YamlError(e) => Display::fmt(e, f),
+            TraceError::MissingColumn(name) => write!(f, "Trace is missing column \"{name}\""),
+            TraceError::MissingField(frame, name) =>
+                write!(f, "Frame {frame} is missing \"{name}\""),
+            TraceError::InvalidValue { frame, name, value } =>
+                write!(f, "Frame {frame} has an invalid {name} \"{value}\""),
+            TraceError::FramesOutOfOrder(frame) =>
+                write!(f, "Frame {frame} comes before the frame preceding it"),
+        }
+    }
+}
+
+impl Error for TraceError {}
+
+impl From<YamlParseError> for TraceError {
+    fn from(value: YamlParseError) -> Self {
+        TraceError::YamlError(value)
+    }
+}
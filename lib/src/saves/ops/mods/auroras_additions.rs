@@ -133,7 +133,7 @@ impl FromYaml for AurorasAdditionsSave {
         })
     }
 
-    fn to_yaml(&self) -> Result<saphyr::Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<saphyr::Yaml<'_>, YamlWriteError> {
         let mut root = Mapping::new();
 
         let sessions_per_level = self
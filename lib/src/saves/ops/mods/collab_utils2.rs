@@ -218,7 +218,7 @@ impl FromYaml for CollabsUtils2Save {
         })
     }
 
-    fn to_yaml(&self) -> Result<saphyr::Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<saphyr::Yaml<'_>, YamlWriteError> {
         let mut root = Mapping::new();
 
         let sessions_per_level = self
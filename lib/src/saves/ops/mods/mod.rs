@@ -204,7 +204,7 @@ impl FromYaml for DynYamlDoc {
         )
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok((&self.1).into())
     }
 }
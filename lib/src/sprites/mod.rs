@@ -0,0 +1,347 @@
+//! Implements reading of Celeste's sprite banks, `Sprites.xml` and `Portraits.xml`
+//!
+//! Vanilla stores these in `Content/Graphics`. Mods can override sprites globally with their own
+//! `Graphics/Sprites.xml` and `Graphics/Portraits.xml` or per map using the `Sprites` and `Portraits`
+//! fields in the map's meta.yaml.
+use std::{
+    collections::{BTreeMap, HashSet},
+    error::Error,
+    fmt::Display,
+    io::Read,
+    num::ParseFloatError,
+};
+
+use quick_xml::{
+    Reader,
+    events::{BytesStart, Event},
+};
+
+use crate::playbacks::Playback;
+
+/// A collection of sprite definitions, keyed by their id
+///
+/// This is the root `Sprites` element of `Sprites.xml` or the `Portraits` element of `Portraits.xml`.
+#[derive(Debug, Clone, Default)]
+pub struct SpriteBank {
+    pub sprites: BTreeMap<String, SpriteData>,
+}
+
+/// A single sprite definition in a [SpriteBank]
+#[derive(Debug, Clone, Default)]
+pub struct SpriteData {
+    /// The name of the element, this is how the sprite is referenced
+    pub id: String,
+    /// The directory in the atlas all the animation paths are relative to
+    pub path: Option<String>,
+    /// The animation the sprite starts playing
+    pub start: Option<String>,
+    /// The id of a sprite to copy animations from
+    pub copy: Option<String>,
+    pub justify: Option<[f32; 2]>,
+    pub origin: Option<[f32; 2]>,
+    pub position: Option<[f32; 2]>,
+    /// Whether the sprite has a `Center` element, which centers its origin
+    pub center: bool,
+    pub animations: Vec<SpriteAnimation>,
+    /// Any other attributes on the sprite element
+    ///
+    /// Portraits use these for things like `textbox` and `sfx`
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// An `Anim` or `Loop` element in a [SpriteData]
+#[derive(Debug, Clone)]
+pub struct SpriteAnimation {
+    pub id: String,
+    /// Whether this was a `Loop` element rather than an `Anim` element
+    pub looping: bool,
+    /// The path of the frames, relative to the sprite's path
+    pub path: String,
+    /// The time between frames in seconds
+    pub delay: Option<f32>,
+    /// The frames to use, in the `0-3,5*2` format the game uses
+    pub frames: Option<String>,
+    /// The animation to play once this one finishes, can be a comma separated list
+    pub goto: Option<String>,
+}
+
+impl SpriteBank {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a sprite bank from a string
+    pub fn parse(str: &str) -> Result<Self, SpriteReadError> {
+        let mut reader = Reader::from_str(str);
+        reader.config_mut().trim_text(true);
+
+        let mut bank = SpriteBank::new();
+        let mut depth = 0;
+        let mut current: Option<SpriteData> = None;
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(start) => {
+                    depth += 1;
+                    match depth {
+                        2 => current = Some(SpriteData::from_element(&start)?),
+                        3 =>
+                            if let Some(sprite) = &mut current {
+                                sprite.add_child(&start)?
+                            },
+                        _ => {}
+                    }
+                }
+                Event::Empty(start) => match depth {
+                    1 => {
+                        let sprite = SpriteData::from_element(&start)?;
+                        bank.sprites.insert(sprite.id.clone(), sprite);
+                    }
+                    2 =>
+                        if let Some(sprite) = &mut current {
+                            sprite.add_child(&start)?
+                        },
+                    _ => {}
+                },
+                Event::End(_) => {
+                    if depth == 2
+                        && let Some(sprite) = current.take()
+                    {
+                        bank.sprites.insert(sprite.id.clone(), sprite);
+                    }
+                    depth -= 1;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(bank)
+    }
+
+    /// Reads and parses a sprite bank
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, SpriteReadError> {
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf)?;
+
+        Self::parse(&buf)
+    }
+
+    pub fn get(&self, id: impl AsRef<str>) -> Option<&SpriteData> {
+        self.sprites.get(id.as_ref())
+    }
+
+    /// Loads every sprite in `other` on top of this bank, replacing any sprites with the same id
+    ///
+    /// This is how the game applies mod and map sprite overrides.
+    pub fn merge(&mut self, other: &SpriteBank) {
+        for (id, sprite) in &other.sprites {
+            self.sprites.insert(id.clone(), sprite.clone());
+        }
+    }
+
+    /// Gets all the animations a sprite can play, including ones gained through `copy`
+    ///
+    /// Animations defined on the sprite itself take priority over copied ones.
+    pub fn animations(&self, id: impl AsRef<str>) -> Vec<&SpriteAnimation> {
+        let mut animations = Vec::new();
+        let mut visited = HashSet::new();
+        let mut next = self.get(id);
+
+        // Follow the copy chain, stopping if it loops
+        while let Some(sprite) = next
+            && visited.insert(sprite.id.as_str())
+        {
+            for anim in &sprite.animations {
+                if !animations
+                    .iter()
+                    .any(|a: &&SpriteAnimation| a.id == anim.id)
+                {
+                    animations.push(anim);
+                }
+            }
+
+            next = sprite.copy.as_ref().and_then(|c| self.get(c));
+        }
+
+        animations
+    }
+
+    /// Checks if a sprite can play an animation, including animations gained through `copy`
+    pub fn has_animation(&self, id: impl AsRef<str>, animation: impl AsRef<str>) -> bool {
+        let animation = animation.as_ref();
+        self.animations(id).iter().any(|a| a.id == animation)
+    }
+
+    /// Returns the animations used in a [Playback] that the sprite can't play
+    ///
+    /// Playback ghosts use the `player_playback` sprite in vanilla.
+    pub fn missing_playback_animations<'a>(
+        &self,
+        id: impl AsRef<str>,
+        playback: &'a Playback,
+    ) -> Vec<&'a str> {
+        let animations = self.animations(id);
+        let mut missing = Vec::new();
+
+        for frame in &playback.frames {
+            let name = frame.animation.as_str();
+            if !animations.iter().any(|a| a.id == name) && !missing.contains(&name) {
+                missing.push(name);
+            }
+        }
+
+        missing
+    }
+}
+
+impl SpriteData {
+    fn from_element(element: &BytesStart) -> Result<Self, SpriteReadError> {
+        let mut sprite = SpriteData {
+            id: String::from_utf8_lossy(element.name().as_ref()).into_owned(),
+            ..Default::default()
+        };
+
+        for (key, value) in read_attributes(element)? {
+            match key.as_str() {
+                "path" => sprite.path = Some(value),
+                "start" => sprite.start = Some(value),
+                "copy" => sprite.copy = Some(value),
+                _ => {
+                    sprite.attributes.insert(key, value);
+                }
+            }
+        }
+
+        Ok(sprite)
+    }
+
+    fn add_child(&mut self, element: &BytesStart) -> Result<(), SpriteReadError> {
+        let attributes = read_attributes(element)?;
+        let get = |name: &'static str| {
+            attributes
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+        };
+        let get_vec2 = || -> Result<[f32; 2], SpriteReadError> {
+            Ok([
+                get("x").map(|x| x.parse()).transpose()?.unwrap_or(0.0),
+                get("y").map(|y| y.parse()).transpose()?.unwrap_or(0.0),
+            ])
+        };
+
+        match element.name().as_ref() {
+            b"Justify" => self.justify = Some(get_vec2()?),
+            b"Origin" => self.origin = Some(get_vec2()?),
+            b"Position" => self.position = Some(get_vec2()?),
+            b"Center" => self.center = true,
+            name @ (b"Anim" | b"Loop") => self.animations.push(SpriteAnimation {
+                id: get("id").ok_or(SpriteReadError::MissingAttribute("id", self.id.clone()))?,
+                looping: name == b"Loop",
+                path: get("path").unwrap_or_default(),
+                delay: get("delay").map(|d| d.parse()).transpose()?,
+                frames: get("frames"),
+                goto: get("goto"),
+            }),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// The full path of an animation in the atlas
+    pub fn animation_path(&self, animation: &SpriteAnimation) -> String {
+        format!(
+            "{}{}",
+            self.path.as_deref().unwrap_or_default(),
+            animation.path
+        )
+    }
+}
+
+impl SpriteAnimation {
+    /// Parses [frames](Self::frames) into a list of frame indicies
+    ///
+    /// Supports ranges like `0-3` and repeats like `4*2`.<br>
+    /// Returns `None` if there isn't a frame list or it is invalid.
+    pub fn frame_indices(&self) -> Option<Vec<u32>> {
+        let frames = self.frames.as_ref()?;
+        let mut output = Vec::new();
+
+        for part in frames.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            if let Some((start, end)) = part.split_once('-') {
+                let start = start.trim().parse::<u32>().ok()?;
+                let end = end.trim().parse::<u32>().ok()?;
+
+                if start <= end {
+                    output.extend(start ..= end);
+                } else {
+                    output.extend((end ..= start).rev());
+                }
+            } else if let Some((frame, count)) = part.split_once('*') {
+                let frame = frame.trim().parse::<u32>().ok()?;
+                let count = count.trim().parse::<usize>().ok()?;
+                output.extend(std::iter::repeat_n(frame, count));
+            } else {
+                output.push(part.parse().ok()?);
+            }
+        }
+
+        Some(output)
+    }
+}
+
+fn read_attributes(element: &BytesStart) -> Result<Vec<(String, String)>, SpriteReadError> {
+    element
+        .attributes()
+        .map(|attr| {
+            let attr = attr.map_err(quick_xml::Error::from)?;
+            Ok((
+                String::from_utf8_lossy(attr.key.as_ref()).into_owned(),
+                attr.unescape_value()?.into_owned(),
+            ))
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum SpriteReadError {
+    XmlError(quick_xml::Error),
+    IoError(std::io::Error),
+    InvalidFloat(ParseFloatError),
+    /// A required attribute was missing, holds the attribute name and the id of the sprite
+    MissingAttribute(&'static str, String),
+}
+
+impl Display for SpriteReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpriteReadError::XmlError(e) => Display::fmt(e, f),
+            SpriteReadError::IoError(e) => Display::fmt(e, f),
+            SpriteReadError::InvalidFloat(e) => Display::fmt(e, f),
+            SpriteReadError::MissingAttribute(name, sprite) =>
+                write!(f, "Missing attribute \"{name}\" in sprite \"{sprite}\""),
+        }
+    }
+}
+
+impl Error for SpriteReadError {}
+
+impl From<quick_xml::Error> for SpriteReadError {
+    fn from(value: quick_xml::Error) -> Self {
+        SpriteReadError::XmlError(value)
+    }
+}
+
+impl From<std::io::Error> for SpriteReadError {
+    fn from(value: std::io::Error) -> Self {
+        SpriteReadError::IoError(value)
+    }
+}
+
+impl From<ParseFloatError> for SpriteReadError {
+    fn from(value: ParseFloatError) -> Self {
+        SpriteReadError::InvalidFloat(value)
+    }
+}
@@ -22,6 +22,11 @@ impl<'a> BinReader<'a> {
         self.cursor = 0;
     }
 
+    /// The amount of bytes left to read
+    pub fn remaining(&self) -> usize {
+        self.contents.len().saturating_sub(self.cursor)
+    }
+
     pub fn seek(&mut self, position: usize) {
         debug_assert!(position < self.contents.len());
         self.cursor = position;
@@ -125,7 +130,8 @@ impl<'a> BinReader<'a> {
     pub fn read_string(&mut self) -> Result<String, BinReadError> {
         let length = self.read_varint()?;
 
-        let mut buf = String::with_capacity(length as usize);
+        // Don't trust the length for preallocating, invalid data can claim strings of up to 4gb
+        let mut buf = String::with_capacity((length as usize).min(self.remaining()));
 
         for _ in 0 .. length {
             buf.push(self.read_char()?)
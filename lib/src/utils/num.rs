@@ -16,6 +16,18 @@ pub enum Integer {
     I64(i64),
 }
 
+impl Integer {
+    /// Gets the value as an `i64`, which can hold every variant
+    pub fn as_i64(&self) -> i64 {
+        match *self {
+            Integer::U8(b) => b as i64,
+            Integer::I16(s) => s as i64,
+            Integer::I32(i) => i as i64,
+            Integer::I64(l) => l,
+        }
+    }
+}
+
 impl Debug for Integer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -27,6 +39,13 @@ impl Debug for Integer {
     }
 }
 
+/// Integers are equal if they have the same value, no matter the variant
+impl PartialEq for Integer {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_i64() == other.as_i64()
+    }
+}
+
 impl Display for Integer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -233,6 +252,41 @@ pub enum Float {
     F64(f64),
 }
 
+impl Float {
+    /// Gets the value as an `f32`, this is the precision the map format uses
+    pub fn as_f32(&self) -> f32 {
+        match *self {
+            Float::U8(b) => b as f32,
+            Float::I16(s) => s as f32,
+            Float::I32(i) => i as f32,
+            Float::I64(l) => l as f32,
+            Float::F32(f) => f,
+            Float::F64(d) => d as f32,
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        match *self {
+            Float::U8(b) => b as f64,
+            Float::I16(s) => s as f64,
+            Float::I32(i) => i as f64,
+            Float::I64(l) => l as f64,
+            Float::F32(f) => f as f64,
+            Float::F64(d) => d,
+        }
+    }
+
+    fn as_integer(&self) -> Option<i64> {
+        match *self {
+            Float::U8(b) => Some(b as i64),
+            Float::I16(s) => Some(s as i64),
+            Float::I32(i) => Some(i as i64),
+            Float::I64(l) => Some(l),
+            Float::F32(_) | Float::F64(_) => None,
+        }
+    }
+}
+
 impl Debug for Float {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -246,6 +300,18 @@ impl Debug for Float {
     }
 }
 
+/// Floats are equal if they have the same value, no matter the variant.
+///
+/// Two integer variants are compared exactly, anything else is compared as an `f64`.
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.as_integer(), other.as_integer()) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.as_f64() == other.as_f64(),
+        }
+    }
+}
+
 impl Display for Float {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
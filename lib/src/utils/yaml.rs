@@ -200,7 +200,7 @@ pub trait FromYaml: Sized {
         }
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError>;
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError>;
 
     fn to_writer(&self, writer: &mut impl Write) -> Result<(), YamlWriteError> {
         let yaml = self.to_yaml()?;
@@ -242,7 +242,7 @@ impl<T: FromYaml> FromYaml for Vec<T> {
         yaml.try_as_vec()?.iter().map(T::parse_from_yaml).collect()
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(Yaml::Sequence(
             self.iter().map(T::to_yaml).collect::<Result<Vec<_>, _>>()?,
         ))
@@ -386,7 +386,7 @@ impl FromYaml for f64 {
         yaml.try_as_f64()
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(Yaml::float(*self))
     }
 }
@@ -396,7 +396,7 @@ impl FromYaml for f32 {
         f64::parse_from_yaml(yaml).map(|d| d as f32)
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(Yaml::float(*self as f64))
     }
 }
@@ -406,7 +406,7 @@ impl FromYaml for i64 {
         yaml.try_as_i64()
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(Yaml::int(*self))
     }
 }
@@ -416,7 +416,7 @@ impl FromYaml for String {
         Ok(yaml.try_as_str()?.to_owned())
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(Yaml::string(self.clone()))
     }
 }
@@ -426,7 +426,7 @@ impl FromYaml for bool {
         yaml.try_as_bool()
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(Yaml::bool(*self))
     }
 }
@@ -450,7 +450,7 @@ impl<K: FromYaml + Eq + Hash, V: FromYaml> FromYaml for HashMap<K, V> {
             .collect::<Result<HashMap<K, V>, _>>()
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(<saphyr::Yaml<'_> as YamlExt>::hash(
             self.iter()
                 .map(|(k, v)| {
@@ -490,7 +490,7 @@ impl<K: FromYaml + Ord, V: FromYaml> FromYaml for BTreeMap<K, V> {
             .collect::<Result<BTreeMap<K, V>, _>>()
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(YamlExt::hash(
             self.iter()
                 .map(|(k, v)| {
@@ -522,7 +522,7 @@ macro_rules! ints_yaml {
                     }
                 }
 
-                fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+                fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
                     // Allow this, compiler should optimize out any unneeded branches
                     #[allow(irrefutable_let_patterns)]
                     if let Ok(int) = (*self).try_into() {
@@ -545,7 +545,7 @@ impl FromYaml for Integer {
         yaml.try_as_i64().map(Integer::I64)
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(Yaml::Value(Scalar::Integer(match *self {
             Integer::U8(b) => b as i64,
             Integer::I16(s) => s as i64,
@@ -560,7 +560,7 @@ impl FromYaml for Float {
         yaml.try_as_float()
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(Yaml::Value(match *self {
             Float::U8(b) => Scalar::Integer(b as i64),
             Float::I16(s) => Scalar::Integer(s as i64),
@@ -603,7 +603,7 @@ impl FromYaml for YamlString {
         })
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
         Ok(Yaml::Value(Scalar::String(Cow::Owned(self.0.clone()))))
     }
 }
@@ -614,7 +614,7 @@ impl<T: FromYaml> FromYaml for Vec<Option<T>> {
 
         let mut output = Vec::new();
         for y in &yaml {
-            output.extend(Vec::<Option<T>>::parse_from_yaml(y)?.into_iter());
+            output.extend(Vec::<Option<T>>::parse_from_yaml(y)?);
         }
 
         Ok(output)
@@ -626,7 +626,7 @@ impl<T: FromYaml> FromYaml for Vec<Option<T>> {
 
         let mut output = Vec::new();
         for y in &yaml {
-            output.extend(Vec::<Option<T>>::parse_from_yaml(y)?.into_iter());
+            output.extend(Vec::<Option<T>>::parse_from_yaml(y)?);
         }
 
         Ok(output)
@@ -641,17 +641,22 @@ impl<T: FromYaml> FromYaml for Vec<Option<T>> {
                 if let Yaml::Value(Scalar::Null) = y {
                     None
                 } else {
-                    Some(T::parse_from_yaml(yaml))
+                    Some(T::parse_from_yaml(y))
                 }
             })
             .map(Option::transpose)
             .collect()
     }
 
-    fn to_yaml(&self) -> Result<Yaml, YamlWriteError> {
+    fn to_yaml(&self) -> Result<Yaml<'_>, YamlWriteError> {
+        // Null elements are kept since the position of elements can be meaningful
+        // e.g. the `Modes` list in a map's meta.yaml is indexed by side
         Ok(Yaml::Sequence(
             self.iter()
-                .filter_map(|v| v.as_ref().map(T::to_yaml))
+                .map(|v| match v {
+                    Some(v) => v.to_yaml(),
+                    None => Ok(Yaml::Value(Scalar::Null)),
+                })
                 .collect::<Result<Vec<_>, _>>()?,
         ))
     }
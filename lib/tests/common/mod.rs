@@ -0,0 +1,213 @@
+//! Helpers for building maps, mods and playbacks in tests
+#![allow(dead_code)]
+
+use std::io::{Cursor, Write};
+
+use celeste_rs::{
+    maps::{
+        LookupTable,
+        MapAttribute,
+        MapManager,
+        RawMap,
+        RawMapElement,
+        ResolvableString,
+        var_types::EncodedVar,
+        writer::MapWriter,
+    },
+    mods::{ModManager, ZipBuf},
+    playbacks::{FRAME_TIME, PlaybackFacing, PlaybackFrame},
+};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+pub fn el(
+    name: &str,
+    attributes: Vec<(&str, EncodedVar)>,
+    children: Vec<RawMapElement>,
+) -> RawMapElement {
+    RawMapElement {
+        name: ResolvableString::String(name.to_owned()),
+        attributes: attributes
+            .into_iter()
+            .map(|(name, value)| {
+                MapAttribute::new(ResolvableString::String(name.to_owned()), value)
+            })
+            .collect(),
+        children,
+    }
+}
+
+pub fn s(value: &str) -> EncodedVar {
+    EncodedVar::String(value.to_owned())
+}
+
+pub fn i(value: i32) -> EncodedVar {
+    EncodedVar::Int(value)
+}
+
+pub fn byte(value: u8) -> EncodedVar {
+    EncodedVar::Byte(value)
+}
+
+pub fn f(value: f32) -> EncodedVar {
+    EncodedVar::Float(value)
+}
+
+pub fn b(value: bool) -> EncodedVar {
+    EncodedVar::Bool(value)
+}
+
+pub fn nodes(count: usize) -> Vec<RawMapElement> {
+    (0 .. count)
+        .map(|n| {
+            el(
+                "node",
+                vec![("x", f(n as f32 * 8.0)), ("y", f(16.0))],
+                vec![],
+            )
+        })
+        .collect()
+}
+
+pub fn entity(
+    name: &str,
+    mut attributes: Vec<(&str, EncodedVar)>,
+    node_count: usize,
+) -> RawMapElement {
+    let mut all = vec![
+        ("id", i(1)),
+        ("x", f(8.0)),
+        ("y", f(16.0)),
+        ("originX", f(0.0)),
+        ("originY", f(0.0)),
+    ];
+    all.append(&mut attributes);
+    el(name, all, nodes(node_count))
+}
+
+pub fn trigger(
+    name: &str,
+    mut attributes: Vec<(&str, EncodedVar)>,
+    node_count: usize,
+) -> RawMapElement {
+    let mut all = vec![
+        ("id", i(1)),
+        ("x", f(8.0)),
+        ("y", f(16.0)),
+        ("width", i(16)),
+        ("height", i(16)),
+    ];
+    all.append(&mut attributes);
+    el(name, all, nodes(node_count))
+}
+
+pub fn effect(name: &str, attributes: Vec<(&str, EncodedVar)>) -> RawMapElement {
+    el(name, attributes, vec![])
+}
+
+/// A 320x184 room with empty tiles
+pub fn level(
+    name: &str,
+    (x, y): (i32, i32),
+    entities: Vec<RawMapElement>,
+    triggers: Vec<RawMapElement>,
+) -> RawMapElement {
+    el(
+        "level",
+        vec![
+            ("name", s(name)),
+            ("width", i(320)),
+            ("height", i(184)),
+            ("x", i(x)),
+            ("y", i(y)),
+            ("c", i(0)),
+        ],
+        vec![
+            el("solids", vec![("innerText", s("000\n0\n"))], vec![]),
+            el("bg", vec![("innerText", s(""))], vec![]),
+            el("entities", vec![], entities),
+            el("triggers", vec![], triggers),
+        ],
+    )
+}
+
+/// A map root with the given rooms and stylegrounds
+pub fn map(
+    levels: Vec<RawMapElement>,
+    backgrounds: Vec<RawMapElement>,
+    foregrounds: Vec<RawMapElement>,
+) -> RawMapElement {
+    el("Map", vec![], vec![
+        el("Filler", vec![], vec![]),
+        el("levels", vec![], levels),
+        el("Style", vec![], vec![
+            el("Backgrounds", vec![], backgrounds),
+            el("Foregrounds", vec![], foregrounds),
+        ]),
+    ])
+}
+
+/// Encodes a map root into the binary map format
+pub fn map_bytes(root: RawMapElement) -> Vec<u8> {
+    let mut map = RawMap {
+        name: "test".to_owned(),
+        lookup_table: LookupTable::new(),
+        root_element: root,
+    };
+    map.unresolve_strings();
+
+    let mut bytes = Vec::new();
+    let mut writer = MapWriter::new(&mut bytes);
+    writer.write_string("CELESTE MAP").unwrap();
+    writer.write_string(&map.name).unwrap();
+    writer.write_lookup_table(&map.lookup_table).unwrap();
+    writer.write_element(&map.root_element).unwrap();
+
+    bytes
+}
+
+/// Reads a map root with the default parsers registered
+pub fn manager(root: RawMapElement) -> MapManager {
+    let mut manager = MapManager::new(&map_bytes(root)[..]).unwrap();
+    manager.default_parsers();
+    manager
+}
+
+/// Reads a zipped mod called `MyMod` containing `files` along with its `everest.yaml`
+pub fn mod_manager(files: Vec<(&str, Vec<u8>)>) -> ModManager<ZipBuf<Cursor<Vec<u8>>>> {
+    named_mod("MyMod", files)
+}
+
+/// Reads a zipped mod containing `files` along with an `everest.yaml` for `name`
+pub fn named_mod(name: &str, files: Vec<(&str, Vec<u8>)>) -> ModManager<ZipBuf<Cursor<Vec<u8>>>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let everest = format!("- Name: {name}\n  Version: 1.0.0\n").into_bytes();
+
+    for (path, contents) in [("everest.yaml", everest)].into_iter().chain(files) {
+        zip.start_file(path, SimpleFileOptions::default()).unwrap();
+        zip.write_all(&contents).unwrap();
+    }
+
+    let bytes = zip.finish().unwrap().into_inner();
+    ModManager::from_reader(Cursor::new(bytes)).unwrap()
+}
+
+/// A playback frame facing right, the player is on the ground unless they are dashing
+pub fn frame(
+    index: usize,
+    position: [f32; 2],
+    animation: &str,
+    dash: [f32; 2],
+) -> PlaybackFrame {
+    PlaybackFrame {
+        position,
+        timestamp: index as f32 * FRAME_TIME,
+        animation: animation.to_owned(),
+        facing: PlaybackFacing::Right,
+        on_ground: dash == [0.0, 0.0],
+        hair_color: [0xAC, 0x32, 0x32],
+        depth: 0,
+        sounds: 0,
+        scale: [1.0, 1.0],
+        dash_direction: dash,
+    }
+}
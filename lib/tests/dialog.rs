@@ -0,0 +1,218 @@
+use celeste_rs::dialog::{Dialog, DialogKeySource, Localization};
+
+mod common;
+use common::*;
+
+/// The start of the vanilla `English.txt`
+const ENGLISH_HEADER: &str = "\u{feff}# NOTES:
+# The # Symbol at the start of a line counts as a Comment. To include in dialog, use a \\#
+# The . Symbol will cause a pause unless escaped with \\. (ex: Mr. Oshiro has a pause, Mr\\. \
+                              Oshiro does not)
+# Newlines automatically create a Page Break, adding an {n} command will only create a Line Break
+# LANGUAGE=english
+# ICON=Icons/english.png
+# ORDER=10
+# SPLIT_REGEX=(\\s|\\{|\\})
+# COMMAS=,
+# PERIODS=.!?
+# FONT=Renogare,64
+
+# ================================== Main Menu ==================================
+	MENU_BEGIN= 			Climb
+	MENU_PLAYTIME=			Playtime
+	AREA_1= 				Forsaken City
+	CH0_INTRO=
+		[MADELINE left normal]
+		Hello there
+";
+
+#[test]
+fn header_properties_are_read_from_comments() {
+    let dialog = Dialog::parse(ENGLISH_HEADER);
+
+    assert_eq!(dialog.language(), Some("english"));
+    assert_eq!(dialog.properties["ICON"], "Icons/english.png");
+    assert_eq!(dialog.properties["ORDER"], "10");
+    assert_eq!(dialog.properties["SPLIT_REGEX"], "(\\s|\\{|\\})");
+    assert_eq!(dialog.properties["COMMAS"], ",");
+    assert_eq!(dialog.properties["PERIODS"], ".!?");
+    assert_eq!(dialog.properties["FONT"], "Renogare,64");
+    assert_eq!(dialog.properties.len(), 7);
+
+    assert_eq!(dialog.len(), 4);
+    assert_eq!(dialog.get("menu_begin"), Some("Climb"));
+    assert_eq!(dialog.get_clean("AREA_1").as_deref(), Some("Forsaken City"));
+    assert_eq!(
+        dialog.get("CH0_INTRO"),
+        Some("{portrait MADELINE left normal}\nHello there")
+    );
+}
+
+#[test]
+fn header_properties_without_comment_prefix() {
+    let dialog = Dialog::parse("LANGUAGE=french\nICON=Icons/french.png\nKEY=value");
+
+    assert_eq!(dialog.language(), Some("french"));
+    assert_eq!(dialog.properties["ICON"], "Icons/french.png");
+    assert_eq!(dialog.get("KEY"), Some("value"));
+    assert!(!dialog.contains_key("LANGUAGE"));
+}
+
+#[test]
+fn mod_dialog_is_merged_over_vanilla() {
+    let mut vanilla = Localization::new();
+    vanilla.add_dialog("English", Dialog::parse(ENGLISH_HEADER));
+
+    let mut modded = Localization::new();
+    modded.add_dialog(
+        "English",
+        Dialog::parse("# FONT=Renogare,32\nmenu_begin= Start\nMYMOD_LAKE= Lake"),
+    );
+    modded.add_dialog(
+        "French",
+        Dialog::parse("# LANGUAGE=french\nMYMOD_LAKE= Lac"),
+    );
+    vanilla.merge(&modded);
+
+    let english = vanilla.language("English").unwrap();
+    // Keys are replaced regardless of their case
+    assert_eq!(english.get("MENU_BEGIN"), Some("Start"));
+    assert_eq!(english.get("MENU_PLAYTIME"), Some("Playtime"));
+    assert_eq!(english.get("MYMOD_LAKE"), Some("Lake"));
+    assert_eq!(english.len(), 5);
+    assert_eq!(english.properties["FONT"], "Renogare,32");
+    assert_eq!(english.language(), Some("english"));
+
+    assert_eq!(vanilla.get("French", "mymod_lake"), Some("Lac"));
+}
+
+#[test]
+fn references_are_resolved() {
+    let dialog = Dialog::parse(
+        "NAME= {#ff0000}Madeline{#}\nGREETING= Hello {+NAME}, {+ MISSING}\nLOOP_A= \
+         {+LOOP_B}\nLOOP_B= {+LOOP_A}",
+    );
+
+    assert_eq!(
+        dialog.get_resolved("GREETING").as_deref(),
+        Some("Hello {#ff0000}Madeline{#}, {+ MISSING}")
+    );
+    assert_eq!(
+        dialog.get_clean("GREETING").as_deref(),
+        Some("Hello Madeline, ")
+    );
+    assert_eq!(dialog.get("GREETING"), Some("Hello {+NAME}, {+ MISSING}"));
+
+    // Loops stop instead of recursing forever
+    assert!(
+        dialog
+            .get_resolved("LOOP_A")
+            .unwrap()
+            .starts_with("{+LOOP_")
+    );
+    assert_eq!(dialog.get_resolved("UNKNOWN"), None);
+}
+
+fn lake_map() -> Vec<u8> {
+    map_bytes(map(
+        vec![level("a-00", (0, 0), vec![], vec![trigger(
+            "minitextboxTrigger",
+            vec![
+                ("mode", s("OnPlayerEnter")),
+                ("dialog_id", s("MYMOD_LAKE_HINT")),
+                ("only_once", b(true)),
+                ("death_count", i(-1)),
+            ],
+            0,
+        )])],
+        vec![],
+        vec![],
+    ))
+}
+
+const LAKE_META: &str = "\
+Modes:
+  - Checkpoints:
+      - Level: b-00
+        Name: MYMOD_LAKE_SHORE
+        Inventory: Default
+        AudioState:
+          Music: event:/music/lvl1/main
+          Ambience: event:/env/amb/01_main
+        Flags: []
+LoadingVignetteText:
+  Dialog: MYMOD_LAKE_INTRO
+";
+
+#[test]
+fn mod_dialog_files() {
+    let mut manager = mod_manager(vec![
+        ("Dialog/English.txt", b"MYMOD_LAKE= Lake".to_vec()),
+        ("Dialog/French.txt", b"MYMOD_LAKE= Lac".to_vec()),
+        // Only files directly in the mod's Dialog folder are loaded
+        ("Dialog/Old/English.txt", b"MYMOD_LAKE= Old Lake".to_vec()),
+        ("Extra/Dialog/German.txt", b"MYMOD_LAKE= See".to_vec()),
+    ]);
+    let localization = manager.dialog().unwrap();
+
+    let mut languages = localization.languages.keys().collect::<Vec<_>>();
+    languages.sort();
+    assert_eq!(languages, ["English", "French"]);
+    assert_eq!(localization.get("English", "MYMOD_LAKE"), Some("Lake"));
+}
+
+#[test]
+fn missing_dialog_keys() {
+    let manager = mod_manager(vec![
+        ("Maps/MyMod/1-Lake.bin", lake_map()),
+        ("Maps/MyMod/1-Lake.meta.yaml", LAKE_META.as_bytes().to_vec()),
+    ]);
+    let references = manager.collection().dialog_references();
+
+    let keys = references
+        .iter()
+        .map(|r| (&r.key[..], &r.source))
+        .collect::<Vec<_>>();
+    assert_eq!(keys, [
+        ("MyMod_1_Lake", &DialogKeySource::ChapterName),
+        ("MyMod", &DialogKeySource::LevelSet),
+        ("MYMOD_LAKE_SHORE", &DialogKeySource::CheckpointName {
+            level: "b-00".to_owned()
+        }),
+        ("MYMOD_LAKE_INTRO", &DialogKeySource::LoadingVignette),
+        ("MYMOD_LAKE_HINT", &DialogKeySource::MiniTextbox {
+            room: "a-00".to_owned()
+        }),
+    ]);
+    assert!(references.iter().all(|r| r.sid == "MyMod/1-Lake"));
+
+    let dialog = Dialog::parse("MyMod= My Mod\nMyMod_1_Lake= Lake\nmymod_lake_hint= Jump!");
+    let missing = dialog
+        .missing_keys(&references)
+        .into_iter()
+        .map(|r| &r.key[..])
+        .collect::<Vec<_>>();
+    assert_eq!(missing, ["MYMOD_LAKE_SHORE", "MYMOD_LAKE_INTRO"]);
+}
+
+#[test]
+fn level_set_dialog_keys() {
+    let manager = mod_manager(vec![
+        ("Maps/MyMod/Extra Maps/2-Cave.bin", lake_map()),
+        ("Maps/1-Root.bin", lake_map()),
+    ]);
+
+    let level_sets = manager
+        .collection()
+        .dialog_references()
+        .into_iter()
+        .filter(|r| r.source == DialogKeySource::LevelSet)
+        .map(|r| (r.sid, r.key))
+        .collect::<Vec<_>>();
+
+    // Maps directly in the Maps folder have no level set
+    assert_eq!(level_sets, [(
+        "MyMod/Extra Maps/2-Cave".to_owned(),
+        "MyMod_Extra_Maps".to_owned()
+    )]);
+}
@@ -0,0 +1,195 @@
+use celeste_rs::mods::dll::{BufferedDLL, CustomEntityType, Hook, HookKind};
+use dotnetdll::prelude::*;
+
+/// Builds a mod dll with a tracked custom entity, an untracked custom trigger and a plain tracked type
+fn mod_dll() -> Vec<u8> {
+    let mut res = Resolution::new(Module::new("TestMod.dll"));
+    res.assembly = Some(Assembly::new("TestMod"));
+
+    let celeste = res.push_assembly_reference(ExternalAssemblyReference::new("Celeste"));
+
+    let entity = res.push_type_reference(type_ref! { Celeste.Entity in #celeste });
+    let trigger = res.push_type_reference(type_ref! { Celeste.Trigger in #celeste });
+
+    let tracked: MethodType =
+        BaseType::class(res.push_type_reference(type_ref! { Monocle.Tracked in #celeste })).into();
+    let tracked_ctor = res.push_method_reference(method_ref! { void #tracked::.ctor(bool) });
+
+    let custom_entity: MethodType =
+        BaseType::class(res.push_type_reference(
+            type_ref! { Celeste.Mod.Entities.CustomEntityAttribute in #celeste },
+        ))
+        .into();
+    let custom_entity_ctor =
+        res.push_method_reference(method_ref! { void #custom_entity::.ctor(string[]) });
+
+    let tracked_attr = || {
+        Attribute::new(tracked_ctor.into(), CustomAttributeData {
+            constructor_args: vec![FixedArg::Boolean(false)],
+            named_args: vec![],
+        })
+    };
+    let custom_entity_attr = |names: &[&'static str]| {
+        Attribute::new(custom_entity_ctor.into(), CustomAttributeData {
+            constructor_args: vec![FixedArg::Array(Some(
+                names.iter().map(|n| FixedArg::String(Some(n))).collect(),
+            ))],
+            named_args: vec![],
+        })
+    };
+
+    let spinner = res.push_type_definition(TypeDefinition::new(Some("TestMod".into()), "Spinner"));
+    res[spinner].set_extends(entity);
+    res[spinner].attributes.extend([
+        custom_entity_attr(&["TestMod/Spinner", "TestMod/SpinnerAlt = LoadAlt"]),
+        tracked_attr(),
+    ]);
+
+    let music =
+        res.push_type_definition(TypeDefinition::new(Some("TestMod".into()), "MusicTrigger"));
+    res[music].set_extends(trigger);
+    res[music]
+        .attributes
+        .push(custom_entity_attr(&["TestMod/MusicTrigger"]));
+
+    let component =
+        res.push_type_definition(TypeDefinition::new(Some("TestMod".into()), "Component"));
+    res[component].attributes.push(tracked_attr());
+
+    res.write(WriteOptions {
+        is_32_bit: false,
+        is_executable: false,
+    })
+    .unwrap()
+}
+
+#[test]
+fn custom_entities_and_tracked_types() {
+    let dll = BufferedDLL::new(mod_dll()).unwrap();
+
+    assert_eq!(dll.custom_entities(), vec![
+        CustomEntityType {
+            type_name: "TestMod.Spinner".to_owned(),
+            names: vec![
+                "TestMod/Spinner".to_owned(),
+                "TestMod/SpinnerAlt".to_owned()
+            ],
+            is_trigger: false,
+            tracked: true,
+        },
+        CustomEntityType {
+            type_name: "TestMod.MusicTrigger".to_owned(),
+            names: vec!["TestMod/MusicTrigger".to_owned()],
+            is_trigger: true,
+            tracked: false,
+        },
+    ]);
+
+    assert_eq!(dll.tracked_types(), vec![
+        "TestMod.Spinner".to_owned(),
+        "TestMod.Component".to_owned()
+    ]);
+    assert_eq!(dll.entity_names(), vec![
+        "TestMod/Spinner".to_owned(),
+        "TestMod/SpinnerAlt".to_owned()
+    ]);
+    assert_eq!(dll.trigger_names(), vec!["TestMod/MusicTrigger".to_owned()]);
+}
+
+/// Builds a mod dll with an `EverestModule` that adds `On` and `IL` hooks when loaded
+fn hook_dll() -> Vec<u8> {
+    let mut res = Resolution::new(Module::new("TestMod.dll"));
+    res.assembly = Some(Assembly::new("TestMod"));
+
+    let celeste = res.push_assembly_reference(ExternalAssemblyReference::new("Celeste"));
+    let mmhook = res.push_assembly_reference(ExternalAssemblyReference::new("MMHOOK_Celeste"));
+
+    let everest_module =
+        res.push_type_reference(type_ref! { Celeste.Mod.EverestModule in #celeste });
+
+    let on_player: MethodType =
+        BaseType::class(res.push_type_reference(type_ref! { On.Celeste.Player in #mmhook })).into();
+    let il_player: MethodType =
+        BaseType::class(res.push_type_reference(type_ref! { IL.Celeste.Player in #mmhook })).into();
+    let player: MethodType =
+        BaseType::class(res.push_type_reference(type_ref! { Celeste.Player in #celeste })).into();
+
+    let on_update =
+        res.push_method_reference(method_ref! { static void #on_player::add_Update(object) });
+    let il_jump =
+        res.push_method_reference(method_ref! { static void #il_player::add_Jump(object) });
+    // Not a hook, it isn't on an On or IL type
+    let add_item = res.push_method_reference(method_ref! { static void #player::add_Item(object) });
+
+    let module =
+        res.push_type_definition(TypeDefinition::new(Some("TestMod".into()), "TestModule"));
+    res[module].set_extends(everest_module);
+    res.push_method(
+        module,
+        Method::new(
+            Accessibility::Public,
+            msig! { void () },
+            "Load",
+            Some(body::Method::new(asm! {
+                LoadNull;
+                call on_update;
+                LoadNull;
+                call il_jump;
+                LoadNull;
+                call on_update;
+                LoadNull;
+                call add_item;
+                Return;
+            })),
+        ),
+    );
+
+    // Inherits from EverestModule through the module above
+    let derived =
+        res.push_type_definition(TypeDefinition::new(Some("TestMod".into()), "DerivedModule"));
+    res[derived].set_extends(module);
+
+    res.push_type_definition(TypeDefinition::new(Some("TestMod".into()), "Helper"));
+
+    res.write(WriteOptions {
+        is_32_bit: false,
+        is_executable: false,
+    })
+    .unwrap()
+}
+
+#[test]
+fn everest_modules() {
+    let dll = BufferedDLL::new(hook_dll()).unwrap();
+
+    assert_eq!(dll.everest_modules(), vec![
+        "TestMod.TestModule".to_owned(),
+        "TestMod.DerivedModule".to_owned()
+    ]);
+    assert!(
+        BufferedDLL::new(mod_dll())
+            .unwrap()
+            .everest_modules()
+            .is_empty()
+    );
+}
+
+#[test]
+fn hooks() {
+    let dll = BufferedDLL::new(hook_dll()).unwrap();
+
+    // Hooks added more than once are only listed once
+    assert_eq!(dll.hooks(), vec![
+        Hook {
+            kind: HookKind::On,
+            target_type: "Celeste.Player".to_owned(),
+            method: "Update".to_owned(),
+        },
+        Hook {
+            kind: HookKind::IL,
+            target_type: "Celeste.Player".to_owned(),
+            method: "Jump".to_owned(),
+        },
+    ]);
+    assert!(BufferedDLL::new(mod_dll()).unwrap().hooks().is_empty());
+}
@@ -0,0 +1,109 @@
+use std::any::Any;
+
+use celeste_rs::maps::{
+    DynMapElement,
+    ErasedMapElement,
+    RawMapElement,
+    elements::{
+        MapRoot,
+        entities::{DynEntity, MapEntity, vanilla_entities::Strawberry},
+    },
+};
+use celeste_rs::utils::num::Float;
+
+mod common;
+use common::*;
+
+fn strawberry(x: celeste_rs::maps::var_types::EncodedVar, checkpoint: u8) -> RawMapElement {
+    let mut raw = entity(
+        "strawberry",
+        vec![("winged", b(false)), ("checkpointID", byte(checkpoint))],
+        1,
+    );
+    raw.set_attribute("x", x);
+    raw
+}
+
+fn test_map() -> RawMapElement {
+    map(
+        vec![level("a-00", (0, 0), vec![strawberry(f(8.0), 0)], vec![
+            trigger("MyMod/Trigger", vec![], 0),
+        ])],
+        vec![effect("stars", vec![])],
+        vec![],
+    )
+}
+
+fn parse(raw: &RawMapElement) -> DynEntity {
+    manager(map(vec![], vec![], vec![]))
+        .parse_entity(raw)
+        .unwrap()
+}
+
+#[test]
+fn map_roots() {
+    let root = manager(test_map()).parse_map().unwrap();
+    let mut clone = root.clone();
+    assert_eq!(clone, root);
+
+    // The clone doesn't share its dynamic elements
+    let entities = &mut clone.levels.levels[0].entities.as_mut().unwrap().entities;
+    entities[0].set_field("winged", Some(b(true))).unwrap();
+    assert_ne!(clone, root);
+
+    let clone = root.clone();
+    let triggers = &root.levels.levels[0].triggers.as_ref().unwrap().triggers;
+    assert!((triggers[0].as_ref() as &dyn Any).is::<RawMapElement>());
+    assert_eq!(
+        clone.levels.levels[0].triggers.as_ref().unwrap().triggers,
+        *triggers
+    );
+
+    // Comparing through the erased element
+    let erased = &root as &dyn ErasedMapElement;
+    assert!(erased.eq_dyn(&clone));
+    assert!(!erased.eq_dyn(&MapRoot::clone(&root).levels));
+    let boxed = erased.clone_dyn();
+    assert!((boxed.as_ref() as &dyn Any).is::<MapRoot>());
+    assert!(boxed.eq_dyn(erased));
+}
+
+#[test]
+fn dyn_entities() {
+    let entity = parse(&strawberry(f(8.0), 0));
+    let clone = entity.clone();
+    assert!((clone.as_ref() as &dyn Any).is::<MapEntity<Strawberry>>());
+    assert!(clone.eq(&entity));
+
+    // Numbers compare by value, not by how they are stored
+    assert!(parse(&strawberry(i(8), 0)).eq(&entity));
+    assert!(parse(&strawberry(byte(8), 0)).eq(&entity));
+    let mut wide = strawberry(f(8.0), 0);
+    wide.set_attribute("checkpointID", i(0));
+    assert!(parse(&wide).eq(&entity));
+
+    assert!(!parse(&strawberry(f(8.5), 0)).eq(&entity));
+    assert!(!parse(&strawberry(f(8.0), 1)).eq(&entity));
+
+    // The same data in a different type of element isn't equal
+    let raw = strawberry(f(8.0), 0);
+    let unparsed = Box::new(raw.clone()) as DynEntity;
+    assert!(!unparsed.eq(&entity));
+    assert!(unparsed.clone().eq(&unparsed));
+
+    let element = Box::new(raw) as DynMapElement;
+    assert!(element.clone().eq(&element));
+    assert!(!element.eq_dyn(entity.as_ref()));
+}
+
+#[test]
+fn floats() {
+    assert_eq!(Float::U8(4), Float::I64(4));
+    assert_eq!(Float::I32(16), Float::F32(16.0));
+    assert_eq!(Float::F32(0.5), Float::F64(0.5));
+
+    // 16_777_217 can't be represented by an f32, so it would round down to 16_777_216
+    assert_ne!(Float::I32(16_777_217), Float::I32(16_777_216));
+    assert_ne!(Float::I64(i64::MAX), Float::I64(i64::MAX - 1));
+    assert_ne!(Float::F32(0.1), Float::F64(0.1));
+}
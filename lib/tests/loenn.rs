@@ -0,0 +1,364 @@
+use celeste_rs::{
+    maps::{
+        schema::{AttributeKind, EntitySchema},
+        var_types::EncodedVar,
+    },
+    mods::loenn::{LoennError, schemas_from_lua},
+};
+
+mod common;
+use common::*;
+
+fn schema(src: &str) -> EntitySchema {
+    let mut schemas = schemas_from_lua(src, false).unwrap();
+    assert_eq!(schemas.len(), 1);
+    schemas.remove(0)
+}
+
+/// Reads a lua string literal through the default of a placement's attribute
+fn string(literal: &str) -> String {
+    let schema = schema(&format!(
+        "return {{ name = \"TestHelper/Text\", \
+         placements = {{ name = \"text\", data = {{ text = {literal} }} }} }}"
+    ));
+
+    match &schema.get_attribute("text").unwrap().default {
+        Some(EncodedVar::String(s)) => s.clone(),
+        other => panic!("expected a string, found {other:?}"),
+    }
+}
+
+fn attribute_names(schema: &EntitySchema) -> Vec<&str> {
+    schema.attributes.iter().map(|a| a.name.as_str()).collect()
+}
+
+#[test]
+fn long_strings() {
+    assert_eq!(string("[[a \"b\" 'c']]"), "a \"b\" 'c'");
+    assert_eq!(string("[==[a ]] ]=] b]==]"), "a ]] ]=] b");
+    // The first newline of a long string is skipped
+    assert_eq!(string("[[\nline one\nline two]]"), "line one\nline two");
+}
+
+#[test]
+fn comments() {
+    let schema = schema(
+        "-- a line comment with \"quotes\"
+        --[[ a long
+        comment with name = \"Wrong\" ]]
+        --[==[ a ]] level 2 comment ]==]
+        return { name = \"TestHelper/Commented\" } -- name = \"AlsoWrong\"",
+    );
+
+    assert_eq!(schema.name, "TestHelper/Commented");
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(string(r#""a\nb\tc\\d\"e""#), "a\nb\tc\\d\"e");
+    assert_eq!(string(r"'it\'s'"), "it's");
+    assert_eq!(string(r#""\65\066\x43\u{44}""#), "ABCD");
+    assert_eq!(string("\"a\\z\n      b\""), "ab");
+    assert_eq!(string("\"a\\\nb\""), "a\nb");
+}
+
+#[test]
+fn single_handler() {
+    let schema = schema(
+        r#"
+        local spinner = {}
+
+        spinner.name = "TestHelper/Spinner"
+        spinner.depth = -8500
+        spinner.placements = {
+            name = "spinner",
+            data = {
+                speed = 1.5,
+                count = 3,
+                color = "Blue",
+                attached = false,
+                width = 8,
+            }
+        }
+
+        function spinner.sprite(room, entity)
+            return {}
+        end
+
+        return spinner
+        "#,
+    );
+
+    assert_eq!(schema.name, "TestHelper/Spinner");
+    assert!(!schema.is_trigger);
+    // Common attributes like width aren't part of the schema
+    assert_eq!(attribute_names(&schema), [
+        "speed", "count", "color", "attached"
+    ]);
+    assert_eq!(
+        schema.get_attribute("speed").unwrap().default,
+        Some(EncodedVar::Float(1.5))
+    );
+    assert_eq!(
+        schema.get_attribute("count").unwrap().default,
+        Some(EncodedVar::Int(3))
+    );
+    assert_eq!(
+        schema.get_attribute("color").unwrap().default,
+        Some(EncodedVar::String("Blue".to_owned()))
+    );
+    assert_eq!(
+        schema.get_attribute("attached").unwrap().default,
+        Some(EncodedVar::Bool(false))
+    );
+    assert_eq!((schema.min_nodes, schema.max_nodes), (0, Some(0)));
+}
+
+#[test]
+fn list_of_handlers() {
+    let schemas = schemas_from_lua(
+        r#"
+        local red = {
+            name = "TestHelper/RedBooster",
+            placements = { name = "red", data = { red = true } },
+        }
+        local green = {
+            name = "TestHelper/GreenBooster",
+            placements = { name = "green", data = { green = 1 } },
+        }
+
+        return { red, green }
+        "#,
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(
+        schemas.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+        ["TestHelper/RedBooster", "TestHelper/GreenBooster"]
+    );
+    assert!(schemas.iter().all(|s| s.is_trigger));
+    assert_eq!(attribute_names(&schemas[0]), ["red"]);
+    assert_eq!(attribute_names(&schemas[1]), ["green"]);
+}
+
+#[test]
+fn handlers_without_return() {
+    let src = r#"
+        local utils = require("utils")
+        local yellow = { name = "TestHelper/YellowBooster" }
+        local blue = {}
+        blue.name = "TestHelper/BlueBooster"
+        local purple = { name = "TestHelper/PurpleBooster" }
+        local orange = { name = "TestHelper/OrangeBooster" }
+        local speed = 4
+        "#;
+
+    // Handlers keep the order they're declared in every time
+    for _ in 0 .. 8 {
+        let schemas = schemas_from_lua(src, false).unwrap();
+        assert_eq!(
+            schemas.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            [
+                "TestHelper/YellowBooster",
+                "TestHelper/BlueBooster",
+                "TestHelper/PurpleBooster",
+                "TestHelper/OrangeBooster"
+            ]
+        );
+    }
+}
+
+#[test]
+fn placement_list() {
+    let schema = schema(
+        r#"
+        return {
+            name = "TestHelper/Block",
+            placements = {
+                { name = "normal", data = { solid = true, speed = 2 } },
+                { name = "fast", data = { solid = false, speed = 6, sound = "event:/fast" } },
+                { name = "computed", data = makeData() },
+            },
+        }
+        "#,
+    );
+
+    // The first placement to set an attribute gives its default
+    assert_eq!(attribute_names(&schema), ["solid", "speed", "sound"]);
+    assert_eq!(
+        schema.get_attribute("solid").unwrap().default,
+        Some(EncodedVar::Bool(true))
+    );
+    assert_eq!(
+        schema.get_attribute("speed").unwrap().default,
+        Some(EncodedVar::Int(2))
+    );
+}
+
+#[test]
+fn node_limits() {
+    let limits = |limits: &str| {
+        let schema = schema(&format!(
+            "return {{ name = \"TestHelper/Path\", nodeLimits = {limits} }}"
+        ));
+        (schema.min_nodes, schema.max_nodes)
+    };
+
+    assert_eq!(limits("{ 1, 1 }"), (1, Some(1)));
+    assert_eq!(limits("{ 2, -1 }"), (2, None));
+    assert_eq!(limits("{ 0 }"), (0, None));
+    // Computed limits could be anything
+    assert_eq!(limits("function() return { 1, 2 } end"), (0, None));
+}
+
+#[test]
+fn field_information() {
+    let schema = schema(
+        r#"
+        local colors = { "Red", "Green", "Blue" }
+
+        return {
+            name = "TestHelper/Light",
+            fieldInformation = {
+                color = { options = colors, editable = false },
+                mode = { fieldType = "integer", options = { ["Off"] = 0, ["On"] = 1 } },
+                size = { fieldType = "number", options = { { "Small", 8 }, { "Large", 16 } } },
+                missing = { fieldType = "integer" },
+            },
+            placements = {
+                name = "light",
+                data = { color = "Red", mode = 0, size = 8 },
+            },
+        }
+        "#,
+    );
+
+    let color = schema.get_attribute("color").unwrap();
+    assert_eq!(color.kind, AttributeKind::String);
+    assert!(!color.editable);
+    assert_eq!(color.options, [
+        EncodedVar::String("Red".to_owned()),
+        EncodedVar::String("Green".to_owned()),
+        EncodedVar::String("Blue".to_owned()),
+    ]);
+    assert!(color.allows(&EncodedVar::String("Blue".to_owned())));
+    assert!(!color.allows(&EncodedVar::String("Purple".to_owned())));
+
+    let mode = schema.get_attribute("mode").unwrap();
+    assert_eq!(mode.kind, AttributeKind::Integer);
+    assert!(mode.editable);
+    assert_eq!(mode.options, [EncodedVar::Int(0), EncodedVar::Int(1)]);
+
+    // Numbers become floats even if they were written as integers
+    let size = schema.get_attribute("size").unwrap();
+    assert_eq!(size.kind, AttributeKind::Float);
+    assert_eq!(size.default, Some(EncodedVar::Float(8.0)));
+    assert_eq!(size.options, [
+        EncodedVar::Float(8.0),
+        EncodedVar::Float(16.0)
+    ]);
+
+    // Field information for attributes without a default is ignored
+    assert!(schema.get_attribute("missing").is_none());
+}
+
+#[test]
+fn field_order() {
+    let schema = schema(
+        r#"
+        return {
+            name = "TestHelper/Order",
+            fieldOrder = { "x", "y", "c", "a" },
+            placements = { name = "order", data = { a = 1, b = 2, c = 3 } },
+        }
+        "#,
+    );
+
+    assert_eq!(attribute_names(&schema), ["c", "a", "b"]);
+}
+
+#[test]
+fn malformed() {
+    let error = |src: &str| schemas_from_lua(src, false).unwrap_err();
+
+    assert_eq!(
+        error("return {\n name = \"a }"),
+        LoennError::UnterminatedString { line: 2 }
+    );
+    assert_eq!(
+        error("return {\n name = [[a }"),
+        LoennError::UnterminatedLongBracket { line: 2 }
+    );
+    assert_eq!(
+        error("--[[ never closed\n return {}"),
+        LoennError::UnterminatedLongBracket { line: 1 }
+    );
+    assert_eq!(error("return { name = \"a\""), LoennError::UnexpectedEof);
+    assert_eq!(error("return { name = f(1, 2"), LoennError::UnexpectedEof);
+    assert_eq!(
+        error("function a()\n if x then return {} end"),
+        LoennError::UnexpectedEof
+    );
+    assert_eq!(
+        error(&format!("return {}", "{".repeat(100_000))),
+        LoennError::TooDeep
+    );
+}
+
+#[test]
+fn garbage_does_not_panic() {
+    let inputs = [
+        "",
+        "return",
+        "return }",
+        "return {[",
+        "return {[1",
+        "x = 0x",
+        "x = 1e",
+        "x = .5e+",
+        "[=",
+        "[==",
+        "\"\\",
+        "'\\u{",
+        "'\\u{zz}'",
+        "'\\999'",
+        "local = = = ,",
+        "a.b.c[\"d\"] = {}",
+        ") ] } end until",
+        "x:y:z()()[[]]{}",
+        "return {} {} {}",
+        "\u{feff}return { ñame = '✓' }",
+    ];
+
+    for input in inputs {
+        let _ = schemas_from_lua(input, false);
+    }
+}
+
+#[test]
+fn mod_plugins() {
+    let plugin = |name: &str| format!("return {{ name = \"{name}\" }}").into_bytes();
+
+    let mut manager = mod_manager(vec![
+        ("Loenn/entities/spinner.lua", plugin("MyMod/Spinner")),
+        ("Loenn/triggers/flag.lua", plugin("MyMod/FlagTrigger")),
+        // Only lua files directly in the plugin folders are read
+        ("Loenn/entities/spinner.lua.bak", plugin("MyMod/Backup")),
+        ("Loenn/libraries/utils.lua", plugin("MyMod/Library")),
+        ("Ahorn/entities/spinner.lua", plugin("MyMod/Ahorn")),
+    ]);
+
+    let mut schemas = manager
+        .loenn_schemas()
+        .unwrap()
+        .into_iter()
+        .map(|s| (s.name, s.is_trigger))
+        .collect::<Vec<_>>();
+    schemas.sort();
+
+    assert_eq!(schemas, [
+        ("MyMod/FlagTrigger".to_owned(), true),
+        ("MyMod/Spinner".to_owned(), false)
+    ]);
+}
@@ -0,0 +1,103 @@
+use celeste_rs::{
+    mods::maps::{AltSidesMeta, MapMeta},
+    utils::FromYaml,
+};
+
+const META: &str = "\
+Parent: Celeste/1-ForsakenCity
+Interlude: false
+CassetteCheckpointIndex: 2
+Modes:
+  - StartLevel: a-00
+    HeartIsEnd: true
+  - ~
+  - StartLevel: c-00
+    Checkpoints:
+      - Level: c-04
+        Name: MyMod_c_checkpoint
+        Inventory: Default
+        AudioState:
+          Music: event:/music/lvl1/main
+          Ambience: event:/env/amb/01_main
+        Flags: [lights_on]
+";
+
+const ALTSIDES: &str = "\
+Sides:
+  - Map: MyMod/1-Lake-B
+    Label: MyMod_Lake_B
+    UnlockMode: consecutive
+    OverrideVanillaSideData: true
+    ShowHeartPoem: false
+  - Map: MyMod/1-Lake-D
+    CanFullClear: true
+";
+
+fn to_string<T: FromYaml>(value: &T) -> String {
+    let mut out = String::new();
+    value.to_writer(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn map_meta_round_trip() {
+    let meta = MapMeta::parse_from_str(META).unwrap();
+    let read = MapMeta::parse_from_str(&to_string(&meta)).unwrap();
+
+    assert_eq!(read.parent.as_deref(), Some("Celeste/1-ForsakenCity"));
+    assert_eq!(read.interlude, Some(false));
+    assert_eq!(read.cassette_checkpoint_idx, Some(2));
+    assert_eq!(read.icon, None);
+
+    // The missing B side keeps its place
+    let modes = read.map_meta_mode_properties.unwrap();
+    assert_eq!(modes.len(), 3);
+    assert!(modes[1].is_none());
+
+    let a_side = modes[0].as_ref().unwrap();
+    assert_eq!(a_side.start_level.as_deref(), Some("a-00"));
+    assert_eq!(a_side.heart_is_end, Some(true));
+
+    let c_side = modes[2].as_ref().unwrap();
+    let checkpoint = &c_side.checkpoints.as_ref().unwrap()[0];
+    assert_eq!(checkpoint.level, "c-04");
+    assert_eq!(checkpoint.audio_state.music, "event:/music/lvl1/main");
+    assert_eq!(checkpoint.flags, ["lights_on"]);
+}
+
+#[test]
+fn optional_list_elements() {
+    let list = Vec::<Option<String>>::parse_from_str("[a, ~, c]").unwrap();
+    assert_eq!(list, [Some("a".to_owned()), None, Some("c".to_owned())]);
+
+    // Every element is parsed as itself, not as the whole list
+    assert!(Vec::<Option<i64>>::parse_from_str("[1, ~, nope]").is_err());
+
+    let read = Vec::<Option<String>>::parse_from_str(&to_string(&list)).unwrap();
+    assert_eq!(read, list);
+}
+
+#[test]
+fn altsides_meta() {
+    let meta = AltSidesMeta::parse_from_reader(ALTSIDES.as_bytes()).unwrap();
+    assert_eq!(meta.sides.len(), 2);
+
+    let b_side = &meta.sides[0];
+    assert_eq!(b_side.map, "MyMod/1-Lake-B");
+    assert_eq!(b_side.label.as_deref(), Some("MyMod_Lake_B"));
+    assert_eq!(b_side.unlock_mode.as_deref(), Some("consecutive"));
+    assert_eq!(b_side.override_vanilla_side_data, Some(true));
+    assert_eq!(b_side.show_heart_poem, Some(false));
+    assert_eq!(b_side.can_full_clear, None);
+
+    let d_side = &meta.sides[1];
+    assert_eq!(d_side.map, "MyMod/1-Lake-D");
+    assert_eq!(d_side.can_full_clear, Some(true));
+
+    let read = AltSidesMeta::parse_from_str(&to_string(&meta)).unwrap();
+    assert_eq!(read.sides.len(), 2);
+    assert_eq!(read.sides[0].label, b_side.label);
+    assert_eq!(read.sides[1].map, d_side.map);
+
+    assert!(AltSidesMeta::parse_from_str("Sides:\n  - Label: missing map\n").is_err());
+}
@@ -0,0 +1,131 @@
+use std::any::Any;
+
+use celeste_rs::maps::{
+    ErasedMapElement,
+    RawMapElement,
+    elements::{
+        Node,
+        NodeError,
+        NodeList,
+        entities::{
+            EntityData,
+            MapEntity,
+            vanilla_entities::{BadelineBoost, Bridge, KillBox, ZipMover},
+        },
+    },
+};
+
+mod common;
+use common::*;
+
+fn parse<T: EntityData + Clone>(raw: &RawMapElement) -> MapEntity<T> {
+    let manager = manager(map(vec![], vec![], vec![]));
+    let mut entity = manager.parse_entity(raw).unwrap();
+    (entity.as_mut() as &mut dyn Any)
+        .downcast_mut::<MapEntity<T>>()
+        .unwrap()
+        .clone()
+}
+
+fn xs(nodes: &[Node]) -> Vec<f32> {
+    nodes.iter().map(|n| n.x.as_f32()).collect()
+}
+
+#[test]
+fn edit_node_path() {
+    let raw = entity("badelineBoost", vec![("lockCamera", b(true))], 3);
+    let mut boost = parse::<BadelineBoost>(&raw);
+    assert_eq!(xs(boost.nodes()), [0.0, 8.0, 16.0]);
+    assert_eq!(boost.node_limits(), (0, None));
+
+    boost.add_node(Node::new(24, 16)).unwrap();
+    boost.insert_node(0, Node::new(-8, 16)).unwrap();
+    assert_eq!(xs(boost.nodes()), [-8.0, 0.0, 8.0, 16.0, 24.0]);
+
+    assert_eq!(boost.remove_node(2).unwrap(), Node::new(8, 16));
+    assert_eq!(xs(boost.nodes()), [-8.0, 0.0, 16.0, 24.0]);
+
+    boost.move_node(0, 3).unwrap();
+    assert_eq!(xs(boost.nodes()), [0.0, 16.0, 24.0, -8.0]);
+    boost.move_node(2, 0).unwrap();
+    assert_eq!(xs(boost.nodes()), [24.0, 0.0, 16.0, -8.0]);
+
+    boost.nodes_mut()[1].y = 32.into();
+    assert_eq!(boost.entity.nodes[1], Node::new(0, 32));
+    boost.validate_nodes().unwrap();
+}
+
+#[test]
+fn node_indices_are_checked() {
+    let raw = entity("badelineBoost", vec![("lockCamera", b(true))], 2);
+    let mut boost = parse::<BadelineBoost>(&raw);
+    let out_of_bounds = |index| NodeError::OutOfBounds { index, len: 2 };
+
+    assert_eq!(
+        boost.insert_node(3, Node::new(0, 0)).unwrap_err(),
+        out_of_bounds(3)
+    );
+    assert_eq!(boost.remove_node(2).unwrap_err(), out_of_bounds(2));
+    assert_eq!(boost.move_node(0, 2).unwrap_err(), out_of_bounds(2));
+    assert_eq!(boost.move_node(5, 0).unwrap_err(), out_of_bounds(5));
+
+    // Nothing changed
+    assert_eq!(xs(boost.nodes()), [0.0, 8.0]);
+}
+
+#[test]
+fn node_limits_are_checked() {
+    let mut zip_mover = parse::<ZipMover>(&entity("zipMover", vec![], 1));
+    assert_eq!(zip_mover.node_limits(), (1, Some(1)));
+
+    assert_eq!(
+        zip_mover.add_node(Node::new(0, 0)),
+        Err(NodeError::TooManyNodes { max: 1, found: 2 })
+    );
+    assert_eq!(
+        zip_mover.remove_node(0),
+        Err(NodeError::TooFewNodes { min: 1, found: 0 })
+    );
+    assert_eq!(
+        zip_mover.set_nodes(vec![]),
+        Err(NodeError::TooFewNodes { min: 1, found: 0 })
+    );
+
+    zip_mover.set_nodes(vec![Node::new(40, 8)]).unwrap();
+    assert_eq!(zip_mover.entity.to, Node::new(40, 8));
+    zip_mover.validate_nodes().unwrap();
+}
+
+#[test]
+fn bridge_gap_nodes() {
+    let mut bridge = parse::<Bridge>(&entity("bridge", vec![("width", i(64))], 2));
+    assert_eq!(xs(bridge.nodes()), [0.0, 8.0]);
+    assert_eq!(bridge.node_limits(), (2, Some(2)));
+    bridge.validate_nodes().unwrap();
+
+    assert_eq!(
+        bridge.remove_node(1),
+        Err(NodeError::TooFewNodes { min: 2, found: 1 })
+    );
+    bridge
+        .set_nodes(vec![Node::new(16, 0), Node::new(32, 0)])
+        .unwrap();
+    assert_eq!(xs(bridge.nodes()), [16.0, 32.0]);
+}
+
+#[test]
+fn nodes_without_a_node_field_are_kept_as_extra() {
+    let raw = entity("killbox", vec![("width", i(16))], 1);
+    let mut killbox = parse::<KillBox>(&raw);
+
+    assert!(killbox.nodes().is_empty());
+    assert_eq!(killbox.node_limits(), (0, Some(0)));
+    assert!(matches!(
+        killbox.add_node(Node::new(0, 0)),
+        Err(NodeError::TooManyNodes { max: 0, found: 1 })
+    ));
+
+    // The node is still written back
+    let encoded = (&killbox as &dyn ErasedMapElement).encode_raw();
+    assert_eq!(encoded.children, raw.children);
+}
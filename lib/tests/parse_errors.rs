@@ -0,0 +1,180 @@
+use std::any::Any;
+
+use celeste_rs::maps::{
+    RawMapElement,
+    elements::{level::Level, style::Backgrounds},
+    parser::MapElementParsingError,
+};
+
+mod common;
+use common::*;
+
+/// The paths and messages of every error in a [MultiError](MapElementParsingError::MultiError)
+fn errors(error: MapElementParsingError) -> Vec<(String, String)> {
+    let MapElementParsingError::MultiError { errors } = error else {
+        panic!("Expected a MultiError, found {error:?}");
+    };
+
+    errors
+        .into_iter()
+        .map(|(_, e)| (e.path().unwrap().to_owned(), e.without_path().to_string()))
+        .collect()
+}
+
+#[test]
+fn child_errors_are_collected() {
+    let mut broken = level("b-00", (320, 0), vec![], vec![]);
+    broken.remove_attribute("width");
+
+    let root = map(
+        vec![
+            level(
+                "a-00",
+                (0, 0),
+                vec![
+                    entity("strawberry", vec![("checkpointID", i(0))], 0),
+                    entity("spikesUp", vec![("type", i(3))], 0),
+                ],
+                vec![],
+            ),
+            broken,
+        ],
+        vec![],
+        vec![],
+    );
+    let error = manager(root).parse_map().unwrap_err();
+
+    // Every broken element is reported in one flat list instead of stopping at the first
+    assert_eq!(errors(error), [
+        (
+            "Map/levels/level[name=a-00]/entities/strawberry[id=1]@winged".to_owned(),
+            "Missing attribute \"winged\"".to_owned()
+        ),
+        (
+            "Map/levels/level[name=a-00]/entities/spikesUp[id=1]@type".to_owned(),
+            "Error trying to parse encoded var into a type, wanted a 'indexed string', found 'int'"
+                .to_owned()
+        ),
+        (
+            "Map/levels/level[name=b-00]@width".to_owned(),
+            "Missing attribute \"width\"".to_owned()
+        ),
+    ]);
+}
+
+#[test]
+fn error_paths() {
+    let manager = manager(map(vec![], vec![], vec![]));
+
+    let mut raw = level("a-00", (0, 0), vec![], vec![]);
+    raw.remove_attribute("height");
+    let error = manager.parse_element::<Level>(&raw).unwrap_err();
+    assert_eq!(error.path(), Some("level[name=a-00]@height"));
+    assert!(matches!(
+        error.without_path(),
+        MapElementParsingError::AttributeMissing { name: "height" }
+    ));
+    assert_eq!(
+        error.to_string(),
+        "level[name=a-00]@height: Missing attribute \"height\""
+    );
+
+    // Missing children are reported on the parent
+    let mut raw = level("a-00", (0, 0), vec![], vec![]);
+    raw.children.retain(|c| c.name.as_str() != Some("solids"));
+    let error = manager.parse_element::<Level>(&raw).unwrap_err();
+    assert_eq!(error.path(), Some("level[name=a-00]"));
+    assert!(matches!(
+        error.without_path(),
+        MapElementParsingError::NoMatchingElementFound {
+            expected: "solids",
+            ..
+        }
+    ));
+
+    // Elements without a name or id only use their element name
+    let raw = el("Backgrounds", vec![], vec![el(
+        "parallax",
+        vec![("texture", i(0))],
+        vec![],
+    )]);
+    let error = manager.parse_element::<Backgrounds>(&raw).unwrap_err();
+    assert_eq!(errors(error), [(
+        "Backgrounds/parallax@texture".to_owned(),
+        "Error trying to parse encoded var into a type, wanted a 'indexed string', found 'int'"
+            .to_owned()
+    )]);
+}
+
+/// A room with a strawberry and a music trigger that are both missing attributes
+fn broken_room() -> RawMapElement {
+    map(
+        vec![level(
+            "a-00",
+            (0, 0),
+            vec![
+                entity("strawberry", vec![("checkpointID", i(0))], 0),
+                entity(
+                    "strawberry",
+                    vec![("winged", b(true)), ("checkpointID", i(0))],
+                    0,
+                ),
+            ],
+            vec![trigger("musicTrigger", vec![], 0)],
+        )],
+        vec![],
+        vec![],
+    )
+}
+
+#[test]
+fn lenient_parsing() {
+    let mut manager = manager(broken_room());
+    assert!(manager.parse_map().is_err());
+    assert!(manager.take_warnings().is_empty());
+
+    manager.set_lenient(true);
+    let root = manager.parse_map().unwrap();
+
+    // The broken elements are kept as they were
+    let room = &root.levels.levels[0];
+    let entities = &room.entities.as_ref().unwrap().entities;
+    assert!((entities[0].as_ref() as &dyn Any).is::<RawMapElement>());
+    assert!(!(entities[1].as_ref() as &dyn Any).is::<RawMapElement>());
+    let triggers = &room.triggers.as_ref().unwrap().triggers;
+    assert!((triggers[0].as_ref() as &dyn Any).is::<RawMapElement>());
+
+    let warnings = manager.take_warnings();
+    assert_eq!(
+        warnings
+            .iter()
+            .map(|w| (w.element.as_str(), w.error.path().unwrap()))
+            .collect::<Vec<_>>(),
+        [
+            (
+                "musicTrigger",
+                "Map/levels/level[name=a-00]/triggers/musicTrigger[id=1]@track"
+            ),
+            (
+                "strawberry",
+                "Map/levels/level[name=a-00]/entities/strawberry[id=1]@winged"
+            )
+        ]
+    );
+    assert_eq!(
+        warnings[1].to_string(),
+        "strawberry was kept unparsed: \
+         Map/levels/level[name=a-00]/entities/strawberry[id=1]@winged: Missing attribute \
+         \"winged\""
+    );
+    assert!(manager.take_warnings().is_empty());
+}
+
+#[test]
+fn lenient_parsing_only_covers_dynamic_elements() {
+    let mut broken = level("a-00", (0, 0), vec![], vec![]);
+    broken.remove_attribute("width");
+    let mut manager = manager(map(vec![broken], vec![], vec![]));
+    manager.set_lenient(true);
+    assert!(manager.parse_map().is_err());
+}
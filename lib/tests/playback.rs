@@ -0,0 +1,126 @@
+use celeste_rs::playbacks::{Playback, PlaybackFacing, PlaybackReadError, PlaybackVersion};
+
+fn string(bytes: &mut Vec<u8>, value: &str) {
+    bytes.push(value.len() as u8);
+    bytes.extend(value.as_bytes());
+}
+
+fn header(version: Option<i32>, count: i32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    if let Some(version) = version {
+        string(&mut bytes, "TIMELINE");
+        bytes.extend(version.to_le_bytes());
+    }
+    bytes.extend(count.to_le_bytes());
+    bytes
+}
+
+/// Appends a frame at `x`, with the scale and dash direction when `scale` is set
+fn frame(bytes: &mut Vec<u8>, x: f32, timestamp: f32, facing: i32, scale: bool) {
+    for value in [x, 16.0, timestamp] {
+        bytes.extend(value.to_le_bytes());
+    }
+    string(bytes, "idle");
+    bytes.extend(facing.to_le_bytes());
+    bytes.push(1);
+    bytes.extend([0xAC, 0x32, 0x32]);
+    bytes.extend(0i32.to_le_bytes());
+
+    if scale {
+        for value in [-1.0f32, 1.0, 0.0, -1.0] {
+            bytes.extend(value.to_le_bytes());
+        }
+    }
+}
+
+fn playback(version: Option<i32>, scale: bool) -> Vec<u8> {
+    let mut bytes = header(version, 2);
+    frame(&mut bytes, 0.0, 0.0, 1, scale);
+    frame(&mut bytes, 2.0, 1.0 / 60.0, -1, scale);
+    bytes
+}
+
+#[test]
+fn versions_are_detected() {
+    for (bytes, version) in [
+        (playback(None, false), PlaybackVersion::Legacy),
+        (playback(Some(1), false), PlaybackVersion::V1),
+        (playback(Some(2), true), PlaybackVersion::V2),
+    ] {
+        let playback = Playback::from_bytes(&bytes).unwrap();
+        assert_eq!(playback.version, version);
+        assert_eq!(playback.frames.len(), 2);
+
+        let frame = &playback.frames[1];
+        assert_eq!(frame.position, [2.0, 16.0]);
+        assert_eq!(frame.animation, "idle");
+        assert_eq!(frame.facing, PlaybackFacing::Left);
+        assert!(frame.on_ground);
+        assert_eq!(frame.hair_color, [0xAC, 0x32, 0x32]);
+
+        if version.has_scale() {
+            assert_eq!(frame.dash_direction, [0.0, -1.0]);
+        } else {
+            // Older versions take the scale from the facing
+            assert_eq!(frame.scale, [-1.0, 1.0]);
+            assert_eq!(frame.dash_direction, [0.0, 0.0]);
+        }
+
+        // Each version is written back the way it was read
+        assert_eq!(playback.to_bytes(), bytes);
+    }
+}
+
+#[test]
+fn invalid_headers() {
+    assert!(matches!(
+        Playback::from_bytes(&[]),
+        Err(PlaybackReadError::InvalidHeader)
+    ));
+
+    // Without a header a frame count larger than the data isn't a playback
+    let mut bytes = header(None, 100);
+    frame(&mut bytes, 0.0, 0.0, 1, false);
+    assert!(matches!(
+        Playback::from_bytes(&bytes),
+        Err(PlaybackReadError::InvalidHeader)
+    ));
+
+    assert!(matches!(
+        Playback::from_bytes(&header(Some(3), 0)),
+        Err(PlaybackReadError::UnknownVersion(3))
+    ));
+    assert!(matches!(
+        Playback::from_bytes(&header(Some(2), -1)),
+        Err(PlaybackReadError::InvalidFrameCount(-1))
+    ));
+}
+
+#[test]
+fn invalid_frames() {
+    let mut bytes = header(Some(1), 2);
+    frame(&mut bytes, 0.0, 0.0, 1, false);
+    frame(&mut bytes, 0.0, 0.0, 0, false);
+    assert!(matches!(
+        Playback::from_bytes(&bytes),
+        Err(PlaybackReadError::InvalidFacing { frame: 1, value: 0 })
+    ));
+
+    let mut bytes = playback(Some(2), true);
+    bytes.truncate(bytes.len() - 4);
+    assert!(matches!(
+        Playback::from_bytes(&bytes),
+        Err(PlaybackReadError::TruncatedFrame(1))
+    ));
+
+    let mut bytes = header(Some(2), 3);
+    frame(&mut bytes, 0.0, 0.0, 1, true);
+    assert!(matches!(
+        Playback::from_bytes(&bytes),
+        Err(PlaybackReadError::FrameCountMismatch {
+            expected: 3,
+            found: 1
+        })
+    ));
+}
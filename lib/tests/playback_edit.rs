@@ -0,0 +1,127 @@
+use celeste_rs::playbacks::{FRAME_TIME, Playback, edit::PlaybackEditError};
+
+mod common;
+use common::*;
+
+/// A playback running to the right one pixel per frame
+fn walk(frames: usize) -> Playback {
+    Playback::new(
+        (0 .. frames)
+            .map(|i| frame(i, [i as f32, 0.0], "walk", [0.0, 0.0]))
+            .collect(),
+    )
+}
+
+fn positions(playback: &Playback) -> Vec<[f32; 2]> {
+    playback.frames.iter().map(|f| f.position).collect()
+}
+
+#[test]
+fn scale_speed() {
+    let mut playback = walk(3);
+    playback.scale_speed(2.0).unwrap();
+
+    let timestamps = playback
+        .frames
+        .iter()
+        .map(|f| f.timestamp)
+        .collect::<Vec<_>>();
+    assert_eq!(timestamps, [0.0, FRAME_TIME / 2.0, FRAME_TIME]);
+}
+
+#[test]
+fn scale_speed_rejects_invalid_speeds() {
+    for speed in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+        let mut playback = walk(3);
+        let before = playback.to_bytes();
+
+        assert!(matches!(
+            playback.scale_speed(speed),
+            Err(PlaybackEditError::InvalidSpeed(_))
+        ));
+        assert_eq!(
+            playback.to_bytes(),
+            before,
+            "speed {speed} changed the playback"
+        );
+    }
+}
+
+#[test]
+fn translate_changes_the_written_frames() {
+    let mut playback = walk(3);
+    playback.translate([8.0, -16.0]);
+
+    let read = Playback::from_bytes(&playback.to_bytes()).unwrap();
+    assert_eq!(positions(&read), [[8.0, -16.0], [9.0, -16.0], [
+        10.0, -16.0
+    ]]);
+}
+
+#[test]
+fn set_origin_changes_the_written_frames() {
+    let mut playback = walk(3);
+    playback.set_origin([-4.0, 24.0]);
+
+    let read = Playback::from_bytes(&playback.to_bytes()).unwrap();
+    assert_eq!(positions(&read), [[-4.0, 24.0], [-3.0, 24.0], [-2.0, 24.0]]);
+}
+
+#[test]
+fn trimming_keeps_positions() {
+    let mut playback = walk(10);
+    playback.trim(5.0 * FRAME_TIME, 7.0 * FRAME_TIME);
+
+    let read = Playback::from_bytes(&playback.to_bytes()).unwrap();
+    assert_eq!(positions(&read), [[5.0, 0.0], [6.0, 0.0], [7.0, 0.0]]);
+    // Timestamps always start at 0
+    assert_eq!(read.frames[0].timestamp, 0.0);
+
+    playback.set_origin([0.0, 0.0]);
+    assert_eq!(positions(&playback), [[0.0, 0.0], [1.0, 0.0], [2.0, 0.0]]);
+}
+
+#[test]
+fn round_trip_is_lossless() {
+    let mut playback = walk(4);
+    playback.translate([3.0, 5.0]);
+    let bytes = playback.to_bytes();
+
+    assert_eq!(Playback::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+}
+
+#[test]
+fn dashes_in_the_same_direction() {
+    let right = [1.0, 0.0];
+    let up = [0.0, -1.0];
+
+    let playback = Playback::new(vec![
+        frame(0, [0.0, 0.0], "idle", [0.0, 0.0]),
+        frame(1, [0.0, 0.0], "dash", right),
+        frame(2, [8.0, 0.0], "dash", right),
+        frame(3, [16.0, 0.0], "fall", right),
+        // A second dash to the right, the direction never changes
+        frame(4, [20.0, 0.0], "dash", right),
+        frame(5, [28.0, 0.0], "dash", right),
+        // A dash in a new direction, still in the dash animation
+        frame(6, [28.0, -8.0], "dash", up),
+        frame(7, [28.0, -16.0], "fall", up),
+    ]);
+
+    assert_eq!(playback.dash_frames(), [1, 4, 6]);
+    assert_eq!(playback.stats().dashes, 3);
+}
+
+#[test]
+fn stats() {
+    let playback = walk(5);
+    let stats = playback.stats();
+
+    assert_eq!(stats.frames, 5);
+    assert_eq!(stats.duration, 4.0 * FRAME_TIME);
+    assert_eq!(stats.distance, 4.0);
+    assert_eq!(stats.dashes, 0);
+    assert_eq!(stats.min, [0.0, 0.0]);
+    assert_eq!(stats.max, [4.0, 0.0]);
+    assert_eq!(stats.animations["walk"], 5);
+}
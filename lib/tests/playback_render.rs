@@ -0,0 +1,134 @@
+use celeste_rs::playbacks::{
+    Playback,
+    render::{MAX_PNG_PIXELS, RenderError, RoomPreview},
+};
+
+mod common;
+use common::*;
+
+/// Walks 8 pixels right then dashes 16 pixels up
+fn playback() -> Playback {
+    let mut frames = (0 .. 9)
+        .map(|i| frame(i, [i as f32, 0.0], "walk", [0.0, 0.0]))
+        .collect::<Vec<_>>();
+    frames.push(frame(9, [8.0, -8.0], "dash", [0.0, -1.0]));
+    frames.push(frame(10, [8.0, -16.0], "dash", [0.0, -1.0]));
+    Playback::new(frames)
+}
+
+fn decode(png: &[u8]) -> (u32, u32, Vec<u8>) {
+    let mut reader = png::Decoder::new(png).read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).unwrap();
+    buf.truncate(info.buffer_size());
+
+    (info.width, info.height, buf)
+}
+
+fn pixel(image: &(u32, u32, Vec<u8>), x: u32, y: u32) -> [u8; 3] {
+    let i = (y * image.0 + x) as usize * 4;
+    [image.2[i], image.2[i + 1], image.2[i + 2]]
+}
+
+#[test]
+fn png_fits_the_path() {
+    let image = decode(&playback().to_png(None, [0.0, 0.0], 2).unwrap());
+
+    // The path covers 8x16 pixels with a 16 pixel margin on each side
+    assert_eq!((image.0, image.1), (40 * 2, 48 * 2));
+    // The background, the ground path, and the dash marker
+    assert_eq!(pixel(&image, 0, 0), [0x1E, 0x1E, 0x28]);
+    assert_eq!(pixel(&image, 16 * 2, 32 * 2), [0x4C, 0xAF, 0x50]);
+    assert_eq!(pixel(&image, 24 * 2, 24 * 2), [0xF4, 0x43, 0x36]);
+}
+
+#[test]
+fn png_over_a_room() {
+    let room = RoomPreview {
+        width: 16,
+        height: 16,
+        solids: vec![vec![false, false], vec![true, true]],
+    };
+
+    let image = decode(&playback().to_png(Some(&room), [0.0, 0.0], 1).unwrap());
+
+    assert_eq!((image.0, image.1), (16, 16));
+    assert_eq!(pixel(&image, 12, 12), [0x5A, 0x5A, 0x6E]);
+}
+
+#[test]
+fn png_lines_are_clipped() {
+    let room = RoomPreview {
+        width: 16,
+        height: 16,
+        solids: Vec::new(),
+    };
+    // The player flies far off the side of the room
+    let playback = Playback::new(vec![
+        frame(0, [0.0, 0.0], "walk", [0.0, 0.0]),
+        frame(1, [1.0e9, 0.0], "walk", [0.0, 0.0]),
+        frame(2, [1.0e9, 1.0e9], "walk", [0.0, 0.0]),
+    ]);
+
+    let image = decode(&playback.to_png(Some(&room), [8.0, 8.0], 1).unwrap());
+
+    assert_eq!(pixel(&image, 8, 8), [0x4C, 0xAF, 0x50]);
+    assert_eq!(pixel(&image, 15, 8), [0x4C, 0xAF, 0x50]);
+    assert_eq!(pixel(&image, 4, 8), [0x1E, 0x1E, 0x28]);
+    assert_eq!(pixel(&image, 15, 12), [0x1E, 0x1E, 0x28]);
+}
+
+#[test]
+fn png_size_is_limited() {
+    let room = RoomPreview {
+        width: 40_000,
+        height: 40_000,
+        solids: Vec::new(),
+    };
+
+    assert_eq!(
+        playback().to_png(Some(&room), [0.0, 0.0], 1),
+        Err(RenderError::TooLarge {
+            width: 40_000,
+            height: 40_000
+        })
+    );
+    // Scaling would overflow a u32
+    assert_eq!(
+        playback().to_png(None, [0.0, 0.0], u32::MAX),
+        Err(RenderError::TooLarge {
+            width: 40 * u32::MAX as u64,
+            height: 48 * u32::MAX as u64
+        })
+    );
+
+    let side = (MAX_PNG_PIXELS as f64).sqrt() as u32;
+    let room = RoomPreview {
+        width: side,
+        height: side + 1,
+        solids: Vec::new(),
+    };
+    assert!(matches!(
+        playback().to_png(Some(&room), [0.0, 0.0], 1),
+        Err(RenderError::TooLarge { .. })
+    ));
+
+    let empty = RoomPreview::default();
+    assert_eq!(
+        playback().to_png(Some(&empty), [0.0, 0.0], 1),
+        Err(RenderError::Empty)
+    );
+}
+
+#[test]
+fn svg_marks_dashes() {
+    let svg = playback().to_svg(None, [100.0, 50.0]);
+
+    assert!(svg.starts_with(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="48" viewBox="84 18 40 48">"#
+    ));
+    assert_eq!(svg.matches("<circle").count(), 1);
+    assert!(svg.contains(r##"<circle cx="108" cy="42" r="2" fill="#f44336"/>"##));
+    // One line on the ground and one in the air
+    assert_eq!(svg.matches("<polyline").count(), 2);
+}
@@ -0,0 +1,138 @@
+use celeste_rs::playbacks::{FRAME_TIME, Playback, PlaybackFacing, trace::TraceError};
+
+#[test]
+fn csv_trace() {
+    let playback = Playback::from_trace_csv(
+        "# recorded in 1a
+        frame, x, y, animation, facing, hair_color, dash_x, dash_y, on_ground, depth
+        120, 100.5, 200, idle, right, ac3232, 0, 0, true, 0
+        121, 101.5, 200, walk, left, #44B7FF, 1, 0, false, -1
+
+        123, 103.5, 196, , -1, , , , 0,
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(playback.frames.len(), 3);
+
+    let [first, second, third] = &playback.frames[..] else {
+        unreachable!()
+    };
+
+    // Positions are relative to the first frame
+    assert_eq!(first.position, [0.0, 0.0]);
+    assert_eq!(second.position, [1.0, 0.0]);
+    assert_eq!(third.position, [3.0, -4.0]);
+
+    assert_eq!(first.animation, "idle");
+    assert_eq!(second.animation, "walk");
+    assert_eq!(third.animation, "idle");
+
+    assert_eq!(second.facing, PlaybackFacing::Left);
+    assert_eq!(second.scale, [-1.0, 1.0]);
+    assert_eq!(third.facing, PlaybackFacing::Left);
+
+    assert_eq!(second.hair_color, [0x44, 0xB7, 0xFF]);
+    assert_eq!(third.hair_color, [0xAC, 0x32, 0x32]);
+    assert_eq!(second.dash_direction, [1.0, 0.0]);
+    assert!(first.on_ground);
+    assert!(!third.on_ground);
+    assert_eq!(second.depth, -1);
+
+    // Skipped frame numbers leave a gap in time
+    let written = Playback::from_bytes(&playback.to_bytes()).unwrap();
+    assert_eq!(written.frames[0].timestamp, 0.0);
+    assert!((written.frames[2].timestamp - 3.0 * FRAME_TIME).abs() < 1e-6);
+}
+
+#[test]
+fn csv_trace_errors() {
+    assert!(matches!(
+        Playback::from_trace_csv("frame, x\n0, 1"),
+        Err(TraceError::MissingColumn("y"))
+    ));
+    assert!(matches!(
+        Playback::from_trace_csv("x, y\n0, 0\n1"),
+        Err(TraceError::MissingField(1, "y"))
+    ));
+    assert!(matches!(
+        Playback::from_trace_csv("x, y, facing\n0, 0, up"),
+        Err(TraceError::InvalidValue {
+            frame: 0,
+            name: "facing",
+            ..
+        })
+    ));
+    assert!(matches!(
+        Playback::from_trace_csv("x, y, hair_color\n0, 0, red"),
+        Err(TraceError::InvalidValue {
+            name: "hair_color",
+            ..
+        })
+    ));
+    assert!(matches!(
+        Playback::from_trace_csv("frame, x, y\n5, 0, 0\n5, 1, 0"),
+        Err(TraceError::FramesOutOfOrder(1))
+    ));
+}
+
+#[test]
+fn json_trace() {
+    let list = Playback::from_trace_json(
+        r#"[
+            { "x": 10, "y": 20, "animation": "dash", "dash_direction": [0, -1], "hair_color": [68, 183, 255] },
+            { "position": [12.5, 18], "facing": "left", "scale": [-1.2, 0.8], "on_ground": true }
+        ]"#,
+    )
+    .unwrap();
+
+    let object = Playback::from_trace_json(
+        r#"{
+            "name": "test",
+            "frames": [
+                { "x": 10, "y": 20, "animation": "dash", "dash_direction": [0, -1], "hair_color": "44b7ff" },
+                { "x": 12.5, "y": 18, "facing": -1, "scale": [-1.2, 0.8], "on_ground": true }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(list.to_bytes(), object.to_bytes());
+
+    let [first, second] = &list.frames[..] else {
+        unreachable!()
+    };
+
+    assert_eq!(first.position, [0.0, 0.0]);
+    assert_eq!(second.position, [2.5, -2.0]);
+    assert_eq!(first.animation, "dash");
+    assert_eq!(first.dash_direction, [0.0, -1.0]);
+    assert_eq!(first.hair_color, [68, 183, 255]);
+    assert_eq!(second.facing, PlaybackFacing::Left);
+    assert_eq!(second.scale, [-1.2, 0.8]);
+    assert!(second.on_ground);
+    assert!((second.timestamp - first.timestamp - FRAME_TIME).abs() < 1e-6);
+}
+
+#[test]
+fn json_trace_errors() {
+    assert!(matches!(
+        Playback::from_trace_json(r#"[{ "x": 1 }]"#),
+        Err(TraceError::MissingField(0, "y"))
+    ));
+    assert!(matches!(
+        Playback::from_trace_json(r#"[{ "position": [1] }]"#),
+        Err(TraceError::InvalidValue {
+            name: "position",
+            ..
+        })
+    ));
+    assert!(matches!(
+        Playback::from_trace_json(r#"[{ "x": 0, "y": 0, "hair_color": [1, 2, 300] }]"#),
+        Err(TraceError::InvalidValue {
+            name: "hair_color",
+            ..
+        })
+    ));
+    assert!(Playback::from_trace_json(r#"{ "frames": 3 }"#).is_err());
+}
@@ -0,0 +1,184 @@
+use std::any::Any;
+
+use celeste_rs::maps::{
+    ErasedMapElement,
+    RawMapElement,
+    elements::{Filler, level::Level, style::Apply},
+    reflect::{FieldError, FieldKind},
+};
+
+mod common;
+use common::*;
+
+fn strawberry() -> RawMapElement {
+    entity(
+        "strawberry",
+        vec![
+            ("winged", b(true)),
+            ("checkpointID", i(2)),
+            ("customAttribute", s("kept")),
+        ],
+        0,
+    )
+}
+
+#[test]
+fn typed_entity_fields() {
+    let manager = manager(map(vec![], vec![], vec![]));
+    let mut entity = manager.parse_entity(&strawberry()).unwrap();
+    let entity = entity.as_mut() as &mut dyn ErasedMapElement;
+
+    let info = entity.field_info();
+    let winged = info.iter().find(|f| f.attribute == "winged").unwrap();
+    assert_eq!((winged.kind, winged.optional), (FieldKind::Bool, false));
+    let order = info.iter().find(|f| f.attribute == "order").unwrap();
+    assert_eq!((order.kind, order.optional), (FieldKind::Integer, true));
+    assert!(
+        info.iter()
+            .any(|f| f.name == "checkpoint_id" && f.attribute == "checkpointID")
+    );
+
+    assert_eq!(entity.get_field("winged"), Some(b(true)));
+    assert_eq!(entity.get_field("x"), Some(f(8.0)));
+    assert_eq!(entity.get_field("order"), None);
+    // Read from the extra attributes
+    assert_eq!(entity.get_field("customAttribute"), Some(s("kept")));
+
+    entity.set_field("winged", Some(b(false))).unwrap();
+    entity.set_field("order", Some(i(4))).unwrap();
+    entity.set_field("customAttribute", None).unwrap();
+    assert_eq!(entity.get_field("winged"), Some(b(false)));
+    assert_eq!(entity.get_field("order"), Some(i(4)));
+    assert_eq!(entity.get_field("customAttribute"), None);
+
+    entity.set_field("order", None).unwrap();
+    assert_eq!(entity.get_field("order"), None);
+
+    assert!(matches!(
+        entity.set_field("winged", None),
+        Err(FieldError::Required(_))
+    ));
+    assert!(matches!(
+        entity.set_field("winged", Some(s("yes"))),
+        Err(FieldError::InvalidValue { .. })
+    ));
+    assert_eq!(entity.get_field("winged"), Some(b(false)));
+
+    let raw = entity.encode_raw();
+    assert_eq!(raw.get_attribute("winged"), Some(&b(false)));
+    assert_eq!(raw.get_attribute("customAttribute"), None);
+}
+
+#[test]
+fn raw_element_fields() {
+    let mut raw = entity("MyMod/Thing", vec![("speed", f(1.5))], 0);
+    let element = &mut raw as &mut dyn ErasedMapElement;
+
+    assert!(element.field_info().is_empty());
+    assert_eq!(element.get_field("speed"), Some(f(1.5)));
+    assert_eq!(element.get_field("missing"), None);
+
+    // Anything can be set on a raw element
+    element.set_field("speed", Some(s("fast"))).unwrap();
+    element.set_field("added", Some(i(1))).unwrap();
+    element.set_field("x", None).unwrap();
+
+    assert_eq!(raw.get_attribute("speed"), Some(&s("fast")));
+    assert_eq!(raw.get_attribute("added"), Some(&i(1)));
+    assert_eq!(raw.get_attribute("x"), None);
+}
+
+#[test]
+fn apply_group_fields() {
+    let manager = manager(map(vec![], vec![], vec![]));
+    let raw = el(
+        "apply",
+        vec![("notflag", s("done")), ("scrollx", f(0.5))],
+        vec![effect("stars", vec![])],
+    );
+    let mut apply = manager.parse_element::<Apply>(&raw).unwrap();
+    let element = &mut apply as &mut dyn ErasedMapElement;
+
+    let info = element.field_info();
+    assert_eq!(info.iter().map(|f| &f.attribute[..]).collect::<Vec<_>>(), [
+        "only", "exclude", "flag", "notflag"
+    ]);
+    assert!(
+        info.iter()
+            .all(|f| f.kind == FieldKind::String && f.optional)
+    );
+
+    assert_eq!(element.get_field("notflag"), Some(s("done")));
+    assert_eq!(element.get_field("scrollx"), Some(f(0.5)));
+
+    element.set_field("only", Some(s("a-*"))).unwrap();
+    element.set_field("notflag", None).unwrap();
+    assert_eq!(element.get_field("only"), Some(s("a-*")));
+
+    let encoded = element.encode_raw();
+    assert_eq!(encoded.get_attribute("notflag"), None);
+    assert_eq!(encoded.children, raw.children);
+    assert_eq!(apply.stylegrounds.len(), 1);
+}
+
+#[test]
+fn unknown_children_keep_their_order() {
+    let manager = manager(map(vec![], vec![], vec![]));
+    let names = |raw: &RawMapElement| {
+        raw.children
+            .iter()
+            .map(|c| c.name.as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    // Start from the order typed levels write their children in
+    let level = manager
+        .parse_element::<Level>(&level("a-00", (0, 0), vec![], vec![]))
+        .unwrap();
+    let mut raw = (&level as &dyn ErasedMapElement).encode_raw();
+    raw.children.insert(1, el("customData", vec![], vec![]));
+    raw.children.push(el("moreData", vec![], vec![]));
+
+    let level = manager.parse_element::<Level>(&raw).unwrap();
+    let encoded = (&level as &dyn ErasedMapElement).encode_raw();
+    assert_eq!(names(&encoded), names(&raw));
+
+    // Unknown children between nodes
+    let mut raw = entity(
+        "strawberry",
+        vec![("winged", b(false)), ("checkpointID", i(0))],
+        2,
+    );
+    raw.children.insert(1, el("customData", vec![], vec![]));
+
+    let strawberry = manager.parse_entity(&raw).unwrap();
+    let encoded = (strawberry.as_ref() as &dyn ErasedMapElement).encode_raw();
+    assert_eq!(names(&encoded), ["node", "customData", "node"]);
+}
+
+#[test]
+fn elements_without_fields_keep_unknown_data() {
+    let manager = manager(map(vec![], vec![], vec![]));
+    let round_trip = |raw: &RawMapElement| {
+        let parsed = manager.parse_dyn_element(raw).unwrap();
+        assert!(!(parsed.as_ref() as &dyn Any).is::<RawMapElement>());
+        parsed.encode_raw()
+    };
+
+    let player = entity("player", vec![("everestExtra", s("kept"))], 0);
+    assert_eq!(round_trip(&player), player);
+
+    let killbox = entity("killbox", vec![("customFlag", b(true))], 1);
+    assert_eq!(round_trip(&killbox), killbox);
+
+    let blocker = trigger("lookoutBlocker", vec![("customFlag", s("kept"))], 1);
+    assert_eq!(round_trip(&blocker), blocker);
+
+    let filler = el("Filler", vec![("customFlag", b(true))], vec![el(
+        "rect",
+        vec![("x", i(0)), ("y", i(0)), ("w", i(4)), ("h", i(2))],
+        vec![],
+    )]);
+    let parsed = manager.parse_element::<Filler>(&filler).unwrap();
+    assert_eq!((&parsed as &dyn ErasedMapElement).encode_raw(), filler);
+}
@@ -0,0 +1,171 @@
+use celeste_rs::{
+    maps::{
+        RawMapElement,
+        elements::{MapRoot, level::Level},
+        rooms::{copy_room, paste_room},
+        transform::TransformError,
+    },
+    utils::num::Float,
+};
+
+mod common;
+use common::*;
+
+fn with_id(mut element: RawMapElement, id: i32) -> RawMapElement {
+    element.set_attribute("id", i(id));
+    element
+}
+
+fn test_map() -> RawMapElement {
+    let mut root = map(
+        vec![
+            level(
+                "a-00",
+                (0, 0),
+                vec![
+                    with_id(entity("MyMod/Thing", vec![], 0), 1),
+                    with_id(entity("MyMod/Thing", vec![], 0), 2),
+                ],
+                vec![with_id(trigger("MyMod/Trigger", vec![], 0), 3)],
+            ),
+            level(
+                "a-01",
+                (320, 0),
+                vec![with_id(entity("MyMod/Thing", vec![], 0), 5)],
+                vec![],
+            ),
+        ],
+        vec![],
+        vec![],
+    );
+    // Overlaps the left edge of a-00
+    root.children[0] = el("Filler", vec![], vec![el(
+        "rect",
+        vec![("x", i(-1)), ("y", i(0)), ("w", i(2)), ("h", i(4))],
+        vec![],
+    )]);
+    root
+}
+
+fn find<'a>(root: &'a MapRoot, name: &str) -> &'a Level {
+    root.levels
+        .levels
+        .iter()
+        .find(|l| l.name.as_str() == Some(name))
+        .unwrap()
+}
+
+fn ids(level: &Level) -> Vec<i64> {
+    let entities = level
+        .entities
+        .iter()
+        .flat_map(|e| &e.entities)
+        .map(|e| e.id().as_i64());
+    let triggers = level.triggers.iter().flat_map(|t| &t.triggers).map(|t| {
+        t.as_ref()
+            .encode_raw()
+            .get_attribute("id")
+            .unwrap()
+            .int()
+            .unwrap()
+            .as_i64()
+    });
+
+    entities.chain(triggers).collect()
+}
+
+#[test]
+fn copy_leaves_the_map_unchanged() {
+    let root = manager(test_map()).parse_map().unwrap();
+
+    let copied = copy_room(&root, "a-00").unwrap();
+    assert_eq!(&copied.level, find(&root, "a-00"));
+    assert_eq!(copied.filler.len(), 1);
+    assert_eq!(root.levels.levels.len(), 2);
+
+    assert!(copy_room(&root, "b-00").is_none());
+}
+
+#[test]
+fn paste_remaps_ids() {
+    let manager = manager(test_map());
+    let mut root = manager.parse_map().unwrap();
+
+    let copied = copy_room(&root, "a-00").unwrap();
+    let report = paste_room(&mut root, &manager, copied).unwrap();
+
+    assert_eq!(root.levels.levels.len(), 3);
+    assert_eq!(ids(find(&root, "a-00")), [1, 2, 3]);
+    // Above the highest id in the map
+    assert_eq!(ids(find(&root, "a-00-2")), [6, 7, 8]);
+    assert!(report.errors.is_empty());
+    assert_eq!(
+        report
+            .changes
+            .iter()
+            .filter(|c| c.description.starts_with("remapped"))
+            .count(),
+        3
+    );
+
+    // Moved to the right of a-01, along with its filler
+    let pasted = find(&root, "a-00-2");
+    assert_eq!((pasted.x.as_f32(), pasted.y.as_f32()), (640.0, 0.0));
+
+    let filler = &root.filler.as_ref().unwrap().filler;
+    assert_eq!(filler.len(), 2);
+    assert_eq!(filler[1].x.as_i64(), 79);
+}
+
+#[test]
+fn paste_moves_by_whole_tiles() {
+    let manager = manager(test_map());
+    let mut root = manager.parse_map().unwrap();
+
+    let mut copied = copy_room(&root, "a-00").unwrap();
+    copied.level.x = Float::from(4);
+    paste_room(&mut root, &manager, copied).unwrap();
+
+    // Still 4 pixels off the grid, lined up with its filler
+    let pasted = find(&root, "a-00-2");
+    assert_eq!(pasted.x.as_f32(), 644.0);
+    assert_eq!(root.filler.as_ref().unwrap().filler[1].x.as_i64(), 79);
+}
+
+#[test]
+fn paste_into_another_map() {
+    let manager = manager(test_map());
+    let source = manager.parse_map().unwrap();
+    let mut target = manager.parse_map().unwrap();
+    target.levels.levels.clear();
+
+    let copied = copy_room(&source, "a-01").unwrap();
+    let report = paste_room(&mut target, &manager, copied).unwrap();
+
+    // Nothing conflicts and a-01 has no filler
+    assert!(report.is_empty());
+    assert_eq!(ids(find(&target, "a-01")), [5]);
+    assert_eq!(find(&target, "a-01").x.as_f32(), 320.0);
+}
+
+#[test]
+fn paste_ids_out_of_range() {
+    let manager = manager(test_map());
+    let mut root = manager.parse_map().unwrap();
+
+    let mut copied = copy_room(&root, "a-00").unwrap();
+    copied
+        .level
+        .entities
+        .as_mut()
+        .unwrap()
+        .entities
+        .push(Box::new(with_id(entity("MyMod/Thing", vec![], 0), i32::MAX)));
+
+    // The three clashing ids would go above i32::MAX
+    assert_eq!(
+        paste_room(&mut root, &manager, copied).unwrap_err(),
+        TransformError::IdOutOfRange(i32::MAX as i64 + 3)
+    );
+    assert_eq!(root, manager.parse_map().unwrap());
+}
@@ -0,0 +1,142 @@
+use std::any::Any;
+
+use celeste_rs::maps::{
+    ErasedMapElement,
+    reflect::{FieldError, FieldKind},
+    schema::{AttributeKind, AttributeSchema, EntitySchema, SchemaEntity},
+    var_types::EncodedVar,
+};
+
+mod common;
+use common::*;
+
+fn spinner_schema() -> EntitySchema {
+    let mut schema = EntitySchema::new("TestHelper/Spinner", false);
+    schema.add_attribute(AttributeSchema::required("color", AttributeKind::String));
+    schema.add_attribute(AttributeSchema::with_default("speed", 1.5f32));
+    schema.add_attribute(AttributeSchema::with_default("attached", false));
+    schema
+}
+
+#[test]
+fn defaults_are_not_written_into_the_element() {
+    let raw = entity(
+        "TestHelper/Spinner",
+        vec![("color", s("Blue")), ("speed", f(3.0))],
+        0,
+    );
+
+    let mut manager = manager(map(vec![], vec![], vec![]));
+    manager.add_schema_parser(spinner_schema());
+
+    let element = manager.parse_dyn_element(&raw).unwrap();
+    let spinner = (element.as_ref() as &dyn Any)
+        .downcast_ref::<SchemaEntity>()
+        .unwrap();
+
+    assert_eq!(spinner.raw, raw);
+    assert_eq!(element.encode_raw(), raw);
+
+    assert_eq!(spinner.get("color"), Some(&s("Blue")));
+    assert_eq!(spinner.get("speed"), Some(&f(3.0)));
+    // Missing attributes fall back to the schema's default
+    assert_eq!(spinner.get("attached"), Some(&b(false)));
+    assert!(matches!(
+        spinner.get_as::<bool>("attached"),
+        Some(Ok(false))
+    ));
+    assert_eq!(spinner.raw.get_attribute("attached"), None);
+    assert_eq!(spinner.get("unknown"), None);
+}
+
+#[test]
+fn invalid_elements_fail_to_parse() {
+    let mut manager = manager(map(vec![], vec![], vec![]));
+    manager.add_schema_parser(spinner_schema());
+
+    let missing = entity("TestHelper/Spinner", vec![("speed", f(3.0))], 0);
+    assert!(manager.parse_dyn_element(&missing).is_err());
+
+    let wrong_kind = entity(
+        "TestHelper/Spinner",
+        vec![("color", s("Blue")), ("attached", EncodedVar::Int(1))],
+        0,
+    );
+    assert!(manager.parse_dyn_element(&wrong_kind).is_err());
+
+    // Unknown attributes only mean the schema is out of date
+    let unknown = entity(
+        "TestHelper/Spinner",
+        vec![("color", s("Blue")), ("newAttribute", i(2))],
+        0,
+    );
+    assert!(manager.parse_dyn_element(&unknown).is_ok());
+}
+
+#[test]
+fn schema_entity_fields() {
+    let mut schema = spinner_schema();
+    schema.add_attribute(AttributeSchema {
+        options: vec![s("slow"), s("fast")],
+        editable: false,
+        ..AttributeSchema::with_default("mode", s("slow"))
+    });
+
+    let mut manager = manager(map(vec![], vec![], vec![]));
+    manager.add_schema_parser(schema);
+
+    let raw = entity("TestHelper/Spinner", vec![("color", s("Blue"))], 0);
+    let mut element = manager.parse_dyn_element(&raw).unwrap();
+    let element = element.as_mut() as &mut dyn ErasedMapElement;
+
+    let info = element
+        .field_info()
+        .into_iter()
+        .map(|f| (f.attribute.into_owned(), f.kind, f.optional))
+        .collect::<Vec<_>>();
+    assert_eq!(info, [
+        ("color".to_owned(), FieldKind::String, false),
+        ("speed".to_owned(), FieldKind::Float, true),
+        ("attached".to_owned(), FieldKind::Bool, true),
+        ("mode".to_owned(), FieldKind::String, true),
+    ]);
+
+    // Missing attributes fall back to the schema's default, like `SchemaEntity::get`
+    assert_eq!(element.get_field("color"), Some(s("Blue")));
+    assert_eq!(element.get_field("speed"), Some(f(1.5)));
+    assert_eq!(element.get_field("x"), Some(f(8.0)));
+
+    element.set_field("speed", Some(i(2))).unwrap();
+    element.set_field("mode", Some(s("fast"))).unwrap();
+    element.set_field("attached", None).unwrap();
+    element
+        .set_field("newAttribute", Some(s("anything")))
+        .unwrap();
+    assert_eq!(element.get_field("speed"), Some(i(2)));
+    assert_eq!(element.get_field("mode"), Some(s("fast")));
+
+    assert!(matches!(
+        element.set_field("speed", Some(s("fast"))),
+        Err(FieldError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        element.set_field("attached", Some(i(1))),
+        Err(FieldError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        element.set_field("mode", Some(s("medium"))),
+        Err(FieldError::NotAnOption(_))
+    ));
+    assert!(matches!(
+        element.set_field("color", None),
+        Err(FieldError::Required(_))
+    ));
+
+    // Failed sets leave the element unchanged, so it still parses
+    assert!(manager.parse_dyn_element(&element.encode_raw()).is_ok());
+    let spinner = (element as &dyn Any)
+        .downcast_ref::<SchemaEntity>()
+        .unwrap();
+    assert_eq!(spinner.raw.get_attribute("speed"), Some(&i(2)));
+    assert_eq!(spinner.raw.get_attribute("color"), Some(&s("Blue")));
+}
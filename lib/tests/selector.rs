@@ -0,0 +1,169 @@
+use celeste_rs::maps::{
+    RawMap,
+    RawMapElement,
+    selector::{Selector, SelectorParseError},
+};
+
+mod common;
+use common::*;
+
+fn strawberry(order: i32) -> RawMapElement {
+    entity(
+        "strawberry",
+        vec![
+            ("winged", b(false)),
+            ("checkpointID", i(0)),
+            ("order", i(order)),
+        ],
+        0,
+    )
+}
+
+fn test_map() -> RawMapElement {
+    let mut first = level(
+        "a-00",
+        (0, 0),
+        vec![
+            strawberry(2),
+            entity("MyMod/Thing", vec![("flag", s("a]/b"))], 2),
+        ],
+        vec![],
+    );
+    // An unknown child, kept in the level's extra data
+    first
+        .children
+        .push(el("customData", vec![("flag", s("x"))], vec![]));
+
+    let second = level(
+        "b-00",
+        (320, 0),
+        vec![
+            strawberry(10),
+            entity("MyMod/Thing", vec![("flag", s("a<=b"))], 0),
+        ],
+        vec![],
+    );
+
+    map(vec![first, second], vec![], vec![])
+}
+
+fn select_raw(selector: &str) -> Vec<String> {
+    let mut map = RawMap::from_bytes(&map_bytes(test_map())).unwrap();
+    map.resolve_strings();
+
+    map.select(&selector.parse().unwrap())
+        .into_iter()
+        .map(|m| m.path.to_string())
+        .collect()
+}
+
+fn select_parsed(selector: &str) -> Vec<String> {
+    let root = manager(test_map()).parse_map().unwrap();
+
+    Selector::parse(selector)
+        .unwrap()
+        .select(&root)
+        .into_iter()
+        .map(|m| m.element.name().to_owned())
+        .collect()
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(Selector::parse(""), Err(SelectorParseError::EmptyStep));
+    assert_eq!(
+        Selector::parse("levels//level"),
+        Err(SelectorParseError::EmptyStep)
+    );
+    assert_eq!(
+        Selector::parse("level[]"),
+        Err(SelectorParseError::EmptyAttribute)
+    );
+    assert_eq!(
+        Selector::parse("level[=a]"),
+        Err(SelectorParseError::EmptyAttribute)
+    );
+    assert_eq!(
+        Selector::parse("level[name=a"),
+        Err(SelectorParseError::UnclosedFilter("level".to_owned()))
+    );
+    assert_eq!(
+        Selector::parse("level[name=\"a]"),
+        Err(SelectorParseError::UnclosedFilter("level".to_owned()))
+    );
+    assert_eq!(
+        Selector::parse("**[name=a]"),
+        Err(SelectorParseError::FilteredDescendants)
+    );
+    assert_eq!(
+        Selector::parse("level[name=a]x"),
+        Err(SelectorParseError::UnexpectedCharacter('x'))
+    );
+}
+
+#[test]
+fn equivalent_selectors() {
+    assert_eq!(
+        Selector::parse(" levels / level [ name = a-00 ] "),
+        Selector::parse("levels/level[name=a-00]")
+    );
+    assert_eq!(
+        Selector::parse("level[name=\"a-00\"]"),
+        Selector::parse("level[name=a-00]")
+    );
+    assert_ne!(
+        Selector::parse("level[name!=a]"),
+        Selector::parse("level[name=a]")
+    );
+    assert_eq!(
+        Selector::parse("level[\"flag\"=\"a<=b\"]"),
+        Selector::parse("level[flag=a\"<=\"b]")
+    );
+    assert_ne!(
+        Selector::parse("level[x<=1]"),
+        Selector::parse("level[x<1]")
+    );
+}
+
+#[test]
+fn filters() {
+    assert_eq!(select_raw("levels/level[name^=b-]"), ["levels[1]/level[1]"]);
+    assert_eq!(select_raw("levels/level[name$=00]").len(), 2);
+    assert_eq!(select_raw("levels/level[name*=-0]").len(), 2);
+    assert_eq!(select_raw("levels/level[name!=a-00]"), [
+        "levels[1]/level[1]"
+    ]);
+    assert_eq!(select_raw("levels/level[x>=320]"), ["levels[1]/level[1]"]);
+    assert_eq!(select_raw("levels/level[x<320]"), ["levels[1]/level[0]"]);
+
+    // Numbers are compared as numbers, not strings
+    assert_eq!(select_raw("**/strawberry[order>3]").len(), 1);
+    assert_eq!(select_raw("**/strawberry[order=2.0]").len(), 1);
+    assert_eq!(select_raw("**/strawberry[order<x]").len(), 0);
+
+    // Quoted values can hold `]` and `/`
+    assert_eq!(select_raw("**/*[flag=\"a]/b\"]").len(), 1);
+    assert_eq!(select_raw("**/*[flag]").len(), 3);
+
+    // Operators inside quotes are part of the value
+    assert_eq!(select_raw("**/*[flag=\"a<=b\"]"), ["levels[1]/level[1]/\
+                                                    entities[2]/MyMod/\
+                                                    Thing[1]"]);
+    assert_eq!(select_raw("**/*[flag^=\"a<\"]").len(), 1);
+    assert_eq!(select_raw("**/node").len(), 2);
+}
+
+#[test]
+fn parsed_maps_include_extra_and_raw_children() {
+    assert_eq!(select_parsed("levels/level/customData"), ["customData"]);
+    assert_eq!(select_parsed("**/*[flag]"), [
+        "customData",
+        "MyMod/Thing",
+        "MyMod/Thing"
+    ]);
+    assert_eq!(select_parsed("**/*[flag=\"a<=b\"]"), ["MyMod/Thing"]);
+    assert_eq!(select_parsed("levels/level[name=b-00]"), ["level"]);
+    // The nodes of the unparsed entity
+    assert_eq!(select_parsed("levels/level/entities/*/node").len(), 2);
+    assert_eq!(select_parsed("**/strawberry[order>3]"), ["strawberry"]);
+}
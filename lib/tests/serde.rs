@@ -0,0 +1,124 @@
+#![cfg(feature = "serde")]
+
+use std::any::Any;
+
+use celeste_rs::maps::{
+    ErasedMapElement,
+    RawMapElement,
+    elements::MapRoot,
+    schema::{AttributeKind, AttributeSchema, EntitySchema, SchemaEntity},
+};
+
+mod common;
+use common::*;
+
+fn test_map() -> RawMapElement {
+    let mut root = map(
+        vec![level(
+            "a-00",
+            (0, 0),
+            vec![
+                entity(
+                    "strawberry",
+                    vec![("winged", b(true)), ("checkpointID", i(0)), ("order", i(3))],
+                    2,
+                ),
+                entity(
+                    "spikesUp",
+                    vec![("type", s("cliffside")), ("width", i(16))],
+                    0,
+                ),
+                // Not parsed, kept as a raw element
+                entity("MyMod/Thing", vec![("speed", f(1.5)), ("flag", s("x"))], 1),
+            ],
+            vec![trigger("MyMod/Trigger", vec![("count", i(4))], 0)],
+        )],
+        vec![
+            effect("stars", vec![("only", s("*")), ("scrollx", f(0.5))]),
+            effect("MyMod/Effect", vec![("color", s("ff00ff"))]),
+        ],
+        vec![],
+    );
+    root.children[0] = el("Filler", vec![], vec![el(
+        "rect",
+        vec![("x", i(-4)), ("y", i(0)), ("w", i(4)), ("h", i(2))],
+        vec![],
+    )]);
+    root
+}
+
+#[test]
+fn json_round_trip() {
+    let root = manager(test_map()).parse_map().unwrap();
+
+    let json = serde_json::to_string(&root).unwrap();
+    let read = serde_json::from_str::<MapRoot>(&json).unwrap();
+
+    assert_eq!(read, root);
+    assert_eq!(
+        (&read as &dyn ErasedMapElement).encode_raw(),
+        (&root as &dyn ErasedMapElement).encode_raw()
+    );
+
+    // Dynamic elements are tagged with their element name
+    let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    let entities = &value["levels"]["levels"][0]["entities"]["entities"];
+    assert_eq!(entities[0]["_name"], "strawberry");
+    assert_eq!(entities[2]["_name"], "MyMod/Thing");
+    assert_eq!(entities[2]["speed"], 1.5);
+}
+
+#[test]
+fn raw_element_round_trip() {
+    let raw = entity("MyMod/Thing", vec![("speed", f(1.5)), ("count", i(-3))], 2);
+
+    let json = serde_json::to_string(&raw).unwrap();
+    assert_eq!(serde_json::from_str::<RawMapElement>(&json).unwrap(), raw);
+
+    // Untyped integers are always read back as ints
+    let raw = el("MyMod/Thing", vec![("byte", byte(4))], vec![]);
+    let json = serde_json::to_string(&raw).unwrap();
+    assert_eq!(
+        serde_json::from_str::<RawMapElement>(&json).unwrap(),
+        el("MyMod/Thing", vec![("byte", i(4))], vec![])
+    );
+}
+
+#[test]
+fn deserialize_with_registered_parsers() {
+    let mut manager = manager(test_map());
+    let root = manager.parse_map().unwrap();
+    let json = serde_json::to_string(&root).unwrap();
+
+    let mut schema = EntitySchema::new("MyMod/Thing", false);
+    schema.add_attribute(AttributeSchema::required("speed", AttributeKind::Float));
+    schema.max_nodes = None;
+    manager.add_schema_parser(schema);
+
+    let read = manager
+        .deserialize_with_parsers::<MapRoot, _>(&mut serde_json::Deserializer::from_str(&json))
+        .unwrap();
+    let thing = &read.levels.levels[0].entities.as_ref().unwrap().entities[2];
+    assert!((thing.as_ref() as &dyn Any).is::<SchemaEntity>());
+
+    // The manager keeps its parsers and plain deserializing goes back to the default parsers
+    let raw = entity("MyMod/Thing", vec![("speed", f(1.5))], 0);
+    let parsed = manager.parse_entity(&raw).unwrap();
+    assert!((parsed.as_ref() as &dyn Any).is::<SchemaEntity>());
+    let read = serde_json::from_str::<MapRoot>(&json).unwrap();
+    let thing = &read.levels.levels[0].entities.as_ref().unwrap().entities[2];
+    assert!((thing.as_ref() as &dyn Any).is::<RawMapElement>());
+}
+
+#[test]
+fn parse_failures_are_errors() {
+    // checkpointID is required on strawberries
+    let raw = entity("strawberry", vec![("winged", b(true))], 0);
+    let root = manager(test_map()).parse_map().unwrap();
+    let json = serde_json::to_string(&root).unwrap();
+
+    let mut value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    value["levels"]["levels"][0]["entities"]["entities"][0] = serde_json::to_value(&raw).unwrap();
+    let error = serde_json::from_value::<MapRoot>(value).unwrap_err();
+    assert!(error.to_string().contains("checkpointID"), "{error}");
+}
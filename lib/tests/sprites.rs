@@ -0,0 +1,178 @@
+use celeste_rs::sprites::{SpriteBank, SpriteReadError};
+
+mod common;
+use common::*;
+
+const SPRITES: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Sprites>
+  <player_base path="characters/player/" start="idle">
+    <Justify x="0.5" y="1"/>
+    <Loop id="idle" path="idle" delay="0.1" frames="0-3,5*2"/>
+    <Anim id="walk" path="walk" delay="0.08" goto="idle"/>
+  </player_base>
+  <player copy="player_base">
+    <Anim id="walk" path="run" delay="0.06"/>
+    <Anim id="dash" path="dash"/>
+  </player>
+  <player_playback copy="player" color="ffffff80">
+    <Center/>
+  </player_playback>
+  <loop_a copy="loop_b"/>
+  <loop_b copy="loop_a">
+    <Anim id="spin" path="spin"/>
+  </loop_b>
+</Sprites>
+"#;
+
+fn ids(bank: &SpriteBank, id: &str) -> Vec<String> {
+    bank.animations(id)
+        .iter()
+        .map(|a| format!("{}:{}", a.id, a.path))
+        .collect()
+}
+
+#[test]
+fn parse_sprites() {
+    let bank = SpriteBank::parse(SPRITES).unwrap();
+    assert_eq!(bank.sprites.len(), 5);
+
+    let base = bank.get("player_base").unwrap();
+    assert_eq!(base.path.as_deref(), Some("characters/player/"));
+    assert_eq!(base.start.as_deref(), Some("idle"));
+    assert_eq!(base.justify, Some([0.5, 1.0]));
+    assert!(!base.center);
+
+    let idle = &base.animations[0];
+    assert!(idle.looping);
+    assert_eq!(idle.delay, Some(0.1));
+    assert_eq!(base.animation_path(idle), "characters/player/idle");
+    assert_eq!(base.animations[1].goto.as_deref(), Some("idle"));
+
+    let playback = bank.get("player_playback").unwrap();
+    assert_eq!(playback.copy.as_deref(), Some("player"));
+    assert_eq!(playback.attributes["color"], "ffffff80");
+    assert!(playback.center);
+}
+
+#[test]
+fn animations_follow_copy_chains() {
+    let bank = SpriteBank::parse(SPRITES).unwrap();
+
+    // The sprite's own animations replace copied ones with the same id
+    assert_eq!(ids(&bank, "player_playback"), [
+        "walk:run",
+        "dash:dash",
+        "idle:idle"
+    ]);
+    assert!(bank.has_animation("player_playback", "idle"));
+    assert!(!bank.has_animation("player_base", "dash"));
+
+    // Copy loops stop instead of going forever
+    assert_eq!(ids(&bank, "loop_a"), ["spin:spin"]);
+    assert!(ids(&bank, "missing").is_empty());
+}
+
+#[test]
+fn frame_indices() {
+    let bank = SpriteBank::parse(SPRITES).unwrap();
+    let mut idle = bank.get("player_base").unwrap().animations[0].clone();
+    assert_eq!(idle.frame_indices(), Some(vec![0, 1, 2, 3, 5, 5]));
+
+    idle.frames = Some("3-1, 7".to_owned());
+    assert_eq!(idle.frame_indices(), Some(vec![3, 2, 1, 7]));
+
+    idle.frames = Some("0-x".to_owned());
+    assert_eq!(idle.frame_indices(), None);
+
+    idle.frames = None;
+    assert_eq!(idle.frame_indices(), None);
+}
+
+#[test]
+fn parse_errors() {
+    assert!(matches!(
+        SpriteBank::parse("<Sprites><a><Anim path=\"x\"/></a></Sprites>"),
+        Err(SpriteReadError::MissingAttribute("id", id)) if id == "a"
+    ));
+    assert!(matches!(
+        SpriteBank::parse("<Sprites><a><Anim id=\"x\" delay=\"fast\"/></a></Sprites>"),
+        Err(SpriteReadError::InvalidFloat(_))
+    ));
+}
+
+#[test]
+fn merge_replaces_whole_sprites() {
+    let mut bank = SpriteBank::parse(SPRITES).unwrap();
+    let overrides =
+        SpriteBank::parse(r#"<Sprites><player path="mymod/player/"/><lamp/></Sprites>"#).unwrap();
+    bank.merge(&overrides);
+
+    assert_eq!(bank.sprites.len(), 6);
+    let player = bank.get("player").unwrap();
+    assert_eq!(player.path.as_deref(), Some("mymod/player/"));
+    assert!(player.copy.is_none());
+    assert!(bank.get("lamp").is_some());
+}
+
+const MOD_SPRITES: &str = r#"<Sprites>
+  <player_base path="mymod/player/">
+    <Anim id="idle" path="idle"/>
+  </player_base>
+</Sprites>"#;
+
+const MAP_SPRITES: &str = r#"<Sprites>
+  <player copy="player_base">
+    <Anim id="swim" path="swim"/>
+  </player>
+</Sprites>"#;
+
+#[test]
+fn mod_sprites_override_vanilla() {
+    let vanilla = SpriteBank::parse(SPRITES).unwrap();
+    let mut manager = mod_manager(vec![
+        ("Graphics/Sprites.xml", MOD_SPRITES.as_bytes().to_vec()),
+        ("Graphics/MyMod/Lake.xml", MAP_SPRITES.as_bytes().to_vec()),
+        (
+            "Maps/MyMod/1-Lake.bin",
+            map_bytes(map(vec![], vec![], vec![])),
+        ),
+        (
+            "Maps/MyMod/1-Lake.meta.yaml",
+            b"Sprites: Graphics/MyMod/Lake".to_vec(),
+        ),
+    ]);
+
+    let global = manager.sprites(&vanilla, None).unwrap();
+    assert_eq!(
+        global.get("player_base").unwrap().path.as_deref(),
+        Some("mymod/player/")
+    );
+    assert_eq!(ids(&global, "player"), [
+        "walk:run",
+        "dash:dash",
+        "idle:idle"
+    ]);
+
+    // The map's sprites go on top of the mod's
+    let lake = manager.sprites(&vanilla, Some("MyMod/1-Lake")).unwrap();
+    assert_eq!(ids(&lake, "player"), ["swim:swim", "idle:idle"]);
+
+    // The mod has no Portraits.xml so nothing is added
+    let portraits = manager.portraits(&SpriteBank::new(), None).unwrap();
+    assert!(portraits.sprites.is_empty());
+}
+
+#[test]
+fn nested_sprite_banks_are_ignored() {
+    let vanilla = SpriteBank::parse(SPRITES).unwrap();
+    let mut manager = mod_manager(vec![(
+        "Extra/Graphics/Sprites.xml",
+        MOD_SPRITES.as_bytes().to_vec(),
+    )]);
+
+    let sprites = manager.sprites(&vanilla, None).unwrap();
+    assert_eq!(
+        sprites.get("player_base").unwrap().path.as_deref(),
+        Some("characters/player/")
+    );
+}
@@ -0,0 +1,153 @@
+use std::io::Read;
+
+use celeste_rs::{
+    maps::{
+        RawMap,
+        RawMapElement,
+        reader::MapReadError,
+        stream::{MapEvent, MapStreamReader},
+    },
+    utils::binary::BinReadError,
+};
+
+mod common;
+use common::*;
+
+fn test_map() -> RawMapElement {
+    map(
+        vec![
+            level(
+                "a-00",
+                (0, 0),
+                vec![
+                    entity("spinner", vec![("attachToSolid", b(true))], 0),
+                    entity("zipMover", vec![("theme", s("Moon"))], 1),
+                ],
+                vec![trigger("cameraOffsetTrigger", vec![("cameraX", f(2.0))], 0)],
+            ),
+            level("a-01", (320, 0), vec![], vec![]),
+        ],
+        vec![effect("stars", vec![("only", s("*"))])],
+        vec![],
+    )
+}
+
+/// Yields at most one byte for every read
+struct ByteReader<'a>(&'a [u8]);
+
+impl Read for ByteReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((&byte, rest)), Some(out)) => {
+                *out = byte;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+fn names(events: &[MapEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            MapEvent::Enter { name } => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn read_root_matches_raw_map() {
+    let bytes = map_bytes(test_map());
+
+    let mut raw = RawMap::from_bytes(&bytes).unwrap();
+    raw.resolve_strings();
+
+    let stream = MapStreamReader::new(&bytes[..]).unwrap();
+    assert_eq!(stream.map_name(), "test");
+    assert_eq!(stream.read_root().unwrap(), raw.root_element);
+
+    let stream = MapStreamReader::new(ByteReader(&bytes)).unwrap();
+    assert_eq!(stream.read_root().unwrap(), raw.root_element);
+}
+
+#[test]
+fn events_walk_the_tree() {
+    let bytes = map_bytes(test_map());
+    let mut stream = MapStreamReader::new(ByteReader(&bytes)).unwrap();
+
+    let mut events = Vec::new();
+    let mut max_depth = 0;
+
+    while let Some(event) = stream.next_event().unwrap() {
+        max_depth = max_depth.max(stream.depth());
+        events.push(event);
+    }
+
+    let enters = names(&events);
+    let leaves = events
+        .iter()
+        .filter(|e| matches!(e, MapEvent::Leave))
+        .count();
+
+    assert_eq!(enters.len(), leaves);
+    assert_eq!(&enters[.. 4], ["Map", "Filler", "levels", "level"]);
+    assert_eq!(max_depth, 6);
+    assert!(events.iter().any(|e| matches!(
+        e,
+        MapEvent::Attribute { name, value } if name == "theme" && *value == s("Moon")
+    )));
+    assert_eq!(stream.depth(), 0);
+}
+
+#[test]
+fn skip_and_read_elements() {
+    let bytes = map_bytes(test_map());
+    let mut stream = MapStreamReader::new(&bytes[..]).unwrap();
+
+    let mut entered = Vec::new();
+    let mut triggers = None;
+
+    while let Some(event) = stream.next_event().unwrap() {
+        match event {
+            MapEvent::Enter { name } if name == "entities" => stream.skip_element().unwrap(),
+            MapEvent::Enter { name } if name == "triggers" && triggers.is_none() => {
+                assert_eq!(stream.path().collect::<Vec<_>>(), [
+                    "Map", "levels", "level", "triggers"
+                ]);
+                triggers = stream.read_element().unwrap();
+            }
+            MapEvent::Enter { name } => entered.push(name),
+            _ => (),
+        }
+    }
+
+    assert!(!entered.iter().any(|n| n == "spinner" || n == "zipMover"));
+    assert!(entered.iter().any(|n| n == "stars"));
+
+    let triggers = triggers.unwrap();
+    assert_eq!(triggers.children.len(), 1);
+    assert_eq!(triggers.children[0].get_attribute("cameraX"), Some(&f(2.0)));
+}
+
+#[test]
+fn truncated_maps_are_an_error() {
+    let bytes = map_bytes(test_map());
+
+    let stream = MapStreamReader::new(&bytes[.. bytes.len() - 3]).unwrap();
+    assert!(matches!(
+        stream.read_root(),
+        Err(MapReadError::BinError(BinReadError::EndOfBuffer))
+    ));
+
+    assert!(matches!(
+        MapStreamReader::new(&b"\x0bCELESTE MAP"[..]),
+        Err(MapReadError::BinError(BinReadError::EndOfBuffer))
+    ));
+    assert!(matches!(
+        MapStreamReader::new(&b"\x0bCELESTE PAM\x00\x00\x00"[..]),
+        Err(MapReadError::InvalidHeader(_))
+    ));
+}
@@ -0,0 +1,302 @@
+use std::any::Any;
+
+use celeste_rs::maps::{
+    RawMapElement,
+    elements::{
+        MapRoot,
+        entities::{MapEntity, vanilla_entities::GoldenBerry},
+    },
+    transform::{
+        AttributeMapping,
+        TileLayer,
+        TransformError,
+        offset_rooms,
+        rename_flag,
+        rename_tile,
+        renumber_ids,
+        replace_entity,
+    },
+    var_types::EncodedVar,
+};
+
+mod common;
+use common::*;
+
+fn map_with_filler() -> RawMapElement {
+    let mut root = map(
+        vec![
+            level("a-00", (0, 0), vec![], vec![]),
+            level("a-01", (320, 0), vec![], vec![]),
+        ],
+        vec![],
+        vec![],
+    );
+    root.children[0] = el("Filler", vec![], vec![el(
+        "rect",
+        vec![("x", i(80)), ("y", i(-2)), ("w", i(4)), ("h", i(4))],
+        vec![],
+    )]);
+    root
+}
+
+fn positions(root: &MapRoot) -> Vec<(f32, f32)> {
+    root.levels
+        .levels
+        .iter()
+        .map(|l| (l.x.as_f32(), l.y.as_f32()))
+        .collect()
+}
+
+fn filler(root: &MapRoot) -> (i64, i64) {
+    let rect = &root.filler.as_ref().unwrap().filler[0];
+    (rect.x.as_i64(), rect.y.as_i64())
+}
+
+#[test]
+fn offset_in_tiles() {
+    let mut root = manager(map_with_filler()).parse_map().unwrap();
+    let report = offset_rooms(&mut root, 16, -40);
+
+    assert_eq!(positions(&root), [(16.0, -40.0), (336.0, -40.0)]);
+    assert_eq!(filler(&root), (82, -7));
+    assert_eq!(report.len(), 3);
+    assert!(report.changes.iter().all(|c| c.element != "Map"));
+}
+
+#[test]
+fn offset_is_snapped_to_tiles() {
+    let mut root = manager(map_with_filler()).parse_map().unwrap();
+    let report = offset_rooms(&mut root, 13, -3);
+
+    // Rooms and filler stay lined up
+    assert_eq!(positions(&root), [(16.0, 0.0), (336.0, 0.0)]);
+    assert_eq!(filler(&root), (82, -2));
+
+    let snapped = &report.changes[0];
+    assert_eq!(snapped.element, "Map");
+    assert_eq!(
+        snapped.description,
+        "snapped the offset (13, -3) to (16, 0)"
+    );
+
+    let mut root = manager(map_with_filler()).parse_map().unwrap();
+    offset_rooms(&mut root, -12, -5);
+    assert_eq!(positions(&root), [(-8.0, -8.0), (312.0, -8.0)]);
+    assert_eq!(filler(&root), (79, -3));
+}
+
+fn with_id(mut raw: RawMapElement, id: i32) -> RawMapElement {
+    raw.set_attribute("id", i(id));
+    raw
+}
+
+fn strawberry(id: i32) -> RawMapElement {
+    with_id(
+        entity(
+            "strawberry",
+            vec![
+                ("winged", b(false)),
+                ("checkpointID", i(2)),
+                ("order", i(0)),
+            ],
+            0,
+        ),
+        id,
+    )
+}
+
+fn entity_ids(root: &MapRoot) -> Vec<Vec<i64>> {
+    root.levels
+        .levels
+        .iter()
+        .map(|l| {
+            let entities = l.entities.iter().flat_map(|e| &e.entities);
+            let triggers = l.triggers.iter().flat_map(|t| &t.triggers);
+
+            entities
+                .map(|e| e.id().as_i64())
+                .chain(triggers.map(|t| t.get_field("id").unwrap().int().unwrap().as_i64()))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn rename_tiles() {
+    let mut root = manager(map_with_filler()).parse_map().unwrap();
+    let report = rename_tile(&mut root, TileLayer::Foreground, '0', '3');
+
+    for level in &root.levels.levels {
+        assert_eq!(level.solids.inner_text.as_deref(), Some("333\n3\n"));
+    }
+    assert_eq!(report.len(), 2);
+    assert_eq!(
+        report.changes[0].to_string(),
+        "a-00/solids: replaced 4 '0' tiles with '3'"
+    );
+
+    // The background grids are empty
+    assert!(rename_tile(&mut root, TileLayer::Background, '0', '3').is_empty());
+}
+
+#[test]
+fn replace_with_mapping() {
+    let root = map(
+        vec![level(
+            "a-00",
+            (0, 0),
+            vec![strawberry(1), strawberry(2)],
+            vec![],
+        )],
+        vec![],
+        vec![],
+    );
+    let manager = manager(root);
+    let mut root = manager.parse_map().unwrap();
+
+    let mapping = AttributeMapping::new()
+        .rename("checkpointID", "oldCheckpoint")
+        .remove("order")
+        .set("moon", true);
+    let report = replace_entity(&mut root, &manager, "strawberry", "goldenBerry", &mapping);
+
+    assert_eq!(report.len(), 2);
+    assert!(report.errors.is_empty());
+    assert_eq!(
+        report.changes[1].to_string(),
+        "a-00/goldenBerry#2: replaced strawberry"
+    );
+
+    let entities = &root.levels.levels[0].entities.as_ref().unwrap().entities;
+    let golden = (entities[0].as_ref() as &dyn Any)
+        .downcast_ref::<MapEntity<GoldenBerry>>()
+        .unwrap();
+    assert_eq!(golden.entity.winged, Some(false));
+    assert_eq!(golden.entity.moon, Some(true));
+    // Renamed attributes without a field are kept as extra data
+    assert_eq!(
+        entities[0].get_field("oldCheckpoint"),
+        Some(EncodedVar::Int(2))
+    );
+    assert_eq!(entities[0].get_field("order"), None);
+
+    // Entities that don't parse after the change are kept raw
+    let report = replace_entity(
+        &mut root,
+        &manager,
+        "goldenBerry",
+        "lightning",
+        &AttributeMapping::new(),
+    );
+    assert_eq!(report.errors.len(), 2);
+    let entities = &root.levels.levels[0].entities.as_ref().unwrap().entities;
+    assert!((entities[0].as_ref() as &dyn Any).is::<RawMapElement>());
+    assert_eq!(entities[0].name(), "lightning");
+}
+
+#[test]
+fn rename_flags() {
+    let root = map(
+        vec![level(
+            "a-00",
+            (0, 0),
+            vec![entity(
+                "MyMod/Door",
+                vec![("flag", s("old")), ("name", s("old"))],
+                0,
+            )],
+            vec![trigger(
+                "MyMod/FlagTrigger",
+                vec![
+                    ("flags", s("a,!old, old ,b")),
+                    ("onlyIfFlag", s("older")),
+                    ("flagCount", i(2)),
+                ],
+                0,
+            )],
+        )],
+        vec![],
+        vec![],
+    );
+    let manager = manager(root);
+    let mut root = manager.parse_map().unwrap();
+
+    let report = rename_flag(&mut root, &manager, "old", "new");
+
+    let level = &root.levels.levels[0];
+    let door = &level.entities.as_ref().unwrap().entities[0];
+    assert_eq!(door.get_field("flag"), Some(s("new")));
+    assert_eq!(door.get_field("name"), Some(s("old")));
+
+    // Inverted flags keep their `!` and similar names are left alone
+    let trigger = &level.triggers.as_ref().unwrap().triggers[0];
+    assert_eq!(trigger.get_field("flags"), Some(s("a,!new,new,b")));
+    assert_eq!(trigger.get_field("onlyIfFlag"), Some(s("older")));
+
+    assert_eq!(
+        report
+            .changes
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>(),
+        [
+            "a-00/MyMod/Door#1: renamed flag in flag",
+            "a-00/MyMod/FlagTrigger#1: renamed flag in flags"
+        ]
+    );
+}
+
+#[test]
+fn renumber_duplicate_ids() {
+    let root = map(
+        vec![
+            level("a-00", (0, 0), vec![strawberry(1), strawberry(1)], vec![
+                with_id(trigger("MyMod/Trigger", vec![], 0), 5),
+            ]),
+            level("b-00", (320, 0), vec![strawberry(3)], vec![trigger(
+                "MyMod/Trigger",
+                vec![],
+                0,
+            )]),
+        ],
+        vec![],
+        vec![],
+    );
+    let manager = manager(root);
+    let mut root = manager.parse_map().unwrap();
+
+    let report = renumber_ids(&mut root, &manager).unwrap();
+
+    // The first use of an id keeps it, the rest count up from the highest id
+    assert_eq!(entity_ids(&root), [vec![1, 6, 5], vec![3, 7]]);
+    assert_eq!(report.len(), 2);
+    assert_eq!(
+        report.changes[1].to_string(),
+        "b-00/MyMod/Trigger#7: renumbered from 1"
+    );
+
+    // Nothing left to renumber
+    assert!(renumber_ids(&mut root, &manager).unwrap().is_empty());
+}
+
+#[test]
+fn renumber_ids_out_of_range() {
+    let root = map(
+        vec![level(
+            "a-00",
+            (0, 0),
+            vec![strawberry(i32::MAX), strawberry(1), strawberry(1)],
+            vec![],
+        )],
+        vec![],
+        vec![],
+    );
+    let manager = manager(root);
+    let mut root = manager.parse_map().unwrap();
+
+    assert_eq!(
+        renumber_ids(&mut root, &manager).unwrap_err(),
+        TransformError::IdOutOfRange(i32::MAX as i64 + 1)
+    );
+    assert_eq!(entity_ids(&root), [vec![i32::MAX as i64, 1, 1]]);
+}
@@ -0,0 +1,194 @@
+use celeste_rs::{
+    maps::{
+        RawMapElement,
+        elements::entities::{
+            ErasedEntity,
+            vanilla_entities::{Cassette, Strawberry},
+        },
+        schema::EntitySchema,
+    },
+    mods::usage::{ElementKind, ElementLocation, ElementSource},
+    utils::num::{Float, Integer},
+};
+
+mod common;
+use common::*;
+
+fn lake_map() -> Vec<u8> {
+    map_bytes(map(
+        vec![
+            level(
+                "a-00",
+                (0, 0),
+                vec![
+                    entity(
+                        "strawberry",
+                        vec![("winged", b(false)), ("checkpointID", i(0))],
+                        0,
+                    ),
+                    entity("MyMod/Lamp", vec![], 0),
+                    entity("MyMod/Lamp", vec![], 0),
+                    entity("FrostHelper/IceSpinner", vec![], 0),
+                ],
+                vec![trigger("mymod/FlagTrigger", vec![], 0)],
+            ),
+            level(
+                "b-00",
+                (320, 0),
+                vec![entity("MyMod/Lamp", vec![], 0)],
+                vec![],
+            ),
+        ],
+        vec![],
+        vec![],
+    ))
+}
+
+fn location(room: &str) -> ElementLocation {
+    ElementLocation {
+        sid: "MyMod/1-Lake".to_owned(),
+        room: room.to_owned(),
+    }
+}
+
+#[test]
+fn usage_counts() {
+    let manager = mod_manager(vec![("Maps/MyMod/1-Lake.bin", lake_map())]);
+    let report = manager.collection().element_usage();
+    assert_eq!(report.elements.len(), 4);
+
+    let lamp = &report.elements[&(ElementKind::Entity, "MyMod/Lamp".to_owned())];
+    assert_eq!(lamp.count, 3);
+    assert_eq!(lamp.locations, [location("a-00"), location("b-00")]);
+    assert!(!lamp.parsed);
+
+    let strawberry = &report.elements[&(ElementKind::Entity, "strawberry".to_owned())];
+    assert_eq!(strawberry.count, 1);
+    assert!(strawberry.parsed);
+    assert_eq!(strawberry.source, ElementSource::Vanilla);
+
+    let trigger = &report.elements[&(ElementKind::Trigger, "mymod/FlagTrigger".to_owned())];
+    assert_eq!(trigger.locations, [location("a-00")]);
+
+    // Most used first
+    let unparsed = report
+        .unparsed()
+        .into_iter()
+        .map(|u| u.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(unparsed[0], "MyMod/Lamp");
+    assert_eq!(unparsed.len(), 3);
+    assert!(report.fallbacks().is_empty());
+}
+
+#[test]
+fn fallbacks_are_counted_apart() {
+    let mod_manager = mod_manager(vec![("Maps/MyMod/1-Lake.bin", lake_map())]);
+
+    // The maps were parsed without this parser, so the lamps were left raw
+    let mut manager = manager(map(vec![], vec![], vec![]));
+    manager.add_schema_parser(EntitySchema::new("MyMod/Lamp", false));
+    let report = mod_manager.collection().element_usage_with(&manager);
+
+    let lamp = &report.elements[&(ElementKind::Entity, "MyMod/Lamp".to_owned())];
+    assert!(lamp.parsed);
+    assert_eq!(lamp.fallbacks, 3);
+
+    let strawberry = &report.elements[&(ElementKind::Entity, "strawberry".to_owned())];
+    assert_eq!(strawberry.fallbacks, 0);
+
+    let fallbacks = report.fallbacks();
+    assert_eq!(fallbacks.len(), 1);
+    assert_eq!(fallbacks[0].name, "MyMod/Lamp");
+
+    let unparsed = report
+        .unparsed()
+        .into_iter()
+        .map(|u| u.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(unparsed, ["FrostHelper/IceSpinner", "mymod/FlagTrigger"]);
+}
+
+#[test]
+fn elements_are_attributed_to_mods() {
+    let manager = mod_manager(vec![("Maps/MyMod/1-Lake.bin", lake_map())]);
+    let mut report = manager.collection().element_usage();
+
+    // Prefixes are matched ignoring case
+    let from_mod = report
+        .from_mod("MyMod")
+        .into_iter()
+        .map(|u| u.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(from_mod, ["MyMod/Lamp", "mymod/FlagTrigger"]);
+
+    let unknown = report.unknown();
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(
+        unknown[0].source,
+        ElementSource::Unknown("FrostHelper".to_owned())
+    );
+
+    // Attributing to a helper only changes what wasn't attributed yet
+    let helper = named_mod("FrostHelper", vec![]);
+    report.attribute(helper.mods());
+    report.attribute(helper.mods());
+
+    assert!(report.unknown().is_empty());
+    let spinner = &report.elements[&(ElementKind::Entity, "FrostHelper/IceSpinner".to_owned())];
+    assert_eq!(
+        spinner.source,
+        ElementSource::Prefix("FrostHelper".to_owned())
+    );
+    assert_eq!(report.from_mod("MyMod").len(), 2);
+}
+
+#[test]
+fn raw_entity_fields() {
+    let mut raw = entity("MyMod/Lamp", vec![("width", i(16))], 0);
+    let lamp = &raw as &dyn ErasedEntity;
+
+    assert_eq!(lamp.id(), Integer::I32(1));
+    assert_eq!((lamp.x(), lamp.y()), (Float::F32(8.0), Float::F32(16.0)));
+    assert_eq!(lamp.width(), Some(Integer::I32(16)));
+    assert_eq!(lamp.height(), None);
+    assert_eq!(lamp.kind(), "RawMapElement");
+    assert!(!lamp.is_parsed());
+    assert_eq!(lamp.as_raw(), Some(&raw));
+
+    // Missing or mistyped fields fall back to 0
+    raw.set_attribute("x", s("left"));
+    raw.remove_attribute("id");
+    let lamp = &raw as &dyn ErasedEntity;
+    assert_eq!(lamp.x(), Float::F32(0.0));
+    assert_eq!(lamp.id(), Integer::I32(0));
+
+    let cloned = lamp.clone_entity();
+    assert_eq!(cloned.as_raw(), Some(&raw as &RawMapElement));
+}
+
+#[test]
+fn downcast_entities() {
+    let raw = entity(
+        "strawberry",
+        vec![("winged", b(false)), ("checkpointID", i(0))],
+        0,
+    );
+    let mut strawberry = manager(map(vec![], vec![], vec![]))
+        .parse_entity(&raw)
+        .unwrap();
+
+    assert!(strawberry.is_parsed());
+    assert!(strawberry.downcast::<Cassette>().is_none());
+    assert_eq!(
+        strawberry.downcast::<Strawberry>().unwrap().entity.checkpoint_id,
+        Integer::I32(0)
+    );
+
+    strawberry.downcast_mut::<Strawberry>().unwrap().entity.winged = true;
+    assert!(strawberry.downcast::<Strawberry>().unwrap().entity.winged);
+    assert!(strawberry.downcast_mut::<Cassette>().is_none());
+
+    let lamp = &raw as &dyn ErasedEntity;
+    assert!(lamp.downcast::<Strawberry>().is_none());
+}
@@ -0,0 +1,808 @@
+//! Checks that every vanilla entity, trigger and styleground effect is parsed into a typed struct
+
+use std::any::Any;
+
+use celeste_rs::maps::{
+    ErasedMapElement,
+    RawMapElement,
+    elements::{
+        NodeList,
+        entities::{
+            EntityData,
+            ErasedEntity,
+            MapEntity,
+            vanilla_entities::{
+                BadelineBoost,
+                BirdPath,
+                Bridge,
+                Cassette,
+                ReflectionHeartStatue,
+                Strawberry,
+                SummitGemManager,
+                Tentacles,
+            },
+        },
+        style::{Apply, Backgrounds, Stars, TentaclesEffect},
+    },
+    var_types::EncodedVar,
+    visitor::{MapVisitor, visit},
+};
+
+mod common;
+use common::*;
+
+fn vanilla_entities() -> Vec<RawMapElement> {
+    vec![
+        entity("spikesUp", vec![], 0),
+        entity("spikesDown", vec![], 0),
+        entity("spikesLeft", vec![], 0),
+        entity("spikesRight", vec![], 0),
+        entity("jumpThru", vec![], 0),
+        entity("wire", vec![("above", b(false))], 1),
+        entity(
+            "strawberry",
+            vec![
+                ("winged", b(false)),
+                ("checkpointID", i(0)),
+                ("order", i(1)),
+                ("moon", b(false)),
+            ],
+            2,
+        ),
+        entity(
+            "lightbeam",
+            vec![("rotation", i(0)), ("flag", s("lightbeam_flag"))],
+            0,
+        ),
+        entity("cassette", vec![], 2),
+        entity("cassetteBlock", vec![("index", i(0))], 0),
+        entity(
+            "dashBlock",
+            vec![
+                ("permanent", b(false)),
+                ("tiletype", s("3")),
+                ("blendin", b(false)),
+                ("canDash", b(false)),
+            ],
+            0,
+        ),
+        entity("bonfire", vec![("mode", s("lit"))], 0),
+        entity("npc", vec![("npc", s("granny_00_house"))], 0),
+        entity("coverupWall", vec![("tiletype", s("3"))], 0),
+        entity("memorial", vec![("dreaming", b(false))], 0),
+        entity("birdForsakenCityGem", vec![], 2),
+        entity("fallingBlock", vec![("tiletype", s("3"))], 0),
+        entity("fakeWall", vec![("tiletype", s("3"))], 0),
+        entity("spring", vec![], 0),
+        entity("zipMover", vec![], 1),
+        entity("refill", vec![], 0),
+        entity(
+            "goldenBerry",
+            vec![("winged", b(true)), ("moon", b(false))],
+            0,
+        ),
+        entity("crumbleBlock", vec![("texture", s("default"))], 0),
+        entity(
+            "checkpoint",
+            vec![
+                ("bg", s("")),
+                ("inventory", s("")),
+                ("dreaming", b(false)),
+                ("coreMode", s("None")),
+                ("allowOrigin", b(true)),
+            ],
+            0,
+        ),
+        entity("darkChaser", vec![("canChangeMusic", b(true))], 0),
+        entity("bounceBlock", vec![("notCoreMode", b(false))], 0),
+        entity("introCar", vec![("hasRoadAndBarriers", b(true))], 0),
+        entity("glassBlock", vec![("sinks", b(true))], 0),
+        entity(
+            "slider",
+            vec![("clockwise", b(true)), ("surface", s("Floor"))],
+            0,
+        ),
+        entity(
+            "rotatingPlatforms",
+            vec![("platforms", i(4)), ("clockwise", b(false))],
+            1,
+        ),
+        entity("fakeHeart", vec![("color", s("Random"))], 0),
+        entity(
+            "tentacles",
+            vec![("fear_distance", s("close")), ("slide_until", byte(0))],
+            1,
+        ),
+        entity(
+            "glider",
+            vec![("bubble", b(false)), ("tutorial", b(false))],
+            0,
+        ),
+        entity("flingBirdIntro", vec![("crashes", b(false))], 1),
+        entity("exitBlock", vec![("tileType", s("3"))], 0),
+        entity("templeCrackedBlock", vec![("persistent", b(false))], 0),
+        entity("clothesline", vec![], 1),
+        entity("redBlocks", vec![("inverted", b(false))], 0),
+        entity("door", vec![("type", s("wood"))], 0),
+        entity(
+            "dashSwitchV",
+            vec![
+                ("ceiling", b(false)),
+                ("persistent", b(false)),
+                ("sprite", s("default")),
+            ],
+            0,
+        ),
+        entity("spinner", vec![("attachToSolid", b(false))], 0),
+        entity(
+            "conditionBlock",
+            vec![
+                ("tileType", s("3")),
+                ("condition", s("Key")),
+                ("conditionID", s("1:7")),
+            ],
+            0,
+        ),
+        entity(
+            "floatySpaceBlock",
+            vec![("tiletype", s("3")), ("disableSpawnOffset", b(false))],
+            0,
+        ),
+        entity(
+            "soundSource",
+            vec![("sound", s("event:/env/local/campfire_loop"))],
+            0,
+        ),
+        entity(
+            "lockBlock",
+            vec![("stepMusicProgress", b(false)), ("sprite", s("wood"))],
+            0,
+        ),
+        entity("seeker", vec![], 1),
+        entity(
+            "dashSwitchH",
+            vec![
+                ("leftSide", b(false)),
+                ("persistent", b(false)),
+                ("sprite", s("default")),
+            ],
+            0,
+        ),
+        entity("summitcheckpoint", vec![("number", byte(0))], 0),
+        entity("summitgem", vec![("gem", byte(0))], 0),
+        entity("summitGemManager", vec![], 6),
+        entity(
+            "fireBall",
+            vec![("amount", byte(0)), ("offset", f(0.0)), ("speed", f(0.0))],
+            1,
+        ),
+        entity("flingBird", vec![("waiting", b(false))], 1),
+        entity(
+            "coreModeToggle",
+            vec![
+                ("onlyFire", b(false)),
+                ("onlyIce", b(false)),
+                ("persistent", b(false)),
+            ],
+            0,
+        ),
+        entity("heartGemDoor", vec![("requires", byte(0))], 1),
+        entity("eyebomb", vec![("right", b(false))], 0),
+        entity(
+            "templeGate",
+            vec![("type", s("CloseBehindPlayer")), ("sprite", s("default"))],
+            0,
+        ),
+        entity("playbackTutorial", vec![("tutorial", s("combo"))], 1),
+        entity("starJumpBlock", vec![("sinks", b(false))], 0),
+        entity("seekerStatue", vec![("hatch", s("Distance"))], 1),
+        entity(
+            "finalBoss",
+            vec![
+                ("patternIndex", byte(0)),
+                ("cameraPastY", byte(0)),
+                ("dialog", b(false)),
+                ("startHit", b(false)),
+                ("cameraLockY", b(false)),
+            ],
+            1,
+        ),
+        entity("cloud", vec![("fragile", b(false))], 0),
+        entity("bigWaterfall", vec![("layer", s("FG"))], 0),
+        entity("wallSpringLeft", vec![], 0),
+        entity("coreMessage", vec![("line", byte(0))], 0),
+        entity("introCrusher", vec![], 1),
+        entity("key", vec![], 1),
+        entity(
+            "hahaha",
+            vec![("ifset", s("hahaha")), ("triggerLaughSfx", b(false))],
+            1,
+        ),
+        entity("badelineBoost", vec![("lockCamera", b(false))], 1),
+        entity("wallSpringRight", vec![], 0),
+        entity(
+            "crumbleWallOnRumble",
+            vec![("blendin", b(false)), ("persistent", b(false))],
+            0,
+        ),
+        entity("risingLava", vec![("intro", b(false))], 0),
+        entity("greenBlocks", vec![("inverted", b(false))], 0),
+        entity(
+            "lightningBlock",
+            vec![
+                ("flag", b(false)),
+                ("music", s("event:/music/lvl5/mirror")),
+                ("music_progress", i(0)),
+                ("flipX", b(false)),
+            ],
+            0,
+        ),
+        entity(
+            "birdPath",
+            vec![("only_once", b(false)), ("onlyIfLeft", b(false))],
+            2,
+        ),
+        entity("cutsceneNode", vec![("nodeName", s("cam"))], 0),
+        entity("clutterDoor", vec![("type", s("Red"))], 0),
+        entity("bigSpinner", vec![], 1),
+        entity("cliffside_flag", vec![("index", byte(0))], 0),
+        entity(
+            "ridgeGate",
+            vec![("strawberries", s("")), ("keys", s(""))],
+            1,
+        ),
+        entity("swapBlock", vec![], 1),
+        entity("movingPlatform", vec![], 1),
+        entity("switchGate", vec![("persistent", b(false))], 1),
+        entity("blackGem", vec![], 0),
+        entity(
+            "SummitBackgroundManager",
+            vec![("index", i(0)), ("intro_launch", b(false))],
+            0,
+        ),
+        entity(
+            "templeMirror",
+            vec![("reflectX", i(0)), ("reflectY", byte(0))],
+            0,
+        ),
+        entity(
+            "moveBlock",
+            vec![
+                ("direction", s("Right")),
+                ("canSteer", b(false)),
+                ("fast", b(false)),
+            ],
+            0,
+        ),
+        entity("dreamBlock", vec![], 1),
+        entity("wallBooster", vec![("left", b(false))], 0),
+        entity(
+            "water",
+            vec![("steamy", b(false)), ("hasBottom", b(false))],
+            0,
+        ),
+        entity(
+            "lightning",
+            vec![("perLevel", b(false)), ("moveTime", f(0.0))],
+            1,
+        ),
+        entity("moonCreature", vec![("number", byte(0))], 0),
+        entity("finalBossMovingBlock", vec![("nodeIndex", byte(0))], 1),
+        entity("rotateSpinner", vec![("clockwise", b(false))], 1),
+        entity("booster", vec![("red", b(false))], 0),
+        entity("bird", vec![("mode", s("Sleeping"))], 1),
+        entity("reflectionHeartStatue", vec![], 5),
+        entity("yellowBlocks", vec![("inverted", b(false))], 0),
+        entity("towerviewer", vec![], 1),
+        entity("cobweb", vec![], 1),
+        entity(
+            "infiniteStar",
+            vec![("shielded", b(false)), ("singleUse", b(false))],
+            0,
+        ),
+        entity("torch", vec![("startLit", b(false))], 0),
+        entity("colorSwitch", vec![("type", s("red"))], 0),
+        entity("cliffflag", vec![], 1),
+        entity("lamp", vec![("broken", b(false))], 0),
+        entity(
+            "powerSourceNumber",
+            vec![
+                ("number", byte(0)),
+                ("strawberries", s("")),
+                ("keys", s("")),
+            ],
+            0,
+        ),
+        entity("bridge", vec![], 2),
+        entity(
+            "crushBlock",
+            vec![("axes", s("both")), ("chillout", b(false))],
+            0,
+        ),
+        entity(
+            "trackSpinner",
+            vec![("startCenter", b(false)), ("speed", s("Normal"))],
+            1,
+        ),
+        entity("gondola", vec![("active", b(false))], 1),
+        entity("player", vec![("isDefaultSpawn", b(true))], 0),
+        entity(
+            "memorialTextController",
+            vec![("winged", b(true)), ("moon", b(false))],
+            1,
+        ),
+        entity("flutterbird", vec![], 0),
+        entity("plateau", vec![], 0),
+        entity("payphone", vec![], 0),
+        entity("wavedashmachine", vec![], 0),
+        entity("playbackBillboard", vec![], 0),
+        entity("sinkingPlatform", vec![], 0),
+        entity("playerSeeker", vec![], 0),
+        entity("picoconsole", vec![], 0),
+        entity("resortRoofEnding", vec![], 0),
+        entity("fireBarrier", vec![], 0),
+        entity("triggerSpikesUp", vec![], 0),
+        entity("triggerSpikesDown", vec![], 0),
+        entity("iceBlock", vec![], 0),
+        entity("waterfall", vec![], 0),
+        entity("resortLantern", vec![], 0),
+        entity("killbox", vec![], 0),
+        entity("touchSwitch", vec![], 0),
+        entity("clutterCabinet", vec![], 0),
+        entity("theoCrystal", vec![], 0),
+        entity("blockField", vec![("width", i(16)), ("height", i(8))], 0),
+        entity(
+            "invisibleBarrier",
+            vec![("width", i(8)), ("height", i(24))],
+            0,
+        ),
+        entity("templeMirrorPortal", vec![], 0),
+        entity("triggerSpikesRight", vec![], 0),
+        entity("bridgeFixed", vec![], 0),
+        entity("foregroundDebris", vec![], 0),
+        entity("oshirodoor", vec![], 0),
+        entity("theoCrystalHoldingBarrier", vec![], 0),
+        entity("templeEye", vec![], 0),
+        entity("theoCrystalPedestal", vec![], 0),
+        entity("floatingDebris", vec![], 0),
+        entity("triggerSpikesLeft", vec![], 0),
+        entity("kevins_pc", vec![], 0),
+        entity("sandwichLava", vec![], 0),
+        entity("trapdoor", vec![], 0),
+        entity("summitcloud", vec![], 0),
+        entity("friendlyGhost", vec![], 0),
+        entity(
+            "finalBossFallingBlock",
+            vec![("width", i(32)), ("height", i(16))],
+            0,
+        ),
+        entity("dreammirror", vec![], 0),
+        entity("seekerBarrier", vec![], 0),
+        entity("templeBigEyeball", vec![], 0),
+        entity("resortmirror", vec![], 0),
+        entity("whiteblock", vec![], 0),
+        entity("hanginglamp", vec![], 0),
+        entity("goldenBlock", vec![], 0),
+        entity("negaBlock", vec![], 0),
+        entity("starClimbController", vec![], 0),
+        entity("chaserBarrier", vec![], 0),
+        entity("dreamHeartGem", vec![], 0),
+        entity("SoundTest3d", vec![], 0),
+    ]
+}
+
+fn vanilla_triggers() -> Vec<RawMapElement> {
+    vec![
+        trigger("lookoutBlocker", vec![], 0),
+        trigger(
+            "musicTrigger",
+            vec![
+                ("track", s("event:/music/lvl1/main")),
+                ("resetOnLeave", b(false)),
+            ],
+            0,
+        ),
+        trigger("birdPathTrigger", vec![], 0),
+        trigger("blackholeStrength", vec![("strength", s("Strong"))], 0),
+        trigger(
+            "cameraOffsetTrigger",
+            vec![("cameraX", f(0.0)), ("cameraY", f(0.0))],
+            0,
+        ),
+        trigger("changeRespawnTrigger", vec![], 1),
+        trigger(
+            "minitextboxTrigger",
+            vec![
+                ("mode", s("OnPlayerEnter")),
+                ("dialog_id", s("ch1_entrance")),
+                ("only_once", b(false)),
+                ("death_count", i(0)),
+            ],
+            0,
+        ),
+        trigger("respawnTargetTrigger", vec![], 1),
+        trigger("noRefillTrigger", vec![("state", b(false))], 0),
+        trigger(
+            "bloomFadeTrigger",
+            vec![
+                ("bloomAddFrom", f(0.0)),
+                ("bloomAddTo", f(0.0)),
+                ("positionMode", s("LeftToRight")),
+            ],
+            0,
+        ),
+        trigger(
+            "cameraTargetTrigger",
+            vec![
+                ("lerpStrength", f(0.0)),
+                ("positionMode", s("NoEffect")),
+                ("xOnly", b(false)),
+                ("yOnly", b(false)),
+            ],
+            1,
+        ),
+        trigger(
+            "musicFadeTrigger",
+            vec![
+                ("direction", s("leftToRight")),
+                ("fadeA", f(0.0)),
+                ("fadeB", f(0.0)),
+            ],
+            0,
+        ),
+        trigger("goldenBerryCollectTrigger", vec![], 0),
+        trigger(
+            "cameraAdvanceTargetTrigger",
+            vec![
+                ("lerpStrengthX", f(0.0)),
+                ("lerpStrengthY", f(0.0)),
+                ("positionModeX", s("NoEffect")),
+                ("positionModeY", s("NoEffect")),
+                ("xOnly", b(false)),
+                ("yOnly", b(false)),
+            ],
+            1,
+        ),
+        trigger(
+            "lightFadeTrigger",
+            vec![
+                ("lightAddFrom", f(0.0)),
+                ("lightAddTo", f(0.0)),
+                ("positionMode", s("LeftToRight")),
+            ],
+            0,
+        ),
+        trigger("spawnFacingTrigger", vec![("facing", s("Right"))], 0),
+        trigger("windAttackTrigger", vec![], 0),
+        trigger(
+            "ambienceParamTrigger",
+            vec![
+                ("direction", s("LeftToRight")),
+                ("parameter", s("rain")),
+                ("from", f(0.0)),
+                ("to", f(0.0)),
+            ],
+            0,
+        ),
+        trigger("eventTrigger", vec![("event", s("end_city"))], 0),
+        trigger("detachFollowersTrigger", vec![("global", b(false))], 1),
+        trigger("checkpointBlockerTrigger", vec![], 0),
+        trigger("stopBoostTrigger", vec![], 0),
+        trigger(
+            "altMusicTrigger",
+            vec![
+                ("track", s("event:/music/lvl6/secret_room")),
+                ("resetOnLeave", b(false)),
+            ],
+            0,
+        ),
+        trigger("windTrigger", vec![("pattern", s("Left"))], 0),
+        trigger("creditsTrigger", vec![("event", s("credits"))], 0),
+        trigger(
+            "rumbleTrigger",
+            vec![("manualTrigger", b(false)), ("persistent", b(false))],
+            1,
+        ),
+        trigger("oshiroTrigger", vec![("state", b(false))], 0),
+        trigger(
+            "moonGlitchBackgroundTrigger",
+            vec![
+                ("duration", s("Short")),
+                ("stay", b(false)),
+                ("glitch", b(false)),
+            ],
+            0,
+        ),
+        trigger(
+            "interactTrigger",
+            vec![
+                ("event", s("ch5_mirror_reflection")),
+                ("event_2", s("")),
+                ("event_3", s("")),
+            ],
+            1,
+        ),
+    ]
+}
+
+fn vanilla_effects() -> Vec<RawMapElement> {
+    vec![
+        effect("parallax", vec![("texture", s("bgs/07/07/bg0"))]),
+        effect("snowBg", vec![]),
+        effect("snowFg", vec![]),
+        effect("stars", vec![]),
+        effect("windsnow", vec![]),
+        effect("dreamstars", vec![]),
+        effect("mirrorfg", vec![]),
+        effect("reflectionfg", vec![]),
+        effect("godrays", vec![]),
+        effect("tentacles", vec![
+            ("side", s("Right")),
+            ("color", s("")),
+            ("offset", f(0.0)),
+        ]),
+        effect("northernlights", vec![]),
+        effect("bossStarField", vec![]),
+        effect("petals", vec![]),
+        effect("heatwave", vec![]),
+        effect("coreStarsFg", vec![]),
+        effect("starfield", vec![("color", s("ffffff")), ("speed", f(1.0))]),
+        effect("planets", vec![("count", i(32)), ("size", s("small"))]),
+        effect("rain", vec![("color", s("161933"))]),
+        effect("stardust", vec![]),
+        effect("blackhole", vec![]),
+    ]
+}
+
+fn vanilla_map() -> RawMapElement {
+    map(
+        vec![level(
+            "a-00",
+            (0, 0),
+            vanilla_entities(),
+            vanilla_triggers(),
+        )],
+        vanilla_effects(),
+        vec![el(
+            "apply",
+            vec![("only", s("a-*")), ("flag", s("fg_flag"))],
+            vanilla_effects(),
+        )],
+    )
+}
+
+/// Collects the names of every element that was kept as a [RawMapElement]
+#[derive(Default)]
+struct RawCollector(Vec<String>);
+
+impl MapVisitor for RawCollector {
+    fn visit_entity(&mut self, entity: &dyn ErasedEntity) {
+        if !entity.is_parsed() {
+            self.0.push(entity.name().to_owned());
+        }
+    }
+
+    fn visit_trigger(&mut self, trigger: &dyn ErasedMapElement) {
+        if (trigger as &dyn Any).is::<RawMapElement>() {
+            self.0.push(trigger.name().to_owned());
+        }
+    }
+
+    fn visit_raw(&mut self, raw: &RawMapElement) {
+        self.0.push(raw.name().to_owned());
+    }
+}
+
+#[test]
+fn vanilla_elements_are_typed() {
+    let root = manager(vanilla_map()).parse_map().unwrap();
+
+    let mut collector = RawCollector::default();
+    visit(&mut collector, &root);
+
+    assert_eq!(collector.0, Vec::<String>::new());
+}
+
+/// Sorts the attributes of an element by name so elements can be compared regardless of attribute order
+fn sorted_attributes(raw: &RawMapElement) -> Vec<(String, EncodedVar)> {
+    let mut attributes = raw
+        .attributes
+        .iter()
+        .map(|a| (a.name.as_str().unwrap().to_owned(), a.value.clone()))
+        .collect::<Vec<_>>();
+    attributes.sort_by(|a, b| a.0.cmp(&b.0));
+    attributes
+}
+
+#[test]
+fn vanilla_entities_keep_their_attributes() {
+    let manager = manager(vanilla_map());
+
+    for mut raw in vanilla_entities().into_iter().chain(vanilla_triggers()) {
+        // An attribute none of the typed elements know about
+        raw.set_attribute("everestExtra", s("kept"));
+
+        let element = manager.parse_dyn_element(&raw).unwrap();
+        let encoded = element.encode_raw();
+
+        assert_eq!(
+            sorted_attributes(&encoded),
+            sorted_attributes(&raw),
+            "{} lost attributes",
+            raw.name()
+        );
+        assert_eq!(encoded.children, raw.children, "{} lost nodes", raw.name());
+    }
+}
+
+/// Checks that every node of the vanilla fixture for `T` is in its node list
+fn check_node_list<T: EntityData>() {
+    let manager = manager(vanilla_map());
+    let raw = vanilla_entities()
+        .into_iter()
+        .find(|raw| raw.name() == T::NAME)
+        .unwrap();
+
+    let entity = manager.parse_entity(&raw).unwrap();
+    let entity = (entity.as_ref() as &dyn Any)
+        .downcast_ref::<MapEntity<T>>()
+        .unwrap();
+
+    assert_eq!(entity.nodes().len(), raw.children.len(), "{}", T::NAME);
+    entity.validate_nodes().unwrap();
+}
+
+#[test]
+fn vanilla_node_lists() {
+    check_node_list::<Strawberry>();
+    check_node_list::<Cassette>();
+    check_node_list::<BadelineBoost>();
+    check_node_list::<BirdPath>();
+    check_node_list::<SummitGemManager>();
+    check_node_list::<ReflectionHeartStatue>();
+    check_node_list::<Bridge>();
+}
+
+#[test]
+fn vanilla_effects_keep_their_attributes() {
+    let manager = manager(vanilla_map());
+
+    for mut raw in vanilla_effects() {
+        raw.set_attribute("only", s("a-*"));
+        raw.set_attribute("everestExtra", s("kept"));
+
+        let encoded = manager.parse_styleground(&raw).unwrap().encode_raw();
+
+        assert_eq!(
+            sorted_attributes(&encoded),
+            sorted_attributes(&raw),
+            "{} lost attributes",
+            raw.name()
+        );
+    }
+}
+
+#[test]
+fn unit_elements_round_trip() {
+    let manager = manager(vanilla_map());
+
+    let elements = vec![
+        entity("player", vec![("everestExtra", s("kept"))], 0),
+        entity(
+            "memorialTextController",
+            vec![("winged", b(true)), ("everestExtra", s("kept"))],
+            2,
+        ),
+        entity("killbox", vec![("width", i(16)), ("customFlag", s("kept"))], 1),
+        entity("touchSwitch", vec![("everestExtra", i(3))], 1),
+        entity(
+            "blockField",
+            vec![("width", i(16)), ("height", i(8)), ("everestExtra", b(true))],
+            0,
+        ),
+        entity(
+            "invisibleBarrier",
+            vec![("width", i(8)), ("height", i(8)), ("everestExtra", f(0.5))],
+            2,
+        ),
+        entity(
+            "finalBossFallingBlock",
+            vec![("width", i(8)), ("height", i(8)), ("everestExtra", s("kept"))],
+            1,
+        ),
+        entity("theoCrystal", vec![("everestExtra", s("kept"))], 1),
+        trigger("stopBoostTrigger", vec![("everestExtra", s("kept"))], 1),
+        trigger("lookoutBlocker", vec![("everestExtra", s("kept"))], 0),
+        trigger("windAttackTrigger", vec![("everestExtra", s("kept"))], 2),
+    ];
+
+    for raw in elements {
+        let element = manager.parse_dyn_element(&raw).unwrap();
+        assert!(!(element.as_ref() as &dyn Any).is::<RawMapElement>());
+
+        let encoded = element.encode_raw();
+        assert_eq!(encoded.name, raw.name);
+        assert_eq!(sorted_attributes(&encoded), sorted_attributes(&raw));
+        assert_eq!(encoded.children, raw.children);
+    }
+}
+
+#[test]
+fn stylegrounds_keep_their_order() {
+    let manager = manager(vanilla_map());
+
+    let raw = el("Backgrounds", vec![("everestExtra", s("kept"))], vec![
+        effect("snowBg", vec![
+            ("only", s("*")),
+            ("exclude", s("b-00")),
+            ("flag", s("snow")),
+            ("notflag", s("no_snow")),
+            ("tag", s("weather")),
+            ("color", s("ffffff")),
+            ("fadex", s("0:320,1,0")),
+        ]),
+        effect("parallax", vec![("texture", s("bgs/04/bg0"))]),
+        el("apply", vec![("exclude", s("b-00"))], vec![
+            effect("tentacles", vec![("side", s("Left"))]),
+            effect("stars", vec![]),
+        ]),
+        effect("MaxHelpingHand/CustomPlanets", vec![("count", i(4))]),
+        effect("rain", vec![]),
+    ]);
+
+    let backgrounds = manager.parse_element::<Backgrounds>(&raw).unwrap();
+    let names = backgrounds
+        .stylegrounds
+        .iter()
+        .map(|s| s.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [
+        "snowBg",
+        "parallax",
+        "apply",
+        "MaxHelpingHand/CustomPlanets",
+        "rain"
+    ]);
+
+    let apply = (backgrounds.stylegrounds[2].as_ref() as &dyn Any)
+        .downcast_ref::<Apply>()
+        .unwrap();
+    assert!((apply.stylegrounds[0].as_ref() as &dyn Any).is::<TentaclesEffect>());
+    // Modded effects without a parser are kept as they are
+    assert!((backgrounds.stylegrounds[3].as_ref() as &dyn Any).is::<RawMapElement>());
+
+    let encoded = (&backgrounds as &dyn ErasedMapElement).encode_raw();
+    assert_eq!(encoded, raw);
+}
+
+#[test]
+fn effect_parsers_are_kept_apart() {
+    let manager = manager(vanilla_map());
+
+    // The same name is parsed as an entity or an effect depending on where it is
+    let raw = entity(
+        "tentacles",
+        vec![("fear_distance", s("close")), ("slide_until", byte(0))],
+        1,
+    );
+    let tentacles = manager.parse_entity(&raw).unwrap();
+    assert!((tentacles.as_ref() as &dyn Any).is::<MapEntity<Tentacles>>());
+    let tentacles = manager.parse_dyn_element(&raw).unwrap();
+    assert!((tentacles.as_ref() as &dyn Any).is::<MapEntity<Tentacles>>());
+
+    let raw = el("Backgrounds", vec![], vec![effect("tentacles", vec![])]);
+    let backgrounds = manager.parse_element::<Backgrounds>(&raw).unwrap();
+    assert!((backgrounds.stylegrounds[0].as_ref() as &dyn Any).is::<TentaclesEffect>());
+
+    // Single stylegrounds use the same order as a list of them
+    let tentacles = manager
+        .parse_styleground(&effect("tentacles", vec![]))
+        .unwrap();
+    assert!((tentacles.as_ref() as &dyn Any).is::<TentaclesEffect>());
+    let stars = manager.parse_styleground(&effect("stars", vec![])).unwrap();
+    assert!((stars.as_ref() as &dyn Any).is::<Stars>());
+
+    // Effects aren't used outside of stylegrounds
+    let stars = manager.parse_dyn_element(&effect("stars", vec![])).unwrap();
+    assert!((stars.as_ref() as &dyn Any).is::<RawMapElement>());
+}
@@ -0,0 +1,130 @@
+use std::any::Any;
+
+use celeste_rs::{
+    maps::{
+        ErasedMapElement,
+        RawMapElement,
+        elements::{
+            MapRoot,
+            entities::{ErasedEntity, MapEntity, vanilla_entities::Strawberry},
+            level::Level,
+        },
+        visitor::{MapVisitorMut, visit_mut, walk_element_mut},
+    },
+    utils::num::Float,
+};
+
+mod common;
+use common::*;
+
+fn test_map() -> RawMapElement {
+    map(
+        vec![
+            level(
+                "a-00",
+                (0, 0),
+                vec![
+                    entity(
+                        "strawberry",
+                        vec![("winged", b(true)), ("checkpointID", i(0))],
+                        0,
+                    ),
+                    entity("MyMod/Thing", vec![], 0),
+                ],
+                vec![trigger("MyMod/Trigger", vec![], 0)],
+            ),
+            level("b-00", (320, 0), vec![], vec![]),
+        ],
+        vec![],
+        vec![],
+    )
+}
+
+/// Grounds every strawberry and marks unknown entities and triggers
+#[derive(Default)]
+struct Edit {
+    /// Stops the walk at rooms not starting with this
+    room_prefix: &'static str,
+    levels: usize,
+    entities: usize,
+    triggers: usize,
+}
+
+impl MapVisitorMut for Edit {
+    fn visit_level(&mut self, level: &mut Level) {
+        self.levels += 1;
+
+        if level
+            .name
+            .as_str()
+            .is_some_and(|n| n.starts_with(self.room_prefix))
+        {
+            walk_element_mut(self, level)
+        }
+    }
+
+    fn visit_entity(&mut self, entity: &mut dyn ErasedEntity) {
+        self.entities += 1;
+        let any = entity as &mut dyn Any;
+
+        if let Some(strawberry) = any.downcast_mut::<MapEntity<Strawberry>>() {
+            strawberry.entity.winged = false;
+            strawberry.x += Float::from(8);
+        } else if let Some(raw) = any.downcast_mut::<RawMapElement>() {
+            raw.set_attribute("visited", true);
+        }
+    }
+
+    fn visit_trigger(&mut self, trigger: &mut dyn ErasedMapElement) {
+        self.triggers += 1;
+
+        if let Some(raw) = (trigger as &mut dyn Any).downcast_mut::<RawMapElement>() {
+            raw.set_attribute("visited", true);
+        }
+    }
+}
+
+fn entities(root: &MapRoot) -> Vec<RawMapElement> {
+    root.levels.levels[0]
+        .entities
+        .iter()
+        .flat_map(|e| &e.entities)
+        .map(|e| (e.as_ref() as &dyn ErasedMapElement).encode_raw())
+        .collect()
+}
+
+#[test]
+fn visitor_edits_entities() {
+    let mut root = manager(test_map()).parse_map().unwrap();
+    let mut edit = Edit::default();
+    visit_mut(&mut edit, &mut root);
+
+    assert_eq!((edit.levels, edit.entities, edit.triggers), (2, 2, 1));
+
+    let [strawberry, thing] = &entities(&root)[..] else {
+        unreachable!()
+    };
+    assert_eq!(strawberry.get_attribute("winged"), Some(&b(false)));
+    assert_eq!(strawberry.get_attribute("x"), Some(&f(16.0)));
+    assert_eq!(thing.get_attribute("visited"), Some(&b(true)));
+
+    let trigger = root.levels.levels[0].triggers.as_ref().unwrap().triggers[0]
+        .as_ref()
+        .encode_raw();
+    assert_eq!(trigger.get_attribute("visited"), Some(&b(true)));
+}
+
+#[test]
+fn visitor_can_stop_walking() {
+    let mut root = manager(test_map()).parse_map().unwrap();
+    let before = entities(&root);
+
+    let mut edit = Edit {
+        room_prefix: "b-",
+        ..Edit::default()
+    };
+    visit_mut(&mut edit, &mut root);
+
+    assert_eq!((edit.levels, edit.entities, edit.triggers), (2, 0, 0));
+    assert_eq!(entities(&root), before);
+}
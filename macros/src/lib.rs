@@ -1,5 +1,7 @@
 mod entity;
 mod map_element;
+mod nodes;
+mod reflect;
 mod trigger;
 mod yaml;
 
@@ -192,11 +194,14 @@ pub fn root_tag(
     .into()
 }
 
-#[proc_macro_derive(MapElement, attributes(child, name, dyn_child, rle, dyn_entities))]
+#[proc_macro_derive(
+    MapElement,
+    attributes(child, name, dyn_child, rle, dyn_entities, dyn_stylegrounds, extra)
+)]
 /// Derives the `MapElement` trait.
 ///
-/// Every field in the struct needs to be annotated with either `child`, `name`, or `dyn_child`.
-/// The struct itself also needs to be annotated with `name`
+/// Every field in the struct needs to be annotated with either `child`, `name`, `dyn_child`, or `extra`.
+/// The struct itself also needs to be annotated with `name`, and implement `Debug`, `Clone` and `PartialEq`
 ///
 /// #### name
 /// The name annotation is used to indicate the element's name in the binary file along with the name of any attributes.<br>
@@ -260,6 +265,45 @@ pub fn root_tag(
 ///     children: Vec<DynMapElement>,
 /// }
 /// ```
+///
+/// #### dyn_stylegrounds
+/// The `dyn_stylegrounds` annotation works like `dyn_child`, but the children are parsed with the effect parsers
+/// registered on the `MapManager`, so effects that share a name with an entity are parsed as the effect.<br>
+/// If this is used, there can be no fields annotated with `child`.
+/// ```ignore
+/// #[derive(MapElement)]
+/// #[name = "apply"]
+/// pub struct Apply {
+///     #[name = "flag"]
+///     flag: Option<ResolvableString>,
+///     #[dyn_stylegrounds]
+///     stylegrounds: Vec<DynMapElement>,
+/// }
+/// ```
+///
+/// #### extra
+/// The `extra` annotation is used on an `ExtraData` field to keep any attributes and children that
+/// don't have a field of their own, so they are written back out when encoding.
+/// The children are put back at the index they were read from.<br>
+/// There can only be one field marked with `extra` in a struct.
+/// ```ignore
+/// #[derive(MapElement)]
+/// #[name = "box"]
+/// pub struct Box {
+///     #[name = "width"]
+///     width: Integer,
+///     #[extra]
+///     extra: ExtraData,
+/// }
+/// ```
+///
+/// #### Visiting
+/// The `child`, `dyn_child`, `dyn_entities` and `dyn_stylegrounds` fields, along with the children kept in the `extra` field,
+/// are listed by `MapElement::child_elements` so that `MapVisitor`s can walk into them.
+///
+/// #### Reflection
+/// Every `name` field is listed by `MapElement::field_info` and can be read and written by its attribute name
+/// with `get_field` and `set_field`. Any other attribute names are read from and written to the `extra` field if there is one.
 pub fn map_element_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -275,31 +319,31 @@ pub fn map_element_derive(input: proc_macro::TokenStream) -> proc_macro::TokenSt
     .into()
 }
 
-#[proc_macro_derive(EntityData, attributes(node, name))]
-/// Derives the `Entity` trait.
+#[proc_macro_derive(EntityData, attributes(node, name, extra))]
+/// Derives the `EntityData` trait.
 ///
-/// Every field in the struct needs to be annotated with either `node` or `name`.
-/// The struct itself also needs to be annotated with `name`
+/// Every field in the struct needs to be annotated with either `node`, `name`, or `extra`.
+/// The struct itself also needs to be annotated with `name`, and implement `Debug`, `Clone` and `PartialEq`
 ///
 /// #### name
 /// The name annotation is used to indicate the entity's name in the binary file along with the name of any attributes.<br>
-/// For example: a struct representing an element called `box` would look like this
+/// For example: a struct representing an entity called `MyMod/Lamp` would look like this
 /// ```ignore
-/// #[derive(MapElement)]
-/// #[name = "box"]
-/// pub struct Box {}
+/// #[derive(Debug, Clone, PartialEq, EntityData)]
+/// #[name = "MyMod/Lamp"]
+/// pub struct Lamp {}
 ///
 /// ```
 ///
-/// If `Box` has the integer attributes `width` and `specialColorNumber` it would look like this:
+/// If `Lamp` has the attributes `brightness` and `onFlag` it would look like this:
 /// ```ignore
-/// #[derive(MapElement)]
-/// #[name = "box"]
-/// pub struct Box {
-///     #[name = "width"]
-///     width: Integer,
-///     #[name = "specialColorNumber"]
-///     special_color_number: Integer,
+/// #[derive(Debug, Clone, PartialEq, EntityData)]
+/// #[name = "MyMod/Lamp"]
+/// pub struct Lamp {
+///     #[name = "brightness"]
+///     brightness: Float,
+///     #[name = "onFlag"]
+///     on_flag: String,
 /// }
 /// ```
 ///
@@ -309,13 +353,44 @@ pub fn map_element_derive(input: proc_macro::TokenStream) -> proc_macro::TokenSt
 /// The `node` annotation is used to indicate that a field is a child and is `Node`, `Option<Node>`, or `Vec<Node>`.<br>
 /// There can only be one field marked with `node` in a struct.
 /// ```ignore
-/// #[derive(MapElement)]
-/// #[name = "box"]
-/// pub struct Box {
+/// #[derive(Debug, Clone, PartialEq, EntityData)]
+/// #[name = "MyMod/Lamp"]
+/// pub struct Lamp {
 ///     #[node]
-///     pos: Node
+///     target: Node
 /// }
 /// ```
+///
+/// The field is edited through the `NodeList` methods of `MapEntity`, and sets `MIN_NODES` and `MAX_NODES` from its type:
+/// a `Node` must be there, an `Option<Node>` can have up to one node, and a `Vec<Node>` can have any number.<br>
+/// A `Vec<Node>` can limit the number of nodes with `min` and `max`.
+/// ```ignore
+/// #[derive(Debug, Clone, PartialEq, EntityData)]
+/// #[name = "MyMod/Lamp"]
+/// pub struct Lamp {
+///     #[node(min = 1, max = 4)]
+///     path: Vec<Node>
+/// }
+/// ```
+///
+/// #### extra
+/// The `extra` annotation is used on an `ExtraData` field to keep any attributes and children that
+/// don't have a field of their own, so they are written back out when encoding.<br>
+/// There can only be one field marked with `extra` in a struct.
+/// ```ignore
+/// #[derive(Debug, Clone, PartialEq, EntityData)]
+/// #[name = "MyMod/Lamp"]
+/// pub struct Lamp {
+///     #[name = "brightness"]
+///     brightness: Float,
+///     #[extra]
+///     extra: ExtraData,
+/// }
+/// ```
+///
+/// #### Reflection
+/// Every `name` field is listed by `EntityData::field_info` and can be read and written by its attribute name,
+/// the same as with `MapElement`.
 pub fn entity_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -331,31 +406,31 @@ pub fn entity_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     .into()
 }
 
-#[proc_macro_derive(Trigger, attributes(node, name))]
+#[proc_macro_derive(Trigger, attributes(node, name, extra))]
 /// Derives the `Trigger` trait.
 ///
-/// Every field in the struct needs to be annotated with either `node` or `name`.
-/// The struct itself also needs to be annotated with `name`
+/// Every field in the struct needs to be annotated with either `node`, `name`, or `extra`.
+/// The struct itself also needs to be annotated with `name`, and implement `Debug`, `Clone` and `PartialEq`
 ///
 /// #### name
 /// The name annotation is used to indicate the trigger's name in the binary file along with the name of any attributes.<br>
-/// For example: a struct representing an element called `box` would look like this
+/// For example: a struct representing a trigger called `MyMod/FlagTrigger` would look like this
 /// ```ignore
-/// #[derive(MapElement)]
-/// #[name = "box"]
-/// pub struct Box {}
+/// #[derive(Debug, Clone, PartialEq, Trigger)]
+/// #[name = "MyMod/FlagTrigger"]
+/// pub struct FlagTrigger {}
 ///
 /// ```
 ///
-/// If `Box` has the integer attributes `width` and `specialColorNumber` it would look like this:
+/// If `FlagTrigger` has the attributes `flag` and `onlyOnce` it would look like this:
 /// ```ignore
-/// #[derive(MapElement)]
-/// #[name = "box"]
-/// pub struct Box {
-///     #[name = "width"]
-///     width: Integer,
-///     #[name = "specialColorNumber"]
-///     special_color_number: Integer,
+/// #[derive(Debug, Clone, PartialEq, Trigger)]
+/// #[name = "MyMod/FlagTrigger"]
+/// pub struct FlagTrigger {
+///     #[name = "flag"]
+///     flag: String,
+///     #[name = "onlyOnce"]
+///     only_once: bool,
 /// }
 /// ```
 ///
@@ -365,13 +440,44 @@ pub fn entity_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 /// The `node` annotation is used to indicate that a field is a child and is `Node`, `Option<Node>`, or `Vec<Node>`.<br>
 /// There can only be one field marked with `node` in a struct.
 /// ```ignore
-/// #[derive(MapElement)]
-/// #[name = "box"]
-/// pub struct Box {
+/// #[derive(Debug, Clone, PartialEq, Trigger)]
+/// #[name = "MyMod/FlagTrigger"]
+/// pub struct FlagTrigger {
 ///     #[node]
-///     pos: Node
+///     target: Node
+/// }
+/// ```
+///
+/// The field is edited through the `NodeList` methods of `MapTrigger`, and sets `MIN_NODES` and `MAX_NODES` from its type:
+/// a `Node` must be there, an `Option<Node>` can have up to one node, and a `Vec<Node>` can have any number.<br>
+/// A `Vec<Node>` can limit the number of nodes with `min` and `max`.
+/// ```ignore
+/// #[derive(Debug, Clone, PartialEq, Trigger)]
+/// #[name = "MyMod/FlagTrigger"]
+/// pub struct FlagTrigger {
+///     #[node(min = 1, max = 4)]
+///     path: Vec<Node>
+/// }
+/// ```
+///
+/// #### extra
+/// The `extra` annotation is used on an `ExtraData` field to keep any attributes and children that
+/// don't have a field of their own, so they are written back out when encoding.<br>
+/// There can only be one field marked with `extra` in a struct.
+/// ```ignore
+/// #[derive(Debug, Clone, PartialEq, Trigger)]
+/// #[name = "MyMod/FlagTrigger"]
+/// pub struct FlagTrigger {
+///     #[name = "flag"]
+///     flag: String,
+///     #[extra]
+///     extra: ExtraData,
 /// }
 /// ```
+///
+/// #### Reflection
+/// Every `name` field is listed by `Trigger::field_info` and can be read and written by its attribute name,
+/// the same as with `MapElement`.
 pub fn trigger_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
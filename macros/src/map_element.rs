@@ -2,6 +2,8 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Data, DeriveInput, Error, Expr, Meta, Type, spanned::Spanned};
 
+use crate::reflect::{ReflectField, reflect_methods};
+
 enum FieldType {
     Normal(Expr, bool),
     Optional(Expr, bool),
@@ -10,7 +12,9 @@ enum FieldType {
         is_optional: bool,
         is_dyn: bool,
         is_entity: bool,
+        is_styleground: bool,
     },
+    Extra,
 }
 
 pub(super) fn map_element_derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -50,6 +54,7 @@ pub(super) fn map_element_derive(input: DeriveInput) -> Result<TokenStream, Erro
     let mut fields = Vec::new();
     let mut found_child = false;
     let mut found_dyn_child = false;
+    let mut found_extra = false;
 
     for field in &struct_data.fields {
         let mut found_rle = false;
@@ -102,6 +107,7 @@ pub(super) fn map_element_derive(input: DeriveInput) -> Result<TokenStream, Erro
                             is_optional,
                             is_dyn: false,
                             is_entity: false,
+                            is_styleground: false,
                         }));
                     } else if path.is_ident("dyn_child") {
                         if found_child {
@@ -127,6 +133,7 @@ pub(super) fn map_element_derive(input: DeriveInput) -> Result<TokenStream, Erro
                             is_optional: false,
                             is_dyn: true,
                             is_entity: false,
+                            is_styleground: false,
                         }));
                     } else if path.is_ident("dyn_entities") {
                         if found_child {
@@ -152,7 +159,46 @@ pub(super) fn map_element_derive(input: DeriveInput) -> Result<TokenStream, Erro
                             is_optional: false,
                             is_dyn: true,
                             is_entity: true,
+                            is_styleground: false,
+                        }));
+                    } else if path.is_ident("dyn_stylegrounds") {
+                        if found_child {
+                            return Err(Error::new(
+                                path.span(),
+                                "dyn_stylegrounds field must be the only child field",
+                            ));
+                        }
+
+                        if found_rle {
+                            return Err(Error::new(
+                                path.span(),
+                                "Can't have both rle and dyn_stylegrounds on a field",
+                            ));
+                        }
+
+                        found_attr = true;
+                        found_child = true;
+                        found_dyn_child = true;
+
+                        fields.push((field.ident.clone().unwrap(), FieldType::Child {
+                            is_vec: true,
+                            is_optional: false,
+                            is_dyn: true,
+                            is_entity: false,
+                            is_styleground: true,
                         }));
+                    } else if path.is_ident("extra") {
+                        if found_extra {
+                            return Err(Error::new(
+                                path.span(),
+                                "There can only be one extra field",
+                            ));
+                        }
+
+                        found_attr = true;
+                        found_extra = true;
+
+                        fields.push((field.ident.clone().unwrap(), FieldType::Extra));
                     },
                 Meta::NameValue(name_value) =>
                     if name_value.path.is_ident("name") {
@@ -179,13 +225,18 @@ pub(super) fn map_element_derive(input: DeriveInput) -> Result<TokenStream, Erro
         if !found_attr {
             return Err(Error::new(
                 field.span(),
-                "Field in a MapElement is missing a name, child, or dyn_child attribute",
+                "Field in a MapElement is missing a name, child, dyn_child, dyn_entities, \
+                 dyn_stylegrounds, or extra attribute",
             ));
         }
     }
 
     let celeste_rs = super::celeste_rs();
 
+    // The extra field has to be parsed last so it only gets what the other fields didn't read,
+    // and encoded last so its children can be put back between the others
+    fields.sort_by_key(|(_, field_type)| matches!(field_type, FieldType::Extra));
+
     let parsers = fields.iter().map(|(name, field_type)| match field_type {
         FieldType::Normal(expr, _) => quote! {#name: parser.get_attribute(#expr)?,},
         FieldType::Optional(expr, _) => quote! {#name: parser.get_optional_attribute(#expr)?,},
@@ -194,31 +245,44 @@ pub(super) fn map_element_derive(input: DeriveInput) -> Result<TokenStream, Erro
             is_optional: true,
             is_dyn: _,
             is_entity: _,
+            is_styleground: _,
         } => quote! {#name: parser.parse_optional_element()?,},
         FieldType::Child {
             is_vec: false,
             is_optional: false,
             is_dyn: _,
             is_entity: _,
+            is_styleground: _,
         } => quote! {#name: parser.parse_element()?,},
         FieldType::Child {
             is_vec: true,
             is_optional: _,
             is_dyn: false,
             is_entity: _,
+            is_styleground: _,
         } => quote! {#name: parser.parse_all_elements()?, },
         FieldType::Child {
             is_vec: true,
             is_optional: _,
             is_dyn: true,
             is_entity: false,
+            is_styleground: false,
         } => quote! {#name: parser.parse_any_element()?, },
+        FieldType::Child {
+            is_vec: true,
+            is_optional: _,
+            is_dyn: true,
+            is_entity: false,
+            is_styleground: true,
+        } => quote! {#name: parser.parse_any_styleground()?, },
         FieldType::Child {
             is_vec: true,
             is_optional: _,
             is_dyn: true,
             is_entity: true,
+            is_styleground: _,
         } => quote! {#name: parser.parse_any_entity()?, },
+        FieldType::Extra => quote! {#name: parser.extra(),},
     });
 
     let encoders = fields.iter().map(|(name, field_type)| match field_type {
@@ -230,8 +294,57 @@ pub(super) fn map_element_derive(input: DeriveInput) -> Result<TokenStream, Erro
         FieldType::Child{is_vec: false, is_optional: true, .. } => quote! {if let Some(v) = &self.#name {encoder.child(v);}},
         FieldType::Child{is_vec: true, is_dyn: true, ..} => quote! {for e in &self.#name {encoder.dyn_child(e.as_ref())}},
         FieldType::Child{is_vec: true, ..} => quote! {encoder.children(&self.#name)},
+        FieldType::Extra => quote! {encoder.extra(&self.#name)},
     });
 
+    let visitor = quote! {#celeste_rs::maps::visitor};
+
+    let children = fields.iter().filter_map(|(name, field_type)| match field_type {
+        FieldType::Child{is_vec: false, is_optional: false, .. } => Some(quote! {children.push(#visitor::ChildElement::Child(&self.#name))}),
+        FieldType::Child{is_vec: false, is_optional: true, .. } => Some(quote! {if let Some(v) = &self.#name {children.push(#visitor::ChildElement::Child(v));}}),
+        FieldType::Child{is_vec: true, is_dyn: false, ..} => Some(quote! {children.extend(self.#name.iter().map(|e| #visitor::ChildElement::Child(e)))}),
+        FieldType::Child{is_vec: true, is_dyn: true, is_entity: false, ..} => Some(quote! {children.extend(self.#name.iter().map(|e| #visitor::ChildElement::DynChild(e.as_ref())))}),
+        FieldType::Child{is_vec: true, is_dyn: true, is_entity: true, ..} => Some(quote! {children.extend(self.#name.iter().map(|e| #visitor::ChildElement::Entity(e.as_ref())))}),
+        FieldType::Extra => Some(quote! {children.extend(self.#name.children.iter().map(|e| #visitor::ChildElement::Child(e)))}),
+        _ => None,
+    });
+
+    let children_mut = fields.iter().filter_map(|(name, field_type)| match field_type {
+        FieldType::Child{is_vec: false, is_optional: false, .. } => Some(quote! {children.push(#visitor::ChildElementMut::Child(&mut self.#name))}),
+        FieldType::Child{is_vec: false, is_optional: true, .. } => Some(quote! {if let Some(v) = &mut self.#name {children.push(#visitor::ChildElementMut::Child(v));}}),
+        FieldType::Child{is_vec: true, is_dyn: false, ..} => Some(quote! {children.extend(self.#name.iter_mut().map(|e| #visitor::ChildElementMut::Child(e)))}),
+        FieldType::Child{is_vec: true, is_dyn: true, is_entity: false, ..} => Some(quote! {children.extend(self.#name.iter_mut().map(|e| #visitor::ChildElementMut::DynChild(e.as_mut())))}),
+        FieldType::Child{is_vec: true, is_dyn: true, is_entity: true, ..} => Some(quote! {children.extend(self.#name.iter_mut().map(|e| #visitor::ChildElementMut::Entity(e.as_mut())))}),
+        FieldType::Extra => Some(quote! {children.extend(self.#name.children.iter_mut().map(|e| #visitor::ChildElementMut::Child(e)))}),
+        _ => None,
+    });
+
+    let reflect_fields = fields
+        .iter()
+        .filter_map(|(name, field_type)| match field_type {
+            FieldType::Normal(expr, rle) => Some((name, expr, false, *rle)),
+            FieldType::Optional(expr, rle) => Some((name, expr, true, *rle)),
+            _ => None,
+        })
+        .map(|(name, expr, optional, rle)| ReflectField {
+            ident: name.clone(),
+            attribute: expr.clone(),
+            ty: struct_data
+                .fields
+                .iter()
+                .find(|f| f.ident.as_ref() == Some(name))
+                .unwrap()
+                .ty
+                .clone(),
+            optional,
+            rle,
+        })
+        .collect::<Vec<_>>();
+    let extra = fields
+        .iter()
+        .find_map(|(name, field_type)| matches!(field_type, FieldType::Extra).then_some(name));
+    let reflect = reflect_methods(&celeste_rs, &reflect_fields, extra);
+
     Ok(quote! {
         impl MapElement for #struct_ident {
             const NAME: &'static str = #struct_name;
@@ -245,6 +358,22 @@ pub(super) fn map_element_derive(input: DeriveInput) -> Result<TokenStream, Erro
             fn to_raw(&self, encoder: &mut #celeste_rs::maps::encoder::MapEncoder) {
                 #(#encoders;)*
             }
+
+            #reflect
+
+            #[allow(unused_mut)]
+            fn child_elements(&self) -> Vec<#visitor::ChildElement<'_>> {
+                let mut children = Vec::new();
+                #(#children;)*
+                children
+            }
+
+            #[allow(unused_mut)]
+            fn child_elements_mut(&mut self) -> Vec<#visitor::ChildElementMut<'_>> {
+                let mut children = Vec::new();
+                #(#children_mut;)*
+                children
+            }
         }
     })
 }
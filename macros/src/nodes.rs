@@ -0,0 +1,119 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Error, Ident, LitInt, Meta};
+
+/// The node count limits set with `#[node(min = .., max = ..)]`
+#[derive(Default)]
+pub(super) struct NodeBounds {
+    pub min: Option<LitInt>,
+    pub max: Option<LitInt>,
+}
+
+impl NodeBounds {
+    /// Parses the bounds from a `node` attribute, which are empty for a plain `#[node]`
+    pub fn parse(attr: &Attribute) -> Result<Self, Error> {
+        let mut bounds = NodeBounds::default();
+
+        if let Meta::List(_) = &attr.meta {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("min") {
+                    bounds.min = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("max") {
+                    bounds.max = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("Expected `min` or `max`"))
+                }
+            })?;
+        }
+
+        Ok(bounds)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+}
+
+/// Generates `MIN_NODES`, `MAX_NODES`, `nodes`, `nodes_mut` and `set_nodes`, which are the same on `EntityData` and `Trigger`
+///
+/// `Node` fields always have one node and `Option<Node>` fields up to one, so only `Vec<Node>` fields can set bounds.
+pub(super) fn node_methods(
+    celeste_rs: &TokenStream,
+    ident: &Ident,
+    is_option: bool,
+    is_vec: bool,
+    bounds: &NodeBounds,
+) -> Result<TokenStream, Error> {
+    let elements = quote! {#celeste_rs::maps::elements};
+
+    if !is_vec && !bounds.is_empty() {
+        let lit = bounds.min.as_ref().or(bounds.max.as_ref()).unwrap();
+        return Err(Error::new(
+            lit.span(),
+            "Node count bounds can only be set on a Vec<Node> field",
+        ));
+    }
+
+    let (min, max, nodes, nodes_mut, set) = match (is_option, is_vec) {
+        (false, false) => (
+            quote! {1},
+            quote! {Some(1)},
+            quote! {::std::slice::from_ref(&self.#ident)},
+            quote! {::std::slice::from_mut(&mut self.#ident)},
+            quote! {
+                if let Some(node) = nodes.into_iter().next() {
+                    self.#ident = node;
+                }
+            },
+        ),
+        (true, false) => (
+            quote! {0},
+            quote! {Some(1)},
+            quote! {self.#ident.as_slice()},
+            quote! {self.#ident.as_mut_slice()},
+            quote! {self.#ident = nodes.into_iter().next();},
+        ),
+        (_, true) => {
+            let min = bounds
+                .min
+                .as_ref()
+                .map(|min| quote! {#min})
+                .unwrap_or(quote! {0});
+            let max = bounds
+                .max
+                .as_ref()
+                .map(|max| quote! {Some(#max)})
+                .unwrap_or(quote! {None});
+
+            (
+                min,
+                max,
+                quote! {&self.#ident},
+                quote! {&mut self.#ident},
+                quote! {self.#ident = nodes;},
+            )
+        }
+    };
+
+    Ok(quote! {
+        const MIN_NODES: usize = #min;
+        const MAX_NODES: Option<usize> = #max;
+
+        fn nodes(&self) -> &[#elements::Node] {
+            #nodes
+        }
+
+        fn nodes_mut(&mut self) -> &mut [#elements::Node] {
+            #nodes_mut
+        }
+
+        fn set_nodes(&mut self, nodes: Vec<#elements::Node>) -> Result<(), #elements::NodeError> {
+            #elements::check_node_count(nodes.len(), Self::MIN_NODES, Self::MAX_NODES)?;
+            #set
+
+            Ok(())
+        }
+    })
+}
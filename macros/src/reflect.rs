@@ -0,0 +1,141 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Expr, GenericArgument, Ident, PathArguments, Type};
+
+/// An attribute field to generate reflection methods for
+pub(super) struct ReflectField {
+    pub ident: Ident,
+    pub attribute: Expr,
+    pub ty: Type,
+    pub optional: bool,
+    pub rle: bool,
+}
+
+/// Generates `field_info`, `get_field` and `set_field`, which are the same on `MapElement`, `EntityData` and `Trigger`
+///
+/// Nothing is generated if there are no attribute fields or extra field, so the trait defaults are used.
+pub(super) fn reflect_methods(
+    celeste_rs: &TokenStream,
+    fields: &[ReflectField],
+    extra: Option<&Ident>,
+) -> TokenStream {
+    if fields.is_empty() && extra.is_none() {
+        return TokenStream::new();
+    }
+
+    let reflect = quote! {#celeste_rs::maps::reflect};
+    let encoded_var = quote! {#celeste_rs::maps::var_types::EncodedVar};
+
+    let infos = fields.iter().map(|field| {
+        let name = field.ident.to_string();
+        let attribute = &field.attribute;
+        let kind = Ident::new(field_kind(&field.ty, field.rle), Span::call_site());
+        let optional = field.optional;
+
+        quote! {#reflect::FieldInfo::new(#name, #attribute, #reflect::FieldKind::#kind, #optional)}
+    });
+
+    let getters = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let attribute = &field.attribute;
+
+        match (field.optional, field.rle) {
+            (false, false) => quote! {#attribute => Some(#encoded_var::from(self.#ident.clone())),},
+            (false, true) => quote! {#attribute => Some(#encoded_var::new_rle_str(&self.#ident)),},
+            (true, false) => quote! {#attribute => self.#ident.clone().map(#encoded_var::from),},
+            (true, true) =>
+                quote! {#attribute => self.#ident.as_ref().map(#encoded_var::new_rle_str),},
+        }
+    });
+
+    let setters = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let attribute = &field.attribute;
+
+        if field.optional {
+            quote! {#attribute => self.#ident = #reflect::optional_field(name, value)?,}
+        } else {
+            quote! {#attribute => self.#ident = #reflect::required_field(name, value)?,}
+        }
+    });
+
+    let (get_fallback, set_fallback) = match extra {
+        Some(extra) => (quote! {self.#extra.get_attribute(name).cloned()}, quote! {
+            match value {
+                Some(value) => self.#extra.set_attribute(name, value),
+                None => {
+                    self.#extra.remove_attribute(name);
+                }
+            }
+        }),
+        None => (
+            quote! {None},
+            quote! {return Err(#reflect::FieldError::UnknownField(name.to_owned()))},
+        ),
+    };
+
+    let (get_body, set_body) = if fields.is_empty() {
+        (get_fallback, quote! {#set_fallback;})
+    } else {
+        (
+            quote! {
+                match name {
+                    #(#getters)*
+                    _ => #get_fallback,
+                }
+            },
+            quote! {
+                match name {
+                    #(#setters)*
+                    _ => #set_fallback,
+                }
+            },
+        )
+    };
+
+    quote! {
+        fn field_info(&self) -> Vec<#reflect::FieldInfo> {
+            vec![#(#infos),*]
+        }
+
+        fn get_field(&self, name: &str) -> Option<#encoded_var> {
+            #get_body
+        }
+
+        fn set_field(&mut self, name: &str, value: Option<#encoded_var>) -> Result<(), #reflect::FieldError> {
+            #set_body
+
+            Ok(())
+        }
+    }
+}
+
+/// Gets the name of the `FieldKind` variant for a field's type
+fn field_kind(ty: &Type, rle: bool) -> &'static str {
+    if rle {
+        return "String";
+    }
+
+    let Type::Path(path) = ty else {
+        return "Other";
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return "Other";
+    };
+
+    if segment.ident == "Option"
+        && let PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(GenericArgument::Type(inner)) = args.args.first()
+    {
+        return field_kind(inner, rle);
+    }
+
+    match segment.ident.to_string().as_str() {
+        "bool" => "Bool",
+        "Integer" | "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" => "Integer",
+        "Float" | "f32" | "f64" => "Float",
+        "String" | "ResolvableString" => "String",
+        "Character" => "Character",
+        _ => "Other",
+    }
+}
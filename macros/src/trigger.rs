@@ -2,10 +2,16 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Data, DeriveInput, Error, Expr, Meta, Type, spanned::Spanned};
 
+use crate::{
+    nodes::{NodeBounds, node_methods},
+    reflect::{ReflectField, reflect_methods},
+};
+
 enum FieldType {
     Normal(Expr),
     Optional(Expr),
     Node(bool, bool),
+    Extra,
 }
 
 pub(super) fn trigger_derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -45,39 +51,54 @@ pub(super) fn trigger_derive(input: DeriveInput) -> Result<TokenStream, Error> {
     let mut fields = Vec::new();
 
     let mut found_node = false;
+    let mut found_extra = false;
+    let mut node_bounds = NodeBounds::default();
     for field in &struct_data.fields {
         let mut found_attr = false;
         for attr in &field.attrs {
             match &attr.meta {
+                Meta::Path(_) | Meta::List(_) if attr.path().is_ident("node") => {
+                    if found_node {
+                        return Err(Error::new_spanned(
+                            field,
+                            "A Trigger implementor can't have multiple fields that take a node",
+                        ));
+                    }
+
+                    found_attr = true;
+                    found_node = true;
+                    node_bounds = NodeBounds::parse(attr)?;
+
+                    let is_vec = if let Type::Path(p) = &field.ty {
+                        p.path.segments.first().is_some_and(|p| p.ident == "Vec")
+                    } else {
+                        false
+                    };
+
+                    let is_option = if let Type::Path(p) = &field.ty {
+                        p.path.segments.first().is_some_and(|p| p.ident == "Option")
+                    } else {
+                        false
+                    };
+
+                    fields.push((
+                        field.ident.clone().unwrap(),
+                        FieldType::Node(is_option, is_vec),
+                    ));
+                }
                 Meta::Path(path) =>
-                    if path.is_ident("node") {
-                        if found_node {
+                    if path.is_ident("extra") {
+                        if found_extra {
                             return Err(Error::new_spanned(
                                 field,
-                                "A Trigger implementor can't have multiple fields that take a node",
+                                "There can only be one extra field",
                             ));
                         }
 
                         found_attr = true;
-                        found_node = true;
-
+                        found_extra = true;
 
-                        let is_vec = if let Type::Path(p) = &field.ty {
-                            p.path.segments.first().is_some_and(|p| p.ident == "Vec")
-                        } else {
-                            false
-                        };
-
-                        let is_option = if let Type::Path(p) = &field.ty {
-                            p.path.segments.first().is_some_and(|p| p.ident == "Option")
-                        } else {
-                            false
-                        };
-
-                        fields.push((
-                            field.ident.clone().unwrap(),
-                            FieldType::Node(is_option, is_vec),
-                        ));
+                        fields.push((field.ident.clone().unwrap(), FieldType::Extra));
                     },
                 Meta::NameValue(name_value) =>
                     if name_value.path.is_ident("name") {
@@ -113,17 +134,22 @@ pub(super) fn trigger_derive(input: DeriveInput) -> Result<TokenStream, Error> {
         if !found_attr {
             return Err(Error::new(
                 field.span(),
-                "Field in a Entity is missing a name or child attribute",
+                "Field in a Entity is missing a name, node, or extra attribute",
             ));
         }
     }
 
+    // The extra field has to be parsed last so it only gets what the other fields didn't read,
+    // and encoded last so its children can be put back between the others
+    fields.sort_by_key(|(_, field_type)| matches!(field_type, FieldType::Extra));
+
     let parsers = fields.iter().map(|(name, field_type)| match field_type {
         FieldType::Normal(expr) => quote! {#name: parser.get_attribute(#expr)?,},
         FieldType::Optional(expr) => quote! {#name: parser.get_optional_attribute(#expr)?,},
         FieldType::Node(true, false) => quote! {#name: parser.parse_element().ok(),},
         FieldType::Node(false, false) => quote! {#name: parser.parse_element()?,},
         FieldType::Node(_, true) => quote! {#name: parser.parse_all_elements()?, },
+        FieldType::Extra => quote! {#name: parser.extra(),},
     });
 
     let encoders = fields.iter().map(|(name, field_type)| match field_type {
@@ -132,10 +158,48 @@ pub(super) fn trigger_derive(input: DeriveInput) -> Result<TokenStream, Error> {
         FieldType::Node(true, false) => quote! {if let Some(v) = &self.#name { encoder.child(v) }},
         FieldType::Node(false, false) => quote! {encoder.child(&self.#name)},
         FieldType::Node(_, true) => quote! {encoder.children(&self.#name)},
+        FieldType::Extra => quote! {encoder.extra(&self.#name)},
     });
 
     let celeste_rs = super::celeste_rs();
 
+    let reflect_fields = fields
+        .iter()
+        .filter_map(|(name, field_type)| match field_type {
+            FieldType::Normal(expr) => Some((name, expr, false, false)),
+            FieldType::Optional(expr) => Some((name, expr, true, false)),
+            _ => None,
+        })
+        .map(|(name, expr, optional, rle)| ReflectField {
+            ident: name.clone(),
+            attribute: expr.clone(),
+            ty: struct_data
+                .fields
+                .iter()
+                .find(|f| f.ident.as_ref() == Some(name))
+                .unwrap()
+                .ty
+                .clone(),
+            optional,
+            rle,
+        })
+        .collect::<Vec<_>>();
+    let extra = fields
+        .iter()
+        .find_map(|(name, field_type)| matches!(field_type, FieldType::Extra).then_some(name));
+    let reflect = reflect_methods(&celeste_rs, &reflect_fields, extra);
+
+    let nodes = match fields
+        .iter()
+        .find_map(|(name, field_type)| match field_type {
+            FieldType::Node(is_option, is_vec) => Some((name, *is_option, *is_vec)),
+            _ => None,
+        }) {
+        Some((name, is_option, is_vec)) =>
+            node_methods(&celeste_rs, name, is_option, is_vec, &node_bounds)?,
+        None => TokenStream::new(),
+    };
+
     Ok(quote! {
         impl #celeste_rs::maps::elements::triggers::Trigger for #struct_ident {
             const NAME: &'static str = #struct_name;
@@ -149,6 +213,10 @@ pub(super) fn trigger_derive(input: DeriveInput) -> Result<TokenStream, Error> {
             fn to_raw(&self, encoder: &mut #celeste_rs::maps::encoder::MapEncoder) {
                 #(#encoders;)*
             }
+
+            #reflect
+
+            #nodes
         }
     })
 }
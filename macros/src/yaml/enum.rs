@@ -17,12 +17,12 @@ pub(super) fn yaml_derive_enum(
         }
     }
 
-    variants.sort_by(|a, b| a.fields.len().cmp(&b.fields.len()));
+    variants.sort_by_key(|a| a.fields.len());
 
     let mut parsers = Vec::with_capacity(variants.len() + 1);
     let mut writer_branches = Vec::with_capacity(variants.len() + unit_variants.len());
 
-    let unit_branches = unit_variants
+    let unit_names = unit_variants
         .iter()
         .map(|v| {
             let mut name_str = v.ident.to_string();
@@ -41,6 +41,14 @@ pub(super) fn yaml_derive_enum(
                 }
             }
 
+            name_str
+        })
+        .collect::<Vec<_>>();
+
+    let unit_branches = unit_variants
+        .iter()
+        .zip(&unit_names)
+        .map(|(v, name_str)| {
             let name = &v.ident;
             quote! {
                 #name_str => #enum_name::#name
@@ -59,9 +67,9 @@ pub(super) fn yaml_derive_enum(
         }
     });
 
-    for variant in unit_variants {
+    // Use the same names we parse from so renamed variants round-trip
+    for (variant, name_str) in unit_variants.iter().zip(&unit_names) {
         let name = &variant.ident;
-        let name_str = &variant.ident.to_string();
         writer_branches.push(quote! {
             #enum_name::#name => Yaml::string(#name_str.to_owned())
         });
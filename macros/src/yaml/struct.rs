@@ -51,20 +51,18 @@ pub(super) fn yaml_derive_struct(
                             "name attribute needs string literal value",
                         ));
                     }
-                } else if name_value.path.is_ident("parse_fn") {
-                    if let Expr::Lit(ExprLit {
+                } else if name_value.path.is_ident("parse_fn")
+                    && let Expr::Lit(ExprLit {
                         lit: Lit::Str(str), ..
                     }) = &name_value.value
-                    {
-                        parsing_fn = Some(Ident::new(&str.value(), str.span()));
-                    }
-                } else if name_value.path.is_ident("write_fn") {
-                    if let Expr::Lit(ExprLit {
+                {
+                    parsing_fn = Some(Ident::new(&str.value(), str.span()));
+                } else if name_value.path.is_ident("write_fn")
+                    && let Expr::Lit(ExprLit {
                         lit: Lit::Str(str), ..
                     }) = &name_value.value
-                    {
-                        writing_fn = Some(Ident::new(&str.value(), str.span()));
-                    }
+                {
+                    writing_fn = Some(Ident::new(&str.value(), str.span()));
                 }
             }
         }
@@ -249,8 +247,26 @@ fn gen_field_writer(field: &Field) -> Result<TokenStream, Error> {
         });
     }
 
-    let writer = gen_type_writer(&field.yaml_name, &field.rust_name, &field.rust_type)?;
     let ident = &field.rust_name;
+
+    // Fields that are `None` are left out of the output entirely instead of being written as null
+    // This keeps written files close to what people would write by hand
+    if let Some(ty) = get_option_ty(&field.rust_type) {
+        let writer = gen_type_writer(&field.yaml_name, &field.rust_name, ty)?;
+        return Ok(quote! {
+            if let Some(val) = &self.#ident {
+                output.insert(
+                    Yaml::string(#name.to_owned()),
+                    {
+                        let output: Result<Yaml, YamlWriteError> = {#writer};
+                        output?
+                    }
+                );
+            }
+        });
+    }
+
+    let writer = gen_type_writer(&field.yaml_name, &field.rust_name, &field.rust_type)?;
     Ok(quote! {
         output.insert(
             Yaml::string(#name.to_owned()),